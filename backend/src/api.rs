@@ -3,7 +3,43 @@ use common::http_utils::request_get;
 use common::ticket::{*};
 use common::gen_cp::CTokenGenerator;
 use serde_json;
-use common::login::QrCodeLoginStatus;
+use common::login::{LoginCredential, QrCodeLoginStatus};
+use common::link_parser::extract_project_id;
+use common::project_search::{parse_search_response, ProjectSearchItem};
+use common::time_format::format_beijing;
+use common::address::{parse_address_list, AddressInput, DeliveryAddress};
+use common::cookie_refresh::{correspond_path, parse_refresh_csrf, set_cookie_pairs, RefreshedCookie};
+use common::schema_drift::parse_tolerant;
+use common::grab_report::ERRNO_NO_CODE;
+
+// 项目详情中抢票/下单依赖的字段，出现缺失或类型变化时不能继续
+const PROJECT_CRITICAL_FIELDS: [&str; 14] = [
+    "data.id",
+    "data.sale_begin",
+    "data.sale_end",
+    "data.id_bind",
+    "data.screen_list",
+    "data.screen_list[].id",
+    "data.screen_list[].start_time",
+    "data.screen_list[].delivery_type",
+    "data.screen_list[].ticket_list",
+    "data.screen_list[].ticket_list[].id",
+    "data.screen_list[].ticket_list[].project_id",
+    "data.screen_list[].ticket_list[].price",
+    "data.screen_list[].ticket_list[].saleStart",
+    "data.screen_list[].ticket_list[].saleEnd",
+];
+
+// 购票人列表中下单依赖的字段
+const BUYER_CRITICAL_FIELDS: [&str; 6] = [
+    "data.list",
+    "data.list[].id",
+    "data.list[].personal_id",
+    "data.list[].name",
+    "data.list[].tel",
+    "data.list[].id_type",
+];
+use common::errno::describe;
 use reqwest::Client;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -19,12 +55,12 @@ pub async fn get_countdown(cookie_manager: Arc<CookieManager>, info: Option<Tick
         Some(info) => info.sale_begin ,
         None => return Err("获取开始时间失败".to_string()),
     };
-    log::debug!("获取开始时间(秒级)：{}", sale_begin_sec);
+    log::debug!("获取开始时间(秒级)：{}（北京时间 {}）", sale_begin_sec, format_beijing(sale_begin_sec));
     
     // 获取网络时间 (秒级)
     let url = "https://api.bilibili.com/x/click-interface/click/now";
     let response = cookie_manager.get(url).await;
-    let now_sec = match response.send().await {
+    let now_sec = match cookie_manager.execute(response).await {
         Ok(data) => {
             let text = data.text().await.unwrap_or_default();
             log::debug!("API原始响应：{}", text);
@@ -70,7 +106,7 @@ pub async fn get_countdown(cookie_manager: Arc<CookieManager>, info: Option<Tick
 
 pub async fn get_buyer_info(cookie_manager: Arc<CookieManager>) -> Result<BuyerInfoResponse,String>{
     let req = cookie_manager.get("https://show.bilibili.com/api/ticket/buyer/list").await;
-    let response = req.send().await;
+    let response = cookie_manager.execute(req).await;
     match response {
         Ok(resp)=>{
             if resp.status().is_success(){
@@ -80,8 +116,11 @@ pub async fn get_buyer_info(cookie_manager: Arc<CookieManager>) -> Result<BuyerI
                 }){
                     Ok(text) => {
                         log::debug!("获取购票人信息：{}",text);
-                        match serde_json::from_str::<BuyerInfoResponse>(&text){
-                            Ok(buyer_info) => {
+                        match parse_tolerant::<BuyerInfoResponse>("购票人列表", &text){
+                            Ok((buyer_info, report)) => {
+                                if buyer_info.errno == 0 {
+                                    report.ensure_fields(&BUYER_CRITICAL_FIELDS)?;
+                                }
                                 return Ok(buyer_info);
                             }
                             Err(e) => {
@@ -110,9 +149,51 @@ pub async fn get_buyer_info(cookie_manager: Arc<CookieManager>) -> Result<BuyerI
     }
 }
 
+pub async fn get_address_list(cookie_manager: Arc<CookieManager>) -> Result<Vec<DeliveryAddress>, String> {
+    let request = cookie_manager.get("https://show.bilibili.com/api/ticket/addr/list").await;
+    let response = cookie_manager.execute(request)
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("请求响应失败: {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| format!("获取响应文本失败: {}", e))?;
+    log::debug!("获取收货地址：{}", text);
+    let value: Value = serde_json::from_str(&text)
+        .map_err(|e| format!("收货地址json解析失败：{}", e))?;
+    parse_address_list(&value)
+}
+
+//新增或修改收货地址（表单带id时为修改）
+pub async fn save_address(cookie_manager: Arc<CookieManager>, input: &AddressInput) -> Result<(), String> {
+    let url = if input.id.is_some() {
+        "https://show.bilibili.com/api/ticket/addr/update"
+    } else {
+        "https://show.bilibili.com/api/ticket/addr/add"
+    };
+    let request = cookie_manager.post(url).await
+        .json(&input.to_form());
+    let response = cookie_manager.execute(request)
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("请求响应失败: {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| format!("获取响应文本失败: {}", e))?;
+    log::debug!("保存收货地址：{}", text);
+    let value: Value = serde_json::from_str(&text)
+        .map_err(|e| format!("解析响应失败: {}", e))?;
+    let errno = value["errno"].as_i64().or_else(|| value["code"].as_i64()).unwrap_or(-1);
+    if errno != 0 {
+        let msg = value["msg"].as_str().or_else(|| value["message"].as_str()).unwrap_or("未知错误");
+        return Err(format!("保存收货地址失败({}): {}", errno, msg));
+    }
+    Ok(())
+}
+
 pub async fn get_project(cookie_manager: Arc<CookieManager>, project_id : &str) -> Result<InfoResponse,String>{
     let req = cookie_manager.get(format!("https://show.bilibili.com/api/ticket/project/getV2?id={}",project_id).as_str()).await;
-    let response = req.send().await;
+    let response = cookie_manager.execute(req).await;
     match response {
         Ok(resp)=>{
             if resp.status().is_success(){
@@ -123,8 +204,11 @@ pub async fn get_project(cookie_manager: Arc<CookieManager>, project_id : &str)
                     Ok(text) => {
                         log::debug!("获取项目详情：{}",text);
                         // 尝试常规解析
-                        match serde_json::from_str::<InfoResponse>(&text){
-                            Ok(ticket_info) => {
+                        match parse_tolerant::<InfoResponse>("项目详情", &text){
+                            Ok((ticket_info, report)) => {
+                                if ticket_info.errno == 0 && ticket_info.code == 0 {
+                                    report.ensure_fields(&PROJECT_CRITICAL_FIELDS)?;
+                                }
                                 return Ok(ticket_info);
                             }
                             Err(e) => {
@@ -150,6 +234,117 @@ pub async fn get_project(cookie_manager: Arc<CookieManager>, project_id : &str)
     }
 }
 
+//检查web登录态是否需要刷新，返回 (是否需要刷新, 服务器毫秒时间戳)
+pub async fn check_cookie_refresh(cookie_manager: Arc<CookieManager>, csrf: &str) -> Result<(bool, i64), String> {
+    let url = format!("https://passport.bilibili.com/x/passport-login/web/cookie/info?csrf={}", csrf);
+    let request = cookie_manager.get(&url).await;
+    let response = cookie_manager.execute(request)
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    let value: Value = response.json().await.map_err(|e| format!("解析响应失败: {}", e))?;
+    log::debug!("检查登录态：{}", value);
+    match value["code"].as_i64() {
+        Some(0) => Ok((
+            value["data"]["refresh"].as_bool().unwrap_or(false),
+            value["data"]["timestamp"].as_i64().unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+        )),
+        Some(-101) => Err("登录已失效，请重新登录".to_string()),
+        code => Err(format!("检查登录态失败({:?}): {}", code, value["message"].as_str().unwrap_or("未知错误"))),
+    }
+}
+
+//刷新web登录态：获取refresh_csrf -> 刷新cookie -> 确认刷新（使旧refresh_token失效）
+//新cookie会直接写入 cookie_manager
+pub async fn refresh_web_cookie(cookie_manager: Arc<CookieManager>, csrf: &str, refresh_token: &str, timestamp_ms: i64) -> Result<RefreshedCookie, String> {
+    let path = correspond_path(timestamp_ms)?;
+    let request = cookie_manager.get(&format!("https://www.bilibili.com/correspond/1/{}", path)).await;
+    let html = cookie_manager.execute(request)
+        .await
+        .map_err(|e| format!("获取refresh_csrf失败: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("获取refresh_csrf失败: {}", e))?;
+    let refresh_csrf = parse_refresh_csrf(&html).ok_or("获取refresh_csrf失败：页面中没有refresh_csrf")?;
+
+    let request = cookie_manager.post("https://passport.bilibili.com/x/passport-login/web/cookie/refresh").await
+        .form(&[
+            ("csrf", csrf),
+            ("refresh_csrf", refresh_csrf.as_str()),
+            ("source", "main_web"),
+            ("refresh_token", refresh_token),
+        ]);
+    let response = cookie_manager.execute(request)
+        .await
+        .map_err(|e| format!("刷新cookie请求失败: {}", e))?;
+    let new_cookies = set_cookie_pairs(response.headers());
+    let value: Value = response.json().await.map_err(|e| format!("解析响应失败: {}", e))?;
+    log::debug!("刷新cookie响应：{}", value);
+    if value["code"].as_i64() != Some(0) {
+        return Err(format!("刷新cookie失败({}): {}", value["code"], value["message"].as_str().unwrap_or("未知错误")));
+    }
+    let new_refresh_token = value["data"]["refresh_token"].as_str().unwrap_or("").to_string();
+    if new_cookies.is_empty() || new_refresh_token.is_empty() {
+        return Err("刷新cookie失败：响应中没有新的cookie".to_string());
+    }
+    cookie_manager.update_cookies(&new_cookies.join("; "));
+    let new_csrf = cookie_manager.get_cookie("bili_jct").unwrap_or_default();
+
+    // 确认刷新失败不影响新cookie使用，只记录日志
+    let request = cookie_manager.post("https://passport.bilibili.com/x/passport-login/web/confirm/refresh").await
+        .form(&[("csrf", new_csrf.as_str()), ("refresh_token", refresh_token)]);
+    let confirm = cookie_manager.execute(request)
+        .await;
+    match confirm {
+        Ok(resp) => match resp.json::<Value>().await {
+            Ok(value) if value["code"].as_i64() == Some(0) => log::debug!("确认刷新成功"),
+            Ok(value) => log::warn!("确认刷新失败: {}", value["message"].as_str().unwrap_or("未知错误")),
+            Err(e) => log::warn!("确认刷新响应解析失败: {}", e),
+        },
+        Err(e) => log::warn!("确认刷新请求失败: {}", e),
+    }
+
+    Ok(RefreshedCookie {
+        cookie: cookie_manager.get_all_cookies(),
+        csrf: new_csrf,
+        refresh_token: new_refresh_token,
+    })
+}
+
+//按关键词搜索演出/展览
+pub async fn search_project(client: &Client, keyword: &str, page: u32) -> Result<Vec<ProjectSearchItem>, String> {
+    let page = page.max(1).to_string();
+    let response = client
+        .get("https://show.bilibili.com/api/ticket/search/list")
+        .query(&[
+            ("version", "133"),
+            ("keyword", keyword),
+            ("pagesize", "16"),
+            ("page", page.as_str()),
+            ("platform", "web"),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("搜索请求失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("搜索请求响应失败: {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| format!("读取搜索结果失败: {}", e))?;
+    log::debug!("搜索结果：{}", text);
+    let json: Value = serde_json::from_str(&text).map_err(|e| format!("搜索结果json解析失败：{}", e))?;
+    parse_search_response(&json)
+}
+
+//跟随b23短链接跳转，从最终地址中取项目id
+pub async fn resolve_short_link(client: &Client, short_url: &str) -> Result<String, String> {
+    let response = client
+        .get(short_url)
+        .send()
+        .await
+        .map_err(|e| format!("短链接请求失败: {}", e))?;
+    let final_url = response.url().to_string();
+    log::debug!("短链接 {} 跳转到 {}", short_url, final_url);
+    extract_project_id(&final_url).map_err(|e| format!("短链接跳转地址无法识别（{}）: {}", final_url, e))
+}
 
 //轮询登录状态
 pub async fn poll_qrcode_login(qrcode_key: &str,user_agent: Option<&str>) ->QrCodeLoginStatus {
@@ -213,7 +408,8 @@ pub async fn poll_qrcode_login(qrcode_key: &str,user_agent: Option<&str>) ->QrCo
             
             
             if !all_cookies.is_empty() {
-                return QrCodeLoginStatus::Success(all_cookies.join("; "));
+                let refresh_token = json["data"]["refresh_token"].as_str().filter(|t| !t.is_empty()).map(str::to_string);
+                return QrCodeLoginStatus::Success(LoginCredential { cookie: all_cookies.join("; "), refresh_token });
             } else {
                 return QrCodeLoginStatus::Failed("无法获取Cookie信息".to_string());
             }
@@ -271,10 +467,10 @@ pub async fn get_ticket_token(cookie_manager:Arc<CookieManager>,
     };
     log::debug!("获取票token参数：{:?}", params);
     let url = format!("https://show.bilibili.com/api/ticket/order/prepare?project_id={}",project_id);
-    let response = cookie_manager
+    let request = cookie_manager
         .post(&url).await
-        .json(&params)
-        .send()
+        .json(&params);
+    let response = cookie_manager.execute(request)
         .await;
     match response {
         Ok(resp) => {
@@ -326,7 +522,7 @@ pub async fn get_ticket_token(cookie_manager:Arc<CookieManager>,
                                 return Err(token_risk_param);
                             }
                             _ => {
-                                log::error!("获取token失败，未知错误码：{}，错误信息：{}，请提issue修复此问题", code, msg);
+                                log::error!("获取token失败：{}", describe(code, msg));
                                 log::error!("{:?}", json);
                                 return Err(TokenRiskParam {
                                     code: code as i32,
@@ -402,28 +598,30 @@ pub async fn get_ticket_token(cookie_manager:Arc<CookieManager>,
 
 }
 
-pub async fn confirm_ticket_order(cookie_manager:Arc<CookieManager>,project_id : &str,token: &str) -> Result<ConfirmTicketResult, String> {
+//失败时返回(错误码, 说明)，错误码用于抢票报告统计；网络错误记为-1，响应无法解析记为412
+pub async fn confirm_ticket_order(cookie_manager:Arc<CookieManager>,project_id : &str,token: &str) -> Result<ConfirmTicketResult, (i64, String)> {
     let url = format!("https://show.bilibili.com/api/ticket/order/confirmInfo?token={}&voucher=&project_id={}&requestSource=neul-next",token,project_id);
-    let response = cookie_manager.get(&url)
-        .await
-        .send()
+    let request = cookie_manager.get(&url)
+        .await;
+    let response = cookie_manager.execute(request)
         .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(|e| (ERRNO_NO_CODE, format!("请求失败: {}", e)))?;
         
     if !response.status().is_success() {
-        return Err(format!("请求失败: {}", response.status()));
+        return Err((response.status().as_u16() as i64, format!("请求失败: {}", response.status())));
     }
     let text = response.text()
         .await
-        .map_err(|e| format!("获取响应文本失败: {}", e))?;
+        .map_err(|e| (ERRNO_NO_CODE, format!("获取响应文本失败: {}", e)))?;
     log::debug!("确认订单响应：{}", text);
     let json: serde_json::Value = serde_json::from_str(&text)
-        .map_err(|e| format!("解析响应文本失败: {}", e))?;
+        .map_err(|e| (412, format!("解析响应文本失败: {}", e)))?;
     if json["errno"]!=0 {
-        return Err(format!("确认订单失败: {}", json["msg"].as_str().unwrap_or("未知错误")));
+        let errno = json["errno"].as_i64().or_else(|| json["code"].as_i64()).unwrap_or(ERRNO_NO_CODE);
+        return Err((errno, format!("确认订单失败: {}", describe(errno, json["msg"].as_str().unwrap_or("")))));
     }
     let confirm_result = serde_json::from_value(json["data"].clone())
-        .map_err(|e| format!("解析确认订单结果失败: {}", e))?;
+        .map_err(|e| (412, format!("解析确认订单结果失败: {}", e)))?;
     Ok(confirm_result)
 }
 
@@ -559,9 +757,9 @@ pub async fn create_order(
     };
 
     log::debug!("抢票data ：{:?}", data);
-    let response = cookie_manager.post_with_headers(&url,input_risk_header).await
-        .json(&data)
-        .send()
+    let request = cookie_manager.post_with_headers(&url,input_risk_header).await
+        .json(&data);
+    let response = cookie_manager.execute(request)
         .await
         .map_err(|e| {
             log::error!("请求失败: {}", e);
@@ -610,9 +808,9 @@ pub async fn check_fake_ticket(cookie_manager: Arc<CookieManager>, project_id: &
         url = format!("{}&orderId={}",url, order_id);
     } 
     log::debug!("check_fake_ticket_url: {}", url);
-    let response = cookie_manager.get(&url)
-        .await
-        .send()
+    let request = cookie_manager.get(&url)
+        .await;
+    let response = cookie_manager.execute(request)
         .await
         .map_err(|e| format!("请求失败: {}", e))?;
     log::debug!("check_fake_ticket: {:?}", response);
@@ -1,14 +1,23 @@
-use common::{cookie_manager::CookieManager, http_utils::request_get};
-use serde_json;
+use common::{cookie_manager::CookieManager, http_utils::request_get, schema_drift::parse_tolerant};
 use std::sync::Arc;
 use common::show_orderlist::{*};
 
+// 订单列表中显示和导出日历依赖的字段
+const ORDER_CRITICAL_FIELDS: [&str; 5] = [
+    "data.list",
+    "data.list[].order_id",
+    "data.list[].status",
+    "data.list[].pay_money",
+    "data.list[].item_info.screen_start_time",
+];
+
 
 pub async fn get_orderlist(cookie_manager :Arc<CookieManager>) -> Result<OrderResponse, String>{
-    match cookie_manager.get(
+    let req = cookie_manager.get(
         
         "https://show.bilibili.com/api/ticket/ordercenter/ticketList?page=0&page_size=10" 
-        ).await.send().await{
+        ).await;
+    match cookie_manager.execute(req).await{
             Ok(resp) =>{
                 if resp.status().is_success(){
 
@@ -19,8 +28,11 @@ pub async fn get_orderlist(cookie_manager :Arc<CookieManager>) -> Result<OrderRe
                 }){
                     Ok(text) => {
                        log::debug!("获取全部订单：{}",text);
-                        match serde_json::from_str::<OrderResponse>(&text){
-                                Ok(order_resp) => {
+                        match parse_tolerant::<OrderResponse>("订单列表", &text){
+                                Ok((order_resp, report)) => {
+                                    if order_resp.errno == 0 {
+                                        report.ensure_fields(&ORDER_CRITICAL_FIELDS)?;
+                                    }
                                     return Ok(order_resp);
                                 }
                                 Err(e) => {log::error!("获取全部订单json解析失败：{}",e);
@@ -16,9 +16,15 @@ use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 use common::taskmanager::{*};
 use common::captcha::handle_risk_verification;
-use common::login::{send_loginsms,sms_login};
+use common::login::{cookie_login, send_loginsms, sms_login};
 use common::ticket::ConfirmTicketResult;
+use common::time_format::format_duration;
 use common::gen_cp::CTokenGenerator;
+use common::address::needs_delivery;
+use common::dry_run::{DryRunReport, DRY_RUN_TAG};
+use common::preflight::{run_preflight, CheckLevel, PreflightInput};
+use common::errno::describe;
+use common::grab_report::{GrabStats, ERRNO_NO_CODE, PHASE_CAPTCHA, PHASE_CHECK, PHASE_CONFIRM, PHASE_CREATE, PHASE_TOKEN};
 use common::ticket::{*};
 use crate::show_orderlist::get_orderlist;
 use crate::api::{*};
@@ -65,12 +71,12 @@ impl TaskManager for TaskManagerImpl {
                                         // 二维码登录逻辑
                                         let status = poll_qrcode_login(&qrcode_req.qrcode_key,qrcode_req.user_agent.as_deref()).await;
                                         
-                                        let (cookie, error) = match &status {
-                                            common::login::QrCodeLoginStatus::Success(cookie) => 
-                                                (Some(cookie.clone()), None),
+                                        let (cookie, refresh_token, error) = match &status {
+                                            common::login::QrCodeLoginStatus::Success(credential) => 
+                                                (Some(credential.cookie.clone()), credential.refresh_token.clone(), None),
                                             common::login::QrCodeLoginStatus::Failed(err) => 
-                                                (None, Some(err.clone())),
-                                            _ => (None, None)
+                                                (None, None, Some(err.clone())),
+                                            _ => (None, None, None)
                                         };
                                         
                                         // 创建正确的结果类型
@@ -78,6 +84,7 @@ impl TaskManager for TaskManagerImpl {
                                             task_id,
                                             status,
                                             cookie,
+                                            refresh_token,
                                             error,
                                         });
                                         
@@ -212,15 +219,15 @@ impl TaskManager for TaskManagerImpl {
                                             let response = sms_login(&phone,  &code,&captcha_key, &client).await;
                                             let success = response.is_ok();
                                             let message: String = match &response {
-                                                    Ok(msg) => msg.clone(),
+                                                    Ok(credential) => credential.cookie.clone(),
                                                     Err(err) => {
                                                         log::error!("提交短信验证码失败: {}", err);
                                                         err.to_string()
                                                     },
                                                 };
-                                            let cookie = match &response {
-                                                Ok(msg) => Some(msg.clone()),
-                                                Err(_) => None,
+                                            let (cookie, refresh_token) = match response {
+                                                Ok(credential) => (Some(credential.cookie), credential.refresh_token),
+                                                Err(_) => (None, None),
                                             };
                                             log::info!("提交短信任务完成 ID: {}, 结果: {}", 
                                                 task_id, 
@@ -233,6 +240,7 @@ impl TaskManager for TaskManagerImpl {
                                                 success,
                                                 message,
                                                 cookie,
+                                                refresh_token,
                                             });
 
                                             let _ = result_tx.send(task_result).await;
@@ -357,6 +365,164 @@ impl TaskManager for TaskManagerImpl {
                                         
                                     });
                                 }
+                                TaskRequest::SearchProjectRequest(search_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        log::debug!("正在搜索项目：{}", search_req.keyword);
+                                        let response = search_project(&search_req.client, &search_req.keyword, search_req.page).await;
+                                        let success = response.is_ok();
+                                        let (items, message) = match response {
+                                            Ok(items) => {
+                                                let message = format!("搜索到{}个项目", items.len());
+                                                (items, message)
+                                            }
+                                            Err(e) => {
+                                                log::error!("搜索项目失败：{}", e);
+                                                (Vec::new(), e)
+                                            }
+                                        };
+                                        let task_result = TaskResult::SearchProjectResult(SearchProjectResult{
+                                            task_id,
+                                            keyword: search_req.keyword.clone(),
+                                            items,
+                                            success,
+                                            message,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::ResolveShortLinkRequest(resolve_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        log::debug!("正在解析短链接：{}", resolve_req.short_url);
+                                        let response = resolve_short_link(&resolve_req.client, &resolve_req.short_url).await;
+                                        let success = response.is_ok();
+                                        let (project_id, message) = match response {
+                                            Ok(id) => (Some(id.clone()), format!("短链接解析成功，项目ID: {}", id)),
+                                            Err(e) => (None, e),
+                                        };
+                                        let task_result = TaskResult::ResolveShortLinkResult(ResolveShortLinkResult{
+                                            task_id,
+                                            short_url: resolve_req.short_url.clone(),
+                                            project_id,
+                                            success,
+                                            message,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::RefreshCookieRequest(refresh_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        let cookie_manager = refresh_req.cookie_manager.clone();
+                                        let result = match check_cookie_refresh(cookie_manager.clone(), &refresh_req.csrf).await {
+                                            Ok((false, _)) => Ok(None),
+                                            Ok((true, timestamp)) => {
+                                                log::info!("账号 {} 登录态需要刷新，正在刷新", refresh_req.uid);
+                                                refresh_web_cookie(cookie_manager, &refresh_req.csrf, &refresh_req.refresh_token, timestamp).await.map(Some)
+                                            }
+                                            Err(e) => Err(e),
+                                        };
+                                        let (success, refreshed, message) = match result {
+                                            Ok(Some(refreshed)) => (true, Some(refreshed), "登录态已刷新".to_string()),
+                                            Ok(None) => (true, None, "登录态无需刷新".to_string()),
+                                            Err(e) => (false, None, e),
+                                        };
+                                        let task_result = TaskResult::RefreshCookieResult(RefreshCookieResult{
+                                            task_id,
+                                            uid: refresh_req.uid,
+                                            success,
+                                            refreshed,
+                                            message,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::ImportAccountRequest(import_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        let ImportAccountRequest { index, cookie, client, user_agent, .. } = import_req;
+                                        // cookie_login 是同步接口，内部会创建运行时，放到阻塞线程执行
+                                        let login_cookie = cookie.clone();
+                                        let result = tokio::task::spawn_blocking(move || cookie_login(&login_cookie, &client, &user_agent))
+                                            .await
+                                            .unwrap_or_else(|e| Err(format!("校验任务异常: {}", e)));
+                                        let task_result = TaskResult::ImportAccountResult(ImportAccountResult{
+                                            task_id,
+                                            index,
+                                            cookie,
+                                            result,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::GetAddressListRequest(address_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        log::debug!("正在获取收货地址{}", task_id);
+                                        let (addresses, success, message) = match get_address_list(address_req.cookie_manager.clone()).await {
+                                            Ok(addresses) => (addresses, true, "获取收货地址成功".to_string()),
+                                            Err(e) => (Vec::new(), false, e),
+                                        };
+                                        let task_result = TaskResult::GetAddressListResult(GetAddressListResult{
+                                            task_id,
+                                            uid: address_req.uid,
+                                            addresses,
+                                            success,
+                                            message,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::SaveAddressRequest(save_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        log::debug!("正在保存收货地址{}", task_id);
+                                        let (success, message) = match save_address(save_req.cookie_manager.clone(), &save_req.input).await {
+                                            Ok(_) => (true, "保存收货地址成功".to_string()),
+                                            Err(e) => (false, e),
+                                        };
+                                        let task_result = TaskResult::SaveAddressResult(SaveAddressResult{
+                                            task_id,
+                                            uid: save_req.uid,
+                                            success,
+                                            message,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::WatchProjectRequest(watch_req) => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        log::debug!("关注列表刷新项目：{}", watch_req.project_id);
+                                        let response = get_project(watch_req.cookie_manager.clone(), &watch_req.project_id).await;
+                                        let (ticket_info, success, message) = match response {
+                                            Ok(info) if info.errno == 0 => (Some(info.data), true, "刷新成功".to_string()),
+                                            Ok(info) => (None, false, format!("刷新失败({}): {}", info.errno, info.msg)),
+                                            Err(e) => (None, false, e),
+                                        };
+                                        let task_result = TaskResult::WatchProjectResult(WatchProjectResult{
+                                            task_id,
+                                            project_id: watch_req.project_id.clone(),
+                                            ticket_info,
+                                            success,
+                                            message,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
+                                TaskRequest::GrabTicketRequest(grab_ticket_req) if grab_ticket_req.dry_run => {
+                                    let result_tx = result_tx.clone();
+                                    tokio::spawn(async move{
+                                        let report = run_dry_run(&grab_ticket_req).await;
+                                        let task_result = TaskResult::DryRunResult(DryRunResult{
+                                            task_id,
+                                            uid: grab_ticket_req.uid,
+                                            report,
+                                        });
+                                        let _ = result_tx.send(task_result).await;
+                                    });
+                                }
                                 TaskRequest::GrabTicketRequest(grab_ticket_req)=>{
                                     let project_id = grab_ticket_req.project_id.clone();
                                     let screen_id = grab_ticket_req.screen_id.clone();
@@ -392,24 +558,27 @@ impl TaskManager for TaskManagerImpl {
                                             rng.gen_range(2000..10000)
                                         )))
                                     };
+                                    let stats = Arc::new(Mutex::new(GrabStats::new()));
                                     tokio::spawn(async move{
                                         log::debug!("开始分析抢票任务：{}",task_id);
                                        
                                         match mode {
-                                            0 => {
+                                            0 => 'timed: {
                                                 log::debug!("定时抢票模式");
                                                 //log::debug!("开售时间：{}",project_info.clone().unwrap().sale_begin);
                                                 let mut countdown = match get_countdown(cookie_manager.clone(),project_info).await{
                                                     Ok(countdown) => countdown,
                                                     Err(e) => {
                                                         log::error!("获取倒计时失败: {}", e);
-                                                        return;
+                                                        // 不能直接return，否则收不到统计报告
+                                                        report_grab_failure(&result_tx, &grab_ticket_req, &stats, &task_id, uid, &format!("获取倒计时失败：{}", e)).await;
+                                                        break 'timed;
                                                     }
                                                 };
                                                 
                                                 //log::debug!("获取倒计时成功：{}",countdown);
                                                 if countdown > 0.0{
-                                                    log::info!("距离抢票时间还有{}秒",countdown);
+                                                    log::info!("距离抢票时间还有{}秒（{}）",countdown, format_duration(countdown as i64));
                                                     loop{
                                                         if countdown <= 20.0 {
                                                             break;
@@ -440,7 +609,9 @@ impl TaskManager for TaskManagerImpl {
                                                 //抢票主循环
                                                 loop{
 
+                                                    let token_started = std::time::Instant::now();
                                                     let token_result = get_ticket_token(cookie_manager.clone(), cpdd.clone(),&project_id, &screen_id, &ticket_id, count, is_hot).await;
+                                                    stats.lock().unwrap().record(PHASE_TOKEN, token_started.elapsed(), token_result.as_ref().map_or_else(|e| e.code as i64, |_| 0));
                                                     match token_result {
                                                         Ok((token,ptoken)) => {
                                                             //获取token成功！
@@ -453,6 +624,7 @@ impl TaskManager for TaskManagerImpl {
                                                                let (success, retry_limit) = handle_grab_ticket(
                                                                 cookie_manager.clone(), 
                                                                 cpdd.clone(),
+                                                                stats.clone(),
                                                                   &project_id, 
                                                                   &token, 
                                                                   &ptoken,
@@ -494,13 +666,16 @@ impl TaskManager for TaskManagerImpl {
                                                             if risk_param.code == -401 || risk_param.code == 401 {
                                                                 //需要处理验证码
                                                                 log::warn!("需要验证码，开始处理验证码...");
-                                                                match handle_risk_verification(
+                                                                let captcha_started = std::time::Instant::now();
+                                                                let captcha_result = handle_risk_verification(
                                                                     cookie_manager.clone(), 
                                                                     risk_param,
                                                                     &custon_config,
                                                                     &csrf,
                                                                     local_captcha.clone(),
-                                                                ).await {
+                                                                ).await;
+                                                                stats.lock().unwrap().record(PHASE_CAPTCHA, captcha_started.elapsed(), if captcha_result.is_ok() { 0 } else { ERRNO_NO_CODE });
+                                                                match captcha_result {
                                                                     Ok(()) => {
                                                                         //验证码处理成功，继续抢票
                                                                         log::info!("验证码处理成功！");
@@ -527,30 +702,11 @@ impl TaskManager for TaskManagerImpl {
                                                                 }
                                                             }else{
                                                              //人为导致无法重试的错误
-                                                             match risk_param.code {
-                                                                100080 | 100082 => {
-                                                                    log::error!("抢票失败，场次/项目/日期选择有误，请重新提交任务");
-                                                                }
-                                                                100039 => {
-                                                                    log::error!("抢票失败，该场次已停售，请重新提交任务");
-                                                                }
-                                                                _ => {
-                                                                    log::error!("抢票失败，未知错误，请重新提交任务");
-                                                                }
-                                                             }
+                                                             let reason = describe(risk_param.code as i64, &risk_param.message);
+                                                             log::error!("抢票失败：{}", reason);
                                                              token_retry_count +=1;
                                                              if token_retry_count >= MAX_TOKEN_RETRY {
-                                                                let task_result = TaskResult::GrabTicketResult(GrabTicketResult{
-                                                                    task_id: task_id.clone(),
-                                                                    uid,
-                                                                    success: false,
-                                                                    message: format!("获取token失败，错误代码: {}，错误信息：{}", risk_param.code, risk_param.message),
-                                                                    order_id: None,
-                                                                    pay_token: None,
-                                                                    pay_result: None,
-                                                                    confirm_result: None,
-                                                                });
-                                                                let _ = result_tx.send(task_result).await;
+                                                                report_grab_failure(&result_tx, &grab_ticket_req, &stats, &task_id, uid, &format!("获取token失败：{}", reason)).await;
                                                                 break;
                                                              }
                                                     }
@@ -576,7 +732,9 @@ impl TaskManager for TaskManagerImpl {
                                                 //抢票主循环
                                                 loop{
 
+                                                    let token_started = std::time::Instant::now();
                                                     let token_result = get_ticket_token(cookie_manager.clone(), cpdd.clone(),&project_id, &screen_id, &ticket_id, count, is_hot).await;
+                                                    stats.lock().unwrap().record(PHASE_TOKEN, token_started.elapsed(), token_result.as_ref().map_or_else(|e| e.code as i64, |_| 0));
                                                     match token_result {
                                                         Ok((token,ptoken)) => {
                                                             //获取token成功！
@@ -589,6 +747,7 @@ impl TaskManager for TaskManagerImpl {
                                                                 let (success, retry_limit) = handle_grab_ticket(
                                                                  cookie_manager.clone(), 
                                                                  cpdd.clone(),
+                                                                 stats.clone(),
                                                                    &project_id, 
                                                                    &token, 
                                                                    &ptoken,
@@ -631,13 +790,16 @@ impl TaskManager for TaskManagerImpl {
                                                             if risk_param.code == -401 || risk_param.code == 401 {
                                                                 //需要处理验证码
                                                                 log::warn!("需要验证码，开始处理验证码...");
-                                                                match handle_risk_verification(
+                                                                let captcha_started = std::time::Instant::now();
+                                                                let captcha_result = handle_risk_verification(
                                                                     cookie_manager.clone(), 
                                                                     risk_param,
                                                                     &custon_config,
                                                                     &csrf,
                                                                     local_captcha.clone(),
-                                                                ).await {
+                                                                ).await;
+                                                                stats.lock().unwrap().record(PHASE_CAPTCHA, captcha_started.elapsed(), if captcha_result.is_ok() { 0 } else { ERRNO_NO_CODE });
+                                                                match captcha_result {
                                                                     Ok(()) => {
                                                                         //验证码处理成功，继续抢票
                                                                         log::info!("验证码处理成功！");
@@ -664,30 +826,11 @@ impl TaskManager for TaskManagerImpl {
                                                                 }
                                                             }else{
                                                              //人为导致无法重试的错误
-                                                             match risk_param.code {
-                                                                100080 | 100082 => {
-                                                                    log::error!("抢票失败，场次/项目/日期选择有误，请重新提交任务");
-                                                                }
-                                                                100039 => {
-                                                                    log::error!("抢票失败，该场次已停售，请重新提交任务");
-                                                                }
-                                                                _ => {
-                                                                    log::error!("抢票失败，未知错误，请重新提交任务");
-                                                                }
-                                                             }
+                                                             let reason = describe(risk_param.code as i64, &risk_param.message);
+                                                             log::error!("抢票失败：{}", reason);
                                                              token_retry_count +=1;
                                                              if token_retry_count >= MAX_TOKEN_RETRY {
-                                                                let task_result = TaskResult::GrabTicketResult(GrabTicketResult{
-                                                                    task_id: task_id.clone(),
-                                                                    uid,
-                                                                    success: false,
-                                                                    message: format!("获取token失败，错误代码: {}，错误信息：{}", risk_param.code, risk_param.message),
-                                                                    order_id: None,
-                                                                    pay_token: None,
-                                                                    pay_result: None,
-                                                                    confirm_result: None,
-                                                                });
-                                                                let _ = result_tx.send(task_result).await;
+                                                                report_grab_failure(&result_tx, &grab_ticket_req, &stats, &task_id, uid, &format!("获取token失败：{}", reason)).await;
                                                                 break;
                                                              }
                                                     }
@@ -766,6 +909,7 @@ impl TaskManager for TaskManagerImpl {
                                                                 rng.gen_range(2000..10000)
                                                             )));
                                                             // 获取token
+                                                            let token_started = std::time::Instant::now();
                                                             let token_result = get_ticket_token(
                                                                 cookie_manager.clone(), 
                                                                 cpdd.clone(),
@@ -776,6 +920,7 @@ impl TaskManager for TaskManagerImpl {
                                                                 count,
                                                                 is_hot.clone()
                                                             ).await;
+                                                            stats.lock().unwrap().record(PHASE_TOKEN, token_started.elapsed(), token_result.as_ref().map_or_else(|e| e.code as i64, |_| 0));
                                                             match token_result {
                                                                 Ok((token,ptoken)) => {
                                                                     //获取token成功！
@@ -787,6 +932,7 @@ impl TaskManager for TaskManagerImpl {
                                                                         let (success, retry_limit) = handle_grab_ticket(
                                                                          cookie_manager.clone(), 
                                                                          cpdd.clone(),
+                                                                         stats.clone(),
                                                                            &project_id, 
                                                                            &token, 
                                                                            &ptoken,
@@ -821,13 +967,16 @@ impl TaskManager for TaskManagerImpl {
                                                             if risk_param.code == -401 || risk_param.code == 401 {
                                                                 //需要处理验证码
                                                                 log::warn!("需要验证码，开始处理验证码...");
-                                                                match handle_risk_verification(
+                                                                let captcha_started = std::time::Instant::now();
+                                                                let captcha_result = handle_risk_verification(
                                                                     cookie_manager.clone(), 
                                                                     risk_param,
                                                                     &custon_config,
                                                                     &csrf,
                                                                     local_captcha.clone(),
-                                                                ).await {
+                                                                ).await;
+                                                                stats.lock().unwrap().record(PHASE_CAPTCHA, captcha_started.elapsed(), if captcha_result.is_ok() { 0 } else { ERRNO_NO_CODE });
+                                                                match captcha_result {
                                                                     Ok(()) => {
                                                                         //验证码处理成功，继续抢票
                                                                         log::info!("验证码处理成功！");
@@ -854,30 +1003,11 @@ impl TaskManager for TaskManagerImpl {
                                                                 }
                                                             }else{
                                                              //人为导致无法重试的错误
-                                                             match risk_param.code {
-                                                                100080 | 100082 => {
-                                                                    log::error!("抢票失败，场次/项目/日期选择有误，请重新提交任务");
-                                                                }
-                                                                100039 => {
-                                                                    log::error!("抢票失败，该场次已停售，请重新提交任务");
-                                                                }
-                                                                _ => {
-                                                                    log::error!("抢票失败，未知错误，请重新提交任务");
-                                                                }
-                                                             }
+                                                             let reason = describe(risk_param.code as i64, &risk_param.message);
+                                                             log::error!("抢票失败：{}", reason);
                                                              token_retry_count +=1;
                                                              if token_retry_count >= MAX_TOKEN_RETRY {
-                                                                let task_result = TaskResult::GrabTicketResult(GrabTicketResult{
-                                                                    task_id: task_id.clone(),
-                                                                    uid,
-                                                                    success: false,
-                                                                    message: format!("获取token失败，错误代码: {}，错误信息：{}", risk_param.code, risk_param.message),
-                                                                    order_id: None,
-                                                                    pay_token: None,
-                                                                    pay_result: None,
-                                                                    confirm_result: None,
-                                                                });
-                                                                let _ = result_tx.send(task_result).await;
+                                                                report_grab_failure(&result_tx, &grab_ticket_req, &stats, &task_id, uid, &format!("获取token失败：{}", reason)).await;
                                                                 break;
                                                              }
                                                     }
@@ -900,6 +1030,11 @@ impl TaskManager for TaskManagerImpl {
                                                 log::error!("未知模式");
                                             }
                                         }
+
+                                        //任务结束，发送统计报告
+                                        let project_name = grab_ticket_req.biliticket.project_info.as_ref().map(|p| p.name.clone()).unwrap_or_default();
+                                        let report = stats.lock().unwrap().finish(&task_id, uid, &project_id, &project_name);
+                                        let _ = result_tx.send(TaskResult::GrabReportResult(GrabReportResult { task_id: task_id.clone(), uid, report })).await;
                                     });
                                 }
                             }
@@ -1033,7 +1168,11 @@ impl TaskManager for TaskManagerImpl {
                 self.running_tasks.insert(task_id.clone(), Task::GetBuyerInfoTask(task));
             }
             TaskRequest::GrabTicketRequest(grab_ticket_req) => {
-                log::info!("提交抢票任务 ID: {}", task_id);
+                if grab_ticket_req.dry_run {
+                    log::info!("{} 提交演练任务 ID: {}（不会创建订单）", DRY_RUN_TAG, task_id);
+                } else {
+                    log::info!("提交抢票任务 ID: {}", task_id);
+                }
                 
                /*  // 创建抢票任务
                 let task = GrabTicketTask {
@@ -1052,6 +1191,76 @@ impl TaskManager for TaskManagerImpl {
                 // 保存任务
                 self.running_tasks.insert(task_id.clone(), Task::GrabTicketTask(task)); */
             }
+            TaskRequest::SearchProjectRequest(search_req) => {
+                log::info!("提交搜索项目任务 ID: {}, 关键词: {}", task_id, search_req.keyword);
+                let task = SearchProjectTask {
+                    task_id: task_id.clone(),
+                    keyword: search_req.keyword.clone(),
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::SearchProjectTask(task));
+            }
+            TaskRequest::ResolveShortLinkRequest(resolve_req) => {
+                log::info!("提交短链接解析任务 ID: {}", task_id);
+                let task = ResolveShortLinkTask {
+                    task_id: task_id.clone(),
+                    short_url: resolve_req.short_url.clone(),
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::ResolveShortLinkTask(task));
+            }
+            TaskRequest::WatchProjectRequest(watch_req) => {
+                log::debug!("提交关注列表刷新任务 ID: {}, 项目: {}", task_id, watch_req.project_id);
+                let task = WatchProjectTask {
+                    task_id: task_id.clone(),
+                    project_id: watch_req.project_id.clone(),
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::WatchProjectTask(task));
+            }
+            TaskRequest::RefreshCookieRequest(refresh_req) => {
+                log::debug!("提交刷新登录态任务 ID: {}, 账号: {}", task_id, refresh_req.uid);
+                let task = RefreshCookieTask {
+                    task_id: task_id.clone(),
+                    uid: refresh_req.uid,
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::RefreshCookieTask(task));
+            }
+            TaskRequest::ImportAccountRequest(import_req) => {
+                log::debug!("提交导入账号校验任务 ID: {}, 序号: {}", task_id, import_req.index);
+                let task = ImportAccountTask {
+                    task_id: task_id.clone(),
+                    index: import_req.index,
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::ImportAccountTask(task));
+            }
+            TaskRequest::GetAddressListRequest(address_req) => {
+                log::debug!("提交获取收货地址任务 ID: {}", task_id);
+                let task = GetAddressListTask {
+                    uid: address_req.uid,
+                    task_id: task_id.clone(),
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::GetAddressListTask(task));
+            }
+            TaskRequest::SaveAddressRequest(save_req) => {
+                log::info!("提交保存收货地址任务 ID: {}", task_id);
+                let task = SaveAddressTask {
+                    uid: save_req.uid,
+                    task_id: task_id.clone(),
+                    status: TaskStatus::Pending,
+                    start_time: Some(std::time::Instant::now()),
+                };
+                self.running_tasks.insert(task_id.clone(), Task::SaveAddressTask(task));
+            }
 
         }
         
@@ -1098,6 +1307,13 @@ impl TaskManager for TaskManagerImpl {
                 Task::GetTicketInfoTask(t) => Some(t.status.clone()),
                 Task::GetBuyerInfoTask(t) => Some(t.status.clone()),
                 Task::GrabTicketTask(t) => Some(t.status.clone()),
+                Task::SearchProjectTask(t) => Some(t.status.clone()),
+                Task::ResolveShortLinkTask(t) => Some(t.status.clone()),
+                Task::WatchProjectTask(t) => Some(t.status.clone()),
+                Task::GetAddressListTask(t) => Some(t.status.clone()),
+                Task::SaveAddressTask(t) => Some(t.status.clone()),
+                Task::RefreshCookieTask(t) => Some(t.status.clone()),
+                Task::ImportAccountTask(t) => Some(t.status.clone()),
             }
         } else {
             None
@@ -1110,13 +1326,171 @@ impl TaskManager for TaskManagerImpl {
             let _ = handle.join();
         }
     }
+
+    fn runtime_handle(&self) -> tokio::runtime::Handle {
+        self.runtime.handle().clone()
+    }
 }
 
 
 
+//演练：加载项目和购票人、解析场次票种、开抢前检查，开售后再获取token并确认订单，但不调用 create_order
+async fn run_dry_run(grab_ticket_req: &GrabTicketRequest) -> DryRunReport {
+    let cookie_manager = grab_ticket_req.cookie_manager.clone();
+    let project_id = grab_ticket_req.project_id.as_str();
+    let screen_id = grab_ticket_req.screen_id.as_str();
+    let ticket_id = grab_ticket_req.ticket_id.as_str();
+    let mut report = DryRunReport::new(grab_ticket_req.uid, project_id, screen_id, ticket_id, grab_ticket_req.count);
+    let mut biliticket = grab_ticket_req.biliticket.clone();
+    log::info!("{} 开始演练，本次不会创建订单", DRY_RUN_TAG);
+
+    // 重新加载项目信息
+    match get_project(cookie_manager.clone(), project_id).await {
+        Ok(info) if info.errno == 0 && info.code == 0 => {
+            report.push(CheckLevel::Pass, "加载项目", format!("{}（{}）", info.data.name, info.data.sale_flag));
+            biliticket.project_info = Some(info.data);
+        }
+        Ok(info) => {
+            // 接口返回错误时 data 是空的，不能覆盖已缓存的项目信息
+            let code = if info.errno != 0 { info.errno } else { info.code };
+            report.push(CheckLevel::Error, "加载项目", describe(code as i64, &info.msg));
+            if biliticket.project_info.is_none() {
+                report.finished_at = chrono::Utc::now().timestamp();
+                return report;
+            }
+        }
+        Err(e) if biliticket.project_info.is_some() => {
+            report.push(CheckLevel::Warning, "加载项目", format!("{}，使用已缓存的项目信息", e));
+        }
+        Err(e) => {
+            report.push(CheckLevel::Error, "加载项目", e);
+            report.finished_at = chrono::Utc::now().timestamp();
+            return report;
+        }
+    }
+    let project = biliticket.project_info.clone().unwrap();
+    report.project_name = project.name.clone();
+
+    // 重新加载购票人，确认所选购票人仍然存在
+    let buyer_info = &grab_ticket_req.buyer_info;
+    if project.id_bind != 0 {
+        match get_buyer_info(cookie_manager.clone()).await {
+            Ok(resp) => {
+                let missing: Vec<&str> = buyer_info
+                    .iter()
+                    .filter(|b| !resp.data.list.iter().any(|item| item.id == b.id))
+                    .map(|b| b.name.as_str())
+                    .collect();
+                if missing.is_empty() {
+                    report.push(CheckLevel::Pass, "加载购票人", format!("账号共{}位购票人，已选{}位", resp.data.list.len(), buyer_info.len()));
+                } else {
+                    report.push(CheckLevel::Error, "加载购票人", format!("以下购票人已不在账号中：{}", missing.join("、")));
+                }
+                biliticket.all_buyer_info = Some(resp.data);
+            }
+            Err(e) => report.push(CheckLevel::Warning, "加载购票人", e),
+        }
+    }
+    report.set_buyers(buyer_info);
+
+    // 解析场次和票种
+    let screen = project.screen_list.iter().find(|s| s.id.to_string() == screen_id).cloned();
+    let ticket = screen
+        .as_ref()
+        .and_then(|s| s.ticket_list.iter().find(|t| t.id.to_string() == ticket_id).cloned());
+    match (&screen, &ticket) {
+        (Some(screen), Some(ticket)) => {
+            report.screen_name = screen.name.clone();
+            report.ticket_desc = ticket.desc.clone();
+            report.price = ticket.price as i64;
+            if needs_delivery(&project, screen) {
+                report.express_fee = project.express_fee as i64;
+            }
+            report.push(CheckLevel::Pass, "解析场次票种", format!("{} / {}", screen.name, ticket.desc));
+        }
+        (Some(screen), None) => {
+            report.screen_name = screen.name.clone();
+            report.push(CheckLevel::Error, "解析场次票种", format!("场次「{}」中没有票种ID {}", screen.name, ticket_id));
+        }
+        _ => report.push(CheckLevel::Error, "解析场次票种", format!("项目中没有场次ID {}", screen_id)),
+    }
+
+    // 开抢前检查
+    let now = chrono::Utc::now().timestamp();
+    let preflight = run_preflight(&PreflightInput {
+        biliticket: &biliticket,
+        screen: screen.as_ref(),
+        ticket_id: ticket_id.parse().ok(),
+        count: grab_ticket_req.count as i32,
+        buyers: buyer_info,
+        push_config: &biliticket.push_self,
+        now,
+    });
+    report.add_preflight(&preflight);
+
+    // 已开售时获取token并确认订单，这两步不会占用库存
+    let on_sale = project.sale_begin <= now && ticket.as_ref().map_or(false, |t| t.clickable);
+    if !on_sale {
+        report.push(CheckLevel::Warning, "确认订单", "尚未开售或票种不可售，跳过获取token和确认订单");
+    } else {
+        let mut rng = StdRng::from_entropy();
+        let cpdd = Arc::new(Mutex::new(CTokenGenerator::new(project.sale_begin, 0, rng.gen_range(2000..10000))));
+        match get_ticket_token(cookie_manager.clone(), cpdd, project_id, screen_id, ticket_id, grab_ticket_req.count, grab_ticket_req.is_hot).await {
+            Ok((token, _)) => {
+                report.push(CheckLevel::Pass, "获取token", "成功");
+                match confirm_ticket_order(cookie_manager.clone(), project_id, &token).await {
+                    Ok(confirm) => report.push(
+                        CheckLevel::Pass,
+                        "确认订单",
+                        format!("{} ×{}，应付 ¥{:.2}", confirm.ticket_info.name, confirm.count, confirm.pay_money as f64 / 100.0),
+                    ),
+                    Err((_, e)) => report.push(CheckLevel::Error, "确认订单", e),
+                }
+            }
+            Err(risk_param) if risk_param.code == -401 || risk_param.code == 401 => {
+                report.push(CheckLevel::Warning, "获取token", "需要验证码，演练不处理验证码");
+            }
+            Err(risk_param) => {
+                report.push(CheckLevel::Error, "获取token", describe(risk_param.code as i64, &risk_param.message));
+            }
+        }
+    }
+
+    report.push(CheckLevel::Pass, "创建订单", "演练模式，已跳过");
+    log::info!("{} 本应提交的订单：\n{}", DRY_RUN_TAG, report.order_summary());
+    report.finished_at = chrono::Utc::now().timestamp();
+    report
+}
+
+//抢票因致命错误终止时通知前端并推送，消息使用错误码对照表的说明
+async fn report_grab_failure(
+    result_tx: &mpsc::Sender<TaskResult>,
+    grab_ticket_req: &GrabTicketRequest,
+    stats: &Arc<Mutex<GrabStats>>,
+    task_id: &str,
+    uid: i64,
+    reason: &str,
+) {
+    stats.lock().unwrap().set_outcome(false, reason);
+    let task_result = TaskResult::GrabTicketResult(GrabTicketResult {
+        task_id: task_id.to_string(),
+        uid,
+        success: false,
+        message: reason.to_string(),
+        order_id: None,
+        pay_token: None,
+        confirm_result: None,
+        pay_result: None,
+    });
+    let _ = result_tx.send(task_result).await;
+    let title = format!("{}抢票失败", grab_ticket_req.biliticket.project_info.as_ref().map(|p| p.name.as_str()).unwrap_or(""));
+    let _ = grab_ticket_req.biliticket.push_self.push_all_async(&title, reason, &None).await;
+}
+
 async fn handle_grab_ticket(
     cookie_manager: Arc<CookieManager>,
     cpdd: Arc<Mutex<CTokenGenerator>>,
+    stats: Arc<Mutex<GrabStats>>,
     project_id: &str,
     token: &str,
     ptoken: &str,
@@ -1128,7 +1502,14 @@ async fn handle_grab_ticket(
     buyer_info: &Vec<BuyerInfo>,
 ) -> (bool, bool) {
     // 确认订单
-    match confirm_ticket_order(cookie_manager.clone(), project_id, token).await {
+    let confirm_started = std::time::Instant::now();
+    let confirm = confirm_ticket_order(cookie_manager.clone(), project_id, token).await;
+    let confirm_errno = match &confirm {
+        Ok(_) => 0,
+        Err((errno, _)) => *errno,
+    };
+    stats.lock().unwrap().record(PHASE_CONFIRM, confirm_started.elapsed(), confirm_errno);
+    match confirm {
         Ok(confirm_result) => {
             log::info!("确认订单成功！准备下单");
             
@@ -1136,6 +1517,7 @@ async fn handle_grab_ticket(
             if let Some((success,retry_limit)) = try_create_order(
                 cookie_manager.clone(),
                 cpdd.clone(),
+                stats.clone(),
                 project_id,
                 token,
                 ptoken,
@@ -1153,7 +1535,7 @@ async fn handle_grab_ticket(
             
             (true, false) // 订单流程已完成
         }
-        Err(e) => {
+        Err((_, e)) => {
             log::error!("确认订单失败，原因：{}  正在重试...", e);
             (false, false) // 需要继续重试
         }
@@ -1164,6 +1546,7 @@ async fn handle_grab_ticket(
 async fn try_create_order(
     cookie_manager: Arc<CookieManager>,
     cpdd: Arc<Mutex<CTokenGenerator>>,
+    stats: Arc<Mutex<GrabStats>>,
     project_id: &str,
     token: &str,
     ptoken: &str,
@@ -1187,7 +1570,8 @@ async fn try_create_order(
             need_retry = true;
         }
         
-        match create_order(
+        let order_started = std::time::Instant::now();
+        let order = create_order(
             cookie_manager.clone(), 
             cpdd.clone(),
             project_id, 
@@ -1201,7 +1585,9 @@ async fn try_create_order(
             need_retry,
             false,
             None
-        ).await {
+        ).await;
+        stats.lock().unwrap().record(PHASE_CREATE, order_started.elapsed(), order.as_ref().map_or_else(|e| *e as i64, |_| 0));
+        match order {
             Ok(order_result) => {
                 log::info!("下单成功！订单信息{:?}", order_result);
                 let empty_json = json!({});
@@ -1215,7 +1601,14 @@ async fn try_create_order(
                 
                 log::info!("下单成功！正在检测是否假票！");
                 // 检测假票
-                let check_result = match check_fake_ticket(cookie_manager.clone(), project_id, pay_token, order_id).await{
+                let check_started = std::time::Instant::now();
+                let check = check_fake_ticket(cookie_manager.clone(), project_id, pay_token, order_id).await;
+                let check_errno = match &check {
+                    Ok(result) => result.get("errno").and_then(|v| v.as_i64()).unwrap_or(0),
+                    Err(_) => ERRNO_NO_CODE,
+                };
+                stats.lock().unwrap().record(PHASE_CHECK, check_started.elapsed(), check_errno);
+                let check_result = match check {
                     Ok(result) => result,
                     Err(e) => {
                         log::error!("检测假票失败，原因：{}，请前往订单列表查看是否下单成功", e);
@@ -1250,6 +1643,7 @@ async fn try_create_order(
 
                 });
                 let _ = result_tx.send(task_result.clone()).await;
+                stats.lock().unwrap().set_outcome(true, "抢票成功");
                 
                 //修复由于挂在后台egui不运行导致任务管理器不加载导致不推送
                 let jump_url = Some(format!("bilibili://mall/web?url=https://mall.bilibili.com/neul-next/ticket/orderDetail.html?order_id={}", order_id.to_string()));
@@ -1264,72 +1658,40 @@ async fn try_create_order(
             
             Err(e) => {
                 // 处理错误情况
+                let reason = describe(e as i64, "");
                 match e {
                     //需要继续重试的临时错误
-                    100001 | 429 | 900001=> log::info!("b站限速，正常现象"),
+                    100001 | 429 | 900001 | 211 => log::info!("{}", reason),
                     100009 => { 
-                        log::info!("当前票种库存不足");
+                        log::info!("{}", reason);
                         //再次降速，不给b站服务器带来压力
                         tokio::time::sleep(tokio::time::Duration::from_secs_f32(0.6)).await; 
 
                     },
-                    211 => {
-                        log::info!("很遗憾，差一点点抢到票，继续加油吧！");
-                    }
                     
                     //需要暂停的情况
                     3 => {
-                        log::info!("抢票速度过快，即将被硬控5秒");
+                        log::info!("{}", reason);
                         log::info!("暂停4.8秒");
                         tokio::time::sleep(tokio::time::Duration::from_secs_f32(4.8)).await;
                     },
                     
                     //需要重新获取token的情况
                     100041 | 100050 | 900002=> {
-                        log::info!("token失效，即将重新获取token");
+                        log::info!("{}", reason);
                         return Some((true,true)); // 需要重新获取token
                     },
                     
                     //需要终止抢票的致命错误
-                    100017 | 100016 => {
-                        log::info!("当前项目/类型/场次已停售");
-                        return Some((true,false));
-                    },
-                    1 => {
-                        log::error!("超人 请慢一点，这是仅限1人抢票的项目，或抢票格式有误，请重新提交任务");
-                        return Some((true,false));
-                    }
-                    83000004 => {
-                        log::error!("没有配置购票人信息！请重新配置");
-                        return Some((true,false));
-                    },
-                    100079 | 100003  => {
-                        log::error!("购票人存在待付款订单，请前往支付或取消后重新下单");
-                        return Some((true,false));
-                    },
-                    100039 => {
-                        log::error!("活动收摊啦,下次要快点哦");
-                        return Some((true,false));
-                    }
-                    
-                    209001 => {
-                        log::error!("当前项目只能选择一个购票人！不支持多选，请重新提交任务");
-                        return Some((true,false));
-                    }
-                    737 => {
-                        log::error!("B站传了一个NUll回来，请看一下上一行的message提示信息，自行决定是否继续，如果取消请关闭重新打开该应用");
-                    }
-                    
-                    999 => {
-                        log::error!("程序内部错误！传参错误")
-                    }
-                    919 => {
-                        log::error!("程序内部错误！该项目区分绑定非绑定项目错误，传入意外值，请尝试重新下单以及提出issue");
+                    100017 | 100016 | 1 | 83000004 | 100079 | 100003 | 100039 | 209001 | 919 => {
+                        log::error!("下单失败：{}", reason);
+                        report_grab_failure(result_tx, grab_ticket_req, &stats, task_id, uid, &reason).await;
                         return Some((true,false));
                     }
+                    737 | 999 => log::error!("下单失败：{}", reason),
 
                     //未知错误
-                    _ => log::error!("下单失败，未知错误码：{} 可以提出issue修复该问题", e),
+                    _ => log::error!("下单失败：{}", reason),
                 }
             }
         }
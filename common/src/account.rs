@@ -4,6 +4,8 @@ use crate::{cookie_manager, http_utils::{request_get_sync,request_post_sync}};
 use serde_json;
 use std::sync::Arc;
 use crate::cookie_manager::CookieManager;
+use crate::cookie_refresh::RefreshedCookie;
+use crate::contact::Contact;
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Account{
     pub uid: i64,  //UID
@@ -16,10 +18,24 @@ pub struct Account{
     pub vip_label: String, //大会员，对应/nav请求中data['vip_label']['text']
     pub is_active: bool, //该账号是否启动抢票
     pub avatar_url: Option<String>, //头像地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>, //扫码/短信登录时下发，用于刷新web登录态
+    #[serde(default)]
+    pub alias: String, //备注名，用户自己填写
+    #[serde(default)]
+    pub notes: String, //备注
+    #[serde(default)]
+    pub tags: Vec<AccountTag>, //标签
+    #[serde(default)]
+    pub sort_order: i32, //手动排序，越小越靠前
+    #[serde(default)]
+    pub contacts: Vec<Contact>, //联系人
     #[serde(skip)]
     pub avatar_texture: Option<eframe::egui::TextureHandle>, //头像地址
     #[serde(skip)] 
     pub cookie_manager: Option<Arc<CookieManager>>, //cookie管理器
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>, //不认识的字段，保存时原样写回
 }
 impl std::fmt::Debug for Account{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -34,6 +50,12 @@ impl std::fmt::Debug for Account{
             .field("vip_label", &self.vip_label)
             .field("is_active", &self.is_active)
             .field("avatar_url", &self.avatar_url)
+            .field("refresh_token", &self.refresh_token.as_ref().map(|_| "******"))
+            .field("alias", &self.alias)
+            .field("notes", &self.notes)
+            .field("tags", &self.tags)
+            .field("sort_order", &self.sort_order)
+            .field("contacts", &self.contacts)
             .field("avatar_texture", &"SKipped")
             .field("client", &self.cookie_manager)
             .finish()
@@ -74,8 +96,15 @@ pub fn add_account(cookie: &str ,client: &Client, ua: &str) -> Result<Account, S
             vip_label: data["vip_label"]["text"].as_str().unwrap_or("").to_string(),
             is_active: true,
             avatar_url: Some(data["face"].as_str().unwrap_or("").to_string()),
+            refresh_token: None,
+            alias: String::new(),
+            notes: String::new(),
+            tags: Vec::new(),
+            sort_order: 0,
+            contacts: Vec::new(),
             avatar_texture: None,
             cookie_manager: Some(cookie_manager),
+            extra: serde_json::Map::new(),
         };
         account.ensure_client();
         Ok(account)
@@ -147,7 +176,122 @@ impl Account {
         }
     }
 
-    
+    //登录态过期时间：SESSDATA 形如 xxxx%2C<过期时间戳>%2Cxxxx，解析不到时返回None
+    pub fn session_expiry(&self) -> Option<i64> {
+        let sessdata = self.cookie.split(';').find_map(|part| {
+            let (key, value) = part.trim().split_once('=')?;
+            if key.trim() == "SESSDATA" { Some(value.trim().to_string()) } else { None }
+        })?;
+        let decoded = sessdata.replace("%2C", ",").replace("%2c", ",");
+        decoded.split(',').nth(1)?.trim().parse::<i64>().ok().filter(|t| *t > 0)
+    }
+
+    //用刷新后的凭据更新cookie、csrf和cookie管理器
+    pub fn apply_refreshed_cookie(&mut self, refreshed: &RefreshedCookie) {
+        self.cookie = refreshed.cookie.clone();
+        self.csrf = refreshed.csrf.clone();
+        self.refresh_token = Some(refreshed.refresh_token.clone());
+        if let Some(cookie_manager) = &self.cookie_manager {
+            cookie_manager.update_cookies(&refreshed.cookie);
+        }
+    }
+
+    pub fn has_session_cookie(&self) -> bool {
+        self.cookie.contains("SESSDATA=") && !self.csrf.is_empty()
+    }
+
+    //有备注名时显示 "备注名 (昵称)"，否则显示昵称
+    pub fn display_name(&self) -> String {
+        if self.alias.trim().is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.alias.trim(), self.name)
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.name == tag)
+    }
+
+    //重新登录/导入时沿用用户填写的备注名、备注、标签、排序和联系人
+    pub fn copy_metadata_from(&mut self, other: &Account) {
+        self.alias = other.alias.clone();
+        self.notes = other.notes.clone();
+        self.tags = other.tags.clone();
+        self.sort_order = other.sort_order;
+        self.contacts = other.contacts.clone();
+    }
+}
+
+//账号标签
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountTag {
+    pub name: String,
+    pub color: [u8; 3],
+}
+
+//标签可选颜色
+pub const TAG_COLORS: [[u8; 3]; 8] = [
+    [239, 68, 68],
+    [249, 115, 22],
+    [234, 179, 8],
+    [34, 160, 80],
+    [20, 184, 166],
+    [59, 130, 246],
+    [139, 92, 246],
+    [236, 72, 153],
+];
+
+//所有账号用到的标签（按名字去重，保留第一次出现的颜色）
+pub fn all_tags(accounts: &[Account]) -> Vec<AccountTag> {
+    let mut tags: Vec<AccountTag> = Vec::new();
+    for tag in accounts.iter().flat_map(|a| a.tags.iter()) {
+        if !tags.iter().any(|t| t.name == tag.name) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+//按手动排序排列，排序值相同时保持原顺序
+pub fn sort_accounts(accounts: &mut [Account]) {
+    accounts.sort_by_key(|a| a.sort_order);
+}
+
+//新账号排在最后
+pub fn next_sort_order(accounts: &[Account]) -> i32 {
+    accounts.iter().map(|a| a.sort_order + 1).max().unwrap_or(0)
+}
+
+//把账号与筛选结果中的上一个/下一个账号交换，并按新位置重新编号排序值
+pub fn move_account(accounts: &mut [Account], uid: i64, up: bool, filter: &Option<String>) -> bool {
+    let index = match accounts.iter().position(|a| a.uid == uid) {
+        Some(index) => index,
+        None => return false,
+    };
+    // 跳过被标签筛选隐藏的账号，否则筛选时看起来没有移动
+    let target = if up {
+        (0..index).rev().find(|i| matches_tag_filter(&accounts[*i], filter))
+    } else {
+        (index + 1..accounts.len()).find(|i| matches_tag_filter(&accounts[*i], filter))
+    };
+    let target = match target {
+        Some(target) => target,
+        None => return false,
+    };
+    accounts.swap(index, target);
+    for (order, account) in accounts.iter_mut().enumerate() {
+        account.sort_order = order as i32;
+    }
+    true
+}
+
+//标签筛选：未选标签时全部显示
+pub fn matches_tag_filter(account: &Account, filter: &Option<String>) -> bool {
+    match filter {
+        Some(tag) => account.has_tag(tag),
+        None => true,
+    }
 }
 
 // 创建client
@@ -178,4 +322,52 @@ fn create_client_for_account(cookie: &str) -> reqwest::Client {
         .cookie_store(true)
         .build()
         .unwrap_or_else(|_| reqwest::Client::new())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn account(uid: i64, tags: &[&str]) -> Account {
+        let tags: Vec<_> = tags.iter().map(|t| json!({ "name": t, "color": [0, 0, 0] })).collect();
+        serde_json::from_value(json!({
+            "uid": uid,
+            "name": format!("账号{}", uid),
+            "level": "6",
+            "cookie": "",
+            "csrf": "",
+            "is_login": true,
+            "account_status": "正常",
+            "vip_label": "",
+            "is_active": false,
+            "avatar_url": null,
+            "tags": tags,
+        }))
+        .unwrap()
+    }
+
+    fn uids(accounts: &[Account]) -> Vec<i64> {
+        accounts.iter().map(|a| a.uid).collect()
+    }
+
+    #[test]
+    fn move_without_filter_swaps_neighbours() {
+        let mut accounts = vec![account(1, &[]), account(2, &[]), account(3, &[])];
+        assert!(move_account(&mut accounts, 3, true, &None));
+        assert_eq!(uids(&accounts), vec![1, 3, 2]);
+        assert_eq!(accounts.iter().map(|a| a.sort_order).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(!move_account(&mut accounts, 1, true, &None));
+        assert!(!move_account(&mut accounts, 2, false, &None));
+    }
+
+    #[test]
+    fn move_with_filter_skips_hidden_accounts() {
+        let mut accounts = vec![account(1, &["主号"]), account(2, &[]), account(3, &["主号"]), account(4, &[])];
+        let filter = Some("主号".to_string());
+        assert!(move_account(&mut accounts, 3, true, &filter));
+        assert_eq!(uids(&accounts), vec![3, 2, 1, 4]);
+        // 筛选结果中已是最后一个
+        assert!(!move_account(&mut accounts, 1, false, &filter));
+        assert_eq!(uids(&accounts), vec![3, 2, 1, 4]);
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::Account;
+use crate::envelope::{check_passphrase, Envelope, EnvelopeKey};
+
+const BUNDLE_FORMAT: &str = "bili_ticket_rush.accounts";
+const BUNDLE_VERSION: u32 = 1;
+
+//账号包里的一个账号
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccountBundleEntry {
+    pub uid: i64,
+    pub name: String,
+    pub cookie: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+impl AccountBundleEntry {
+    pub fn from_account(account: &Account) -> Self {
+        Self {
+            uid: account.uid,
+            name: account.name.clone(),
+            cookie: account.cookie.clone(),
+            refresh_token: account.refresh_token.clone(),
+        }
+    }
+}
+
+//导出为加密账号包
+pub fn export_bundle(entries: &[AccountBundleEntry], passphrase: &str) -> Result<String, String> {
+    if entries.is_empty() {
+        return Err("没有选择要导出的账号".to_string());
+    }
+    check_passphrase(passphrase)?;
+    let plain = serde_json::to_vec(entries).map_err(|e| format!("账号序列化失败: {}", e))?;
+    EnvelopeKey::generate(passphrase)?.seal(BUNDLE_FORMAT, BUNDLE_VERSION, &plain)?.to_json()
+}
+
+//解密账号包，口令错误或文件被修改时返回错误
+pub fn import_bundle(content: &str, passphrase: &str) -> Result<Vec<AccountBundleEntry>, String> {
+    let file = Envelope::parse(content, BUNDLE_FORMAT).ok_or_else(|| "不是有效的账号包文件".to_string())?;
+    if file.version > BUNDLE_VERSION {
+        return Err(format!("账号包版本({})过新，请先升级程序", file.version));
+    }
+    let plain = EnvelopeKey::derive(passphrase, file.salt()?)?.open(&file)?;
+    serde_json::from_slice(&plain).map_err(|e| format!("账号包内容解析失败: {}", e))
+}
+
+//批量导入的cookie文本：每行一个cookie，空行和#开头的行忽略，返回 (行号, cookie)
+pub fn parse_cookie_lines(text: &str) -> Vec<(usize, String)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_no, line)| (line_no, line.trim_start_matches("Cookie:").trim().to_string()))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportStatus {
+    Pending,
+    Validating, //已提交校验，等待结果
+    Added { uid: i64, name: String },
+    Updated { uid: i64, name: String },
+    Failed(String),
+}
+
+impl ImportStatus {
+    pub fn is_in_progress(&self) -> bool {
+        matches!(self, ImportStatus::Pending | ImportStatus::Validating)
+    }
+}
+
+//导入报告中的一行
+#[derive(Clone, Debug)]
+pub struct ImportItem {
+    pub source: String, //来源，如 "第3行" / "账号包: 昵称(uid)"
+    pub cookie: String,
+    pub refresh_token: Option<String>,
+    pub status: ImportStatus,
+}
+
+impl ImportItem {
+    pub fn from_cookie_lines(text: &str) -> Vec<Self> {
+        parse_cookie_lines(text)
+            .into_iter()
+            .map(|(line_no, cookie)| Self {
+                source: format!("第{}行", line_no),
+                status: if cookie.contains("SESSDATA=") {
+                    ImportStatus::Pending
+                } else {
+                    ImportStatus::Failed("缺少SESSDATA，不是有效的登录cookie".to_string())
+                },
+                cookie,
+                refresh_token: None,
+            })
+            .collect()
+    }
+
+    pub fn from_bundle(entries: Vec<AccountBundleEntry>) -> Vec<Self> {
+        entries
+            .into_iter()
+            .map(|entry| Self {
+                source: format!("账号包: {}({})", entry.name, entry.uid),
+                cookie: entry.cookie,
+                refresh_token: entry.refresh_token,
+                status: ImportStatus::Pending,
+            })
+            .collect()
+    }
+}
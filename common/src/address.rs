@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ticket::{ScreenInfo, TicketInfo};
+
+// 纸质票快递的配送方式
+pub const DELIVERY_TYPE_EXPRESS: usize = 3;
+
+//收货地址（会员购地址簿）
+//地址簿的列表/新增/编辑接口和省市区字段是按网页版推测的，未经抓包验证，默认关闭，需在设置中开启实验功能
+//下单接口带地址的格式同样未验证，因此这里只管理地址簿，不会随订单提交
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryAddress {
+    pub id: i64,
+    pub name: String,
+    pub phone: String,
+    pub prov: String,
+    pub city: String,
+    pub area: String,
+    pub addr: String, //详细地址
+    pub is_default: bool,
+}
+
+impl DeliveryAddress {
+    pub fn full_address(&self) -> String {
+        // 直辖市的省和市相同，只显示一次
+        let city = if self.city == self.prov { "" } else { self.city.as_str() };
+        format!("{}{}{}{}", self.prov, city, self.area, self.addr)
+    }
+
+    pub fn masked_phone(&self) -> String {
+        let chars: Vec<char> = self.phone.chars().collect();
+        if chars.len() < 7 {
+            return self.phone.clone();
+        }
+        let prefix: String = chars[..3].iter().collect();
+        let suffix: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}****{}", prefix, suffix)
+    }
+}
+
+//解析地址列表接口返回，兼容 data.addr_list / data.list / data 三种结构
+pub fn parse_address_list(value: &Value) -> Result<Vec<DeliveryAddress>, String> {
+    let errno = value["errno"].as_i64().or_else(|| value["code"].as_i64()).unwrap_or(-1);
+    if errno != 0 {
+        let msg = value["msg"].as_str().or_else(|| value["message"].as_str()).unwrap_or("未知错误");
+        return Err(format!("获取收货地址失败({}): {}", errno, msg));
+    }
+    let data = &value["data"];
+    let list = data["addr_list"]
+        .as_array()
+        .or_else(|| data["list"].as_array())
+        .or_else(|| data.as_array());
+    let list = match list {
+        Some(list) => list,
+        None => return Ok(Vec::new()),
+    };
+    Ok(list
+        .iter()
+        .map(|item| DeliveryAddress {
+            id: value_i64(&item["id"]),
+            name: value_text(&item["name"]),
+            phone: value_text(&item["phone"]),
+            prov: value_text(&item["prov"]),
+            city: value_text(&item["city"]),
+            area: value_text(&item["area"]),
+            addr: value_text(&item["addr"]),
+            is_default: value_i64(&item["def"]) == 1 || item["def"].as_bool().unwrap_or(false),
+        })
+        .filter(|address| address.id != 0)
+        .collect())
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn value_i64(value: &Value) -> i64 {
+    match value {
+        Value::Number(n) => n.as_i64().unwrap_or(0),
+        Value::String(s) => s.trim().parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+//新增/编辑地址表单
+#[derive(Clone, Debug, Default)]
+pub struct AddressInput {
+    pub id: Option<i64>, //编辑时为地址ID
+    pub name: String,
+    pub phone: String,
+    pub prov: String,
+    pub city: String,
+    pub area: String,
+    pub addr: String,
+    pub is_default: bool,
+}
+
+impl AddressInput {
+    pub fn from_address(address: &DeliveryAddress) -> Self {
+        Self {
+            id: Some(address.id),
+            name: address.name.clone(),
+            phone: address.phone.clone(),
+            prov: address.prov.clone(),
+            city: address.city.clone(),
+            area: address.area.clone(),
+            addr: address.addr.clone(),
+            is_default: address.is_default,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("请填写收货人姓名".to_string());
+        }
+        let phone = self.phone.trim();
+        if phone.len() != 11 || !phone.chars().all(|c| c.is_ascii_digit()) {
+            return Err("请填写11位手机号".to_string());
+        }
+        if self.prov.trim().is_empty() || self.city.trim().is_empty() {
+            return Err("请填写省份和城市".to_string());
+        }
+        if self.addr.trim().chars().count() < 4 {
+            return Err("详细地址太短".to_string());
+        }
+        Ok(())
+    }
+
+    //提交给地址新增/修改接口的表单
+    pub fn to_form(&self) -> Value {
+        let mut form = json!({
+            "name": self.name.trim(),
+            "phone": self.phone.trim(),
+            "prov": self.prov.trim(),
+            "city": self.city.trim(),
+            "area": self.area.trim(),
+            "addr": self.addr.trim(),
+            "def": if self.is_default { 1 } else { 0 },
+        });
+        if let Some(id) = self.id {
+            form["id"] = json!(id);
+        }
+        form
+    }
+}
+
+//场次是否需要快递寄送纸质票；快递费是项目级的，同一项目里也可能有电子票场次，只看场次的配送方式
+pub fn needs_delivery(_info: &TicketInfo, screen: &ScreenInfo) -> bool {
+    screen.delivery_type == DELIVERY_TYPE_EXPRESS
+}
+
+//应付总额（分）：票价 × 数量 + 快递费
+pub fn total_with_express(price: usize, count: i32, express_fee: usize) -> i64 {
+    price as i64 * count.max(0) as i64 + express_fee as i64
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::envelope::{check_passphrase, Envelope, EnvelopeKey};
+
+const LOCKED_FORMAT: &str = "bili_ticket_rush.config.locked";
+const LOCKED_VERSION: u32 = 1;
+
+//启动口令设置，保存在配置里
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppLockConfig {
+    pub auto_lock_minutes: u32, //无操作多久后自动锁定，0为不自动锁定
+}
+
+impl Default for AppLockConfig {
+    fn default() -> Self {
+        Self { auto_lock_minutes: 15 }
+    }
+}
+
+enum LockState {
+    Disabled,          //未设置口令，沿用机器码加密
+    Locked,            //配置已用口令加密，尚未解锁，此时不能保存配置
+    Unlocked(EnvelopeKey),
+}
+
+static LOCK_STATE: Lazy<Mutex<LockState>> = Lazy::new(|| Mutex::new(LockState::Disabled));
+
+fn parse_locked(content: &str) -> Option<Envelope> {
+    Envelope::parse(content, LOCKED_FORMAT)
+}
+
+pub fn is_locked_content(content: &str) -> bool {
+    parse_locked(content).is_some()
+}
+
+fn is_locked_file(path: &Path) -> bool {
+    fs::read_to_string(path).map(|content| is_locked_content(&content)).unwrap_or(false)
+}
+
+//加载配置前调用：配置已用口令加密时进入锁定状态，返回是否需要解锁
+pub fn prepare(path: &Path) -> bool {
+    let locked = is_locked_file(path);
+    *LOCK_STATE.lock().unwrap() = if locked { LockState::Locked } else { LockState::Disabled };
+    locked
+}
+
+pub fn is_enabled() -> bool {
+    !matches!(*LOCK_STATE.lock().unwrap(), LockState::Disabled)
+}
+
+pub fn is_unlocked() -> bool {
+    matches!(*LOCK_STATE.lock().unwrap(), LockState::Unlocked(_))
+}
+
+//用口令解锁配置文件，成功后即可正常加载和保存配置
+pub fn unlock(path: &Path, passphrase: &str) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    let file = parse_locked(&content).ok_or_else(|| "配置文件未设置口令".to_string())?;
+    let key = EnvelopeKey::derive(passphrase, file.salt()?)?;
+    decrypt_file(&key, &file)?;
+    *LOCK_STATE.lock().unwrap() = LockState::Unlocked(key);
+    log::info!("配置已解锁");
+    Ok(())
+}
+
+//自动锁定后再次解锁：校验口令是否与当前密钥一致
+pub fn verify(passphrase: &str) -> Result<(), String> {
+    // 派生密钥较慢，不能持锁，否则期间保存配置会卡住界面
+    let key = match &*LOCK_STATE.lock().unwrap() {
+        LockState::Unlocked(key) => key.clone(),
+        _ => return Err("未设置口令".to_string()),
+    };
+    let candidate = EnvelopeKey::derive(passphrase, key.salt().to_vec())?;
+    if candidate.same_as(&key) {
+        Ok(())
+    } else {
+        Err("口令错误".to_string())
+    }
+}
+
+//切换状态后用 save 保存配置，保存失败时恢复原状态，避免内存中的口令与文件不一致
+fn switch_state(state: LockState, save: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    let previous = std::mem::replace(&mut *LOCK_STATE.lock().unwrap(), state);
+    if let Err(e) = save() {
+        *LOCK_STATE.lock().unwrap() = previous;
+        return Err(e);
+    }
+    Ok(())
+}
+
+//设置/修改口令并用新口令保存配置
+pub fn set_passphrase(passphrase: &str, save: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    check_passphrase(passphrase)?;
+    if matches!(*LOCK_STATE.lock().unwrap(), LockState::Locked) {
+        return Err("配置尚未解锁".to_string());
+    }
+    let key = EnvelopeKey::generate(passphrase)?;
+    switch_state(LockState::Unlocked(key), save)
+}
+
+//关闭口令并恢复机器码加密保存配置
+pub fn disable(save: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    switch_state(LockState::Disabled, save)
+}
+
+//忘记口令时重置：删除无法解密的配置（账号、cookie和设置全部清除，密文无法只保留部分字段）
+pub fn reset(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| format!("删除配置文件失败: {}", e))?;
+    }
+    *LOCK_STATE.lock().unwrap() = LockState::Disabled;
+    log::warn!("已重置启动口令，当前档案的配置已删除");
+    Ok(())
+}
+
+//按当前状态加密配置；返回None表示未设置口令，由调用方使用机器码加密
+pub fn encrypt(data: &[u8]) -> Result<Option<String>, String> {
+    let state = LOCK_STATE.lock().unwrap();
+    let key = match &*state {
+        LockState::Disabled => return Ok(None),
+        LockState::Locked => return Err("配置已加密，请先解锁再保存".to_string()),
+        LockState::Unlocked(key) => key,
+    };
+    key.seal(LOCKED_FORMAT, LOCKED_VERSION, data)?.to_json().map(Some)
+}
+
+//解密口令加密的配置，需先解锁
+pub fn decrypt(content: &str) -> Result<String, String> {
+    let file = parse_locked(content).ok_or_else(|| "配置文件格式错误".to_string())?;
+    let state = LOCK_STATE.lock().unwrap();
+    let key = match &*state {
+        LockState::Unlocked(key) => key,
+        _ => return Err("配置已加密，请先输入口令解锁".to_string()),
+    };
+    if file.salt()? != key.salt() {
+        return Err("配置文件的口令与当前口令不一致，请重新解锁".to_string());
+    }
+    decrypt_file(key, &file)
+}
+
+fn decrypt_file(key: &EnvelopeKey, file: &Envelope) -> Result<String, String> {
+    if file.version > LOCKED_VERSION {
+        return Err(format!("配置文件版本({})过新，请先升级程序", file.version));
+    }
+    let plain = key.open(file)?;
+    String::from_utf8(plain).map_err(|e| format!("配置文件内容不是utf8: {}", e))
+}
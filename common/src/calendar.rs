@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use chrono::TimeZone;
+use serde::{Deserialize, Serialize};
+
+use crate::ics::IcsEvent;
+use crate::show_orderlist::Order;
+use crate::time_format::{beijing_offset, parse_beijing_time};
+use crate::watchlist::WatchlistConfig;
+
+pub const DEFAULT_REMINDER_MINUTES: u32 = 10;
+
+//日历设置，保存在 AppConfig.calendar
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    #[serde(default)]
+    pub reminder_enabled: bool, //开售前推送提醒
+    #[serde(default = "default_reminder_minutes")]
+    pub reminder_minutes: u32,
+    #[serde(default)]
+    pub reminded: Vec<String>, //已提醒过的开售（项目ID@时间戳），避免重启后重复提醒
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            reminder_enabled: false,
+            reminder_minutes: DEFAULT_REMINDER_MINUTES,
+            reminded: Vec::new(),
+        }
+    }
+}
+
+fn default_reminder_minutes() -> u32 {
+    DEFAULT_REMINDER_MINUTES
+}
+
+impl CalendarConfig {
+    //取出进入提醒时间窗口且未提醒过的开售，同时清理一天前的记录
+    pub fn take_due_reminders(&mut self, entries: &[CalendarEntry], now: i64) -> Vec<CalendarEntry> {
+        self.reminded.retain(|key| {
+            key.rsplit('@')
+                .next()
+                .and_then(|t| t.parse::<i64>().ok())
+                .map(|t| t > now - 86400)
+                .unwrap_or(false)
+        });
+        let lead = self.reminder_minutes as i64 * 60;
+        let mut due = Vec::new();
+        for entry in entries.iter().filter(|e| e.kind == EntryKind::SaleStart) {
+            if now < entry.time - lead || now >= entry.time {
+                continue;
+            }
+            let key = format!("{}@{}", entry.project_id, entry.time);
+            if self.reminded.contains(&key) {
+                continue;
+            }
+            self.reminded.push(key);
+            due.push(entry.clone());
+        }
+        due
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    SaleStart,
+    SaleEnd,
+    Performance,
+}
+
+impl EntryKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryKind::SaleStart => "开售",
+            EntryKind::SaleEnd => "停售",
+            EntryKind::Performance => "演出",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CalendarEntry {
+    pub time: i64,
+    pub end: Option<i64>,
+    pub kind: EntryKind,
+    pub project_id: String,
+    pub title: String,
+    pub detail: String,
+    pub location: String,
+    pub source: &'static str, //来源：关注 / 订单
+    pub order_id: Option<String>, //订单条目的订单号，同一场次可能有多个订单
+}
+
+//关注项目的开售、停售和各场次时间（需要至少刷新过一次）
+pub fn entries_from_watchlist(watchlist: &WatchlistConfig) -> Vec<CalendarEntry> {
+    let mut entries = Vec::new();
+    for project in &watchlist.projects {
+        let snapshot = match &project.snapshot {
+            Some(snapshot) => snapshot,
+            None => continue,
+        };
+        let entry = |time: i64, kind: EntryKind, detail: String| CalendarEntry {
+            time,
+            end: None,
+            kind,
+            project_id: project.project_id.clone(),
+            title: snapshot.name.clone(),
+            detail,
+            location: snapshot.venue.clone(),
+            source: "关注",
+            order_id: None,
+        };
+        if snapshot.sale_begin > 0 {
+            entries.push(entry(snapshot.sale_begin, EntryKind::SaleStart, snapshot.sale_flag.clone()));
+        }
+        if snapshot.sale_end > 0 {
+            entries.push(entry(snapshot.sale_end, EntryKind::SaleEnd, String::new()));
+        }
+        for screen in &snapshot.screens {
+            if screen.start_time > 0 {
+                entries.push(entry(screen.start_time as i64, EntryKind::Performance, screen.name.clone()));
+            }
+        }
+    }
+    entries
+}
+
+//已购订单的演出时间，venues 为已知的 项目ID -> 场馆名
+pub fn entries_from_orders(orders: &[Order], venues: &HashMap<String, String>) -> Vec<CalendarEntry> {
+    orders
+        .iter()
+        .filter_map(|order| {
+            let venue = venues.get(&order.item_id.to_string()).map(String::as_str).unwrap_or("");
+            order_entry(order, venue)
+        })
+        .collect()
+}
+
+//单个订单的演出日程，只收录已支付的订单；未支付、已取消/已退款或没有场次时间的返回None
+pub fn order_entry(order: &Order, venue: &str) -> Option<CalendarEntry> {
+    if !order.is_paid() {
+        return None;
+    }
+    let start = parse_beijing_time(&order.item_info.screen_start_time)?;
+    Some(CalendarEntry {
+        time: start,
+        end: parse_beijing_time(&order.item_info.screen_end_time).filter(|end| *end > start),
+        kind: EntryKind::Performance,
+        project_id: order.item_id.to_string(),
+        title: order.item_info.name.clone(),
+        detail: format!("场次：{}\n数量：{}张\n订单号：{}", order.item_info.screen_name, order.count, order.order_id),
+        location: venue.to_string(),
+        source: "订单",
+        order_id: Some(order.order_id.clone()),
+    })
+}
+
+//今天及以后的条目，按时间排序并去重
+pub fn upcoming(mut entries: Vec<CalendarEntry>, now: i64) -> Vec<CalendarEntry> {
+    let beijing = beijing_offset();
+    let today_start = beijing
+        .timestamp_opt(now, 0)
+        .single()
+        .and_then(|dt| dt.date_naive().and_hms_opt(0, 0, 0))
+        .and_then(|dt| beijing.from_local_datetime(&dt).single())
+        .map(|dt| dt.timestamp())
+        .unwrap_or(now);
+    entries.retain(|e| e.time >= today_start);
+    entries.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.title.cmp(&b.title)));
+    entries.dedup_by(|a, b| a.time == b.time && a.kind == b.kind && a.project_id == b.project_id && a.order_id == b.order_id);
+    entries
+}
+
+//转换为日历事件，只有开售事件带提醒
+pub fn to_ics_events(entries: &[CalendarEntry], alarm_minutes: Option<u32>) -> Vec<IcsEvent> {
+    entries
+        .iter()
+        .map(|entry| IcsEvent {
+            uid: match &entry.order_id {
+                Some(order_id) => format!("{}-{:?}-{}-{}@bili_ticket_rush", entry.project_id, entry.kind, entry.time, order_id),
+                None => format!("{}-{:?}-{}@bili_ticket_rush", entry.project_id, entry.kind, entry.time),
+            },
+            summary: format!("【{}】{}", entry.kind.label(), entry.title),
+            description: if entry.detail.is_empty() {
+                format!("项目ID: {}", entry.project_id)
+            } else {
+                format!("{}\n项目ID: {}", entry.detail, entry.project_id)
+            },
+            location: entry.location.clone(),
+            start: entry.time,
+            end: entry.end,
+            alarm_minutes: if entry.kind == EntryKind::SaleStart { alarm_minutes } else { None },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn order(order_id: &str, status: i32, sub_status_name: &str) -> Order {
+        serde_json::from_value(json!({
+            "order_id": order_id,
+            "order_type": 1,
+            "item_id": 85939,
+            "item_info": {
+                "name": "测试演出",
+                "image": null,
+                "screen_id": "153744",
+                "screen_name": "5月1日 晚场",
+                "screen_start_time": "2025-05-01 19:30:00",
+                "screen_end_time": "2025-05-01 22:00:00",
+            },
+            "total_money": 38000,
+            "count": 1,
+            "pay_money": 38000,
+            "pay_channel": null,
+            "status": status,
+            "sub_status": 0,
+            "ctime": "2025-04-01 20:00:01",
+            "img": { "url": "" },
+            "sub_status_name": sub_status_name,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_paid_orders_become_entries() {
+        assert!(order_entry(&order("1", 2, "已完成"), "").is_some());
+        assert!(order_entry(&order("1", 1, "待付款"), "").is_none());
+        assert!(order_entry(&order("1", 2, "已退款"), "").is_none());
+        assert!(order_entry(&order("1", 2, "已取消"), "").is_none());
+    }
+
+    #[test]
+    fn orders_for_the_same_screen_stay_separate() {
+        let entries = vec![order_entry(&order("1001", 2, "已完成"), "").unwrap(), order_entry(&order("1002", 2, "已完成"), "").unwrap()];
+        let entries = upcoming(entries, 1746000000);
+        assert_eq!(entries.len(), 2);
+        let events = to_ics_events(&entries, Some(30));
+        assert_ne!(events[0].uid, events[1].uid);
+        assert!(events[0].uid.contains("1001") || events[1].uid.contains("1001"));
+        // 演出条目不带提醒
+        assert!(events.iter().all(|e| e.alarm_minutes.is_none()));
+    }
+}
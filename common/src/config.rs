@@ -0,0 +1,558 @@
+use std::fs;
+use std::path::Path;
+
+use aes::Aes128;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use block_modes::block_padding::Pkcs7;
+use block_modes::{BlockMode, Cbc};
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::account::Account;
+use crate::contact::Contact;
+use crate::data_dir;
+use crate::push::PushConfig;
+use crate::utility::{CustomConfig, DEFAULT_CUSTOM_UA};
+use crate::watchlist::WatchlistConfig;
+use crate::calendar::CalendarConfig;
+use crate::app_lock::{self, AppLockConfig};
+
+// 配置结构版本，结构变化时+1并在 MIGRATIONS 里补一个迁移函数
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+// 按顺序执行：MIGRATIONS[n] 把版本 n 升级到 n+1
+const MIGRATIONS: [fn(&mut Map<String, Value>) -> Result<(), String>; 2] = [migrate_v0_to_v1, migrate_v1_to_v2];
+
+//程序配置，加密保存在数据目录（当前档案）下的config
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default = "PushConfig::new")]
+    pub push_config: PushConfig,
+    #[serde(default = "CustomConfig::new")]
+    pub custom_config: CustomConfig,
+    #[serde(default)]
+    pub watchlist: WatchlistConfig,
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    #[serde(default)]
+    pub app_lock: AppLockConfig,
+    //所有账号共用的联系人（含旧版预填手机号），下单和短信登录时可选
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_contacts: Vec<Contact>,
+    //本版本不认识的字段原样保留，避免旧版本覆盖新版本写入的数据
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppConfig {
+    pub fn new() -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            accounts: Vec::new(),
+            push_config: PushConfig::new(),
+            custom_config: CustomConfig::new(),
+            watchlist: WatchlistConfig::default(),
+            calendar: CalendarConfig::default(),
+            app_lock: AppLockConfig::default(),
+            shared_contacts: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+
+    //加载当前档案配置；没有加密配置时尝试迁移旧版明文config.json
+    pub fn load() -> Result<Self, String> {
+        let path = data_dir::config_path();
+        if path.exists() {
+            return Self::load_from(&path);
+        }
+        let legacy_path = data_dir::legacy_json_config_path();
+        if legacy_path.exists() {
+            log::info!("未找到配置文件，尝试迁移json配置");
+            let content = fs::read_to_string(&legacy_path)
+                .map_err(|e| format!("读取旧配置 {} 失败: {}", legacy_path.display(), e))?;
+            let config = Self::from_json_str(&content)?;
+            config.save()?;
+            match fs::remove_file(&legacy_path) {
+                Ok(_) => log::info!("旧配置文件删除成功"),
+                Err(e) => log::error!("旧配置文件删除失败: {}", e),
+            }
+            log::info!("迁移成功");
+            return Ok(config);
+        }
+        log::info!("未找到配置文件，使用默认配置");
+        let config = Self::new();
+        config.save()?;
+        Ok(config)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        let raw_context = fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件 {} 失败: {}", path.display(), e))?;
+        let plain_text = decrypt_config(&raw_context)?;
+        Self::from_json_str(&plain_text)
+    }
+
+    //解析明文json：迁移到当前版本 -> 校验 -> 转为强类型
+    pub fn from_json_str(content: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(content)
+            .map_err(|e| format!("配置不是有效的json: {}", e))?;
+        Self::from_value(value)
+    }
+
+    pub fn from_value(value: Value) -> Result<Self, String> {
+        let mut map = match value {
+            Value::Object(map) => map,
+            Value::Null => Map::new(),
+            other => return Err(format!("配置顶层应为对象，实际为 {}", json_type_name(&other))),
+        };
+        migrate(&mut map)?;
+        validate(&map)?;
+        let mut config: Self = serde_json::from_value(Value::Object(map)).map_err(|e| format!("配置解析失败: {}", e))?;
+        crate::account::sort_accounts(&mut config.accounts);
+        Ok(config)
+    }
+
+    //严格解析失败时按部分恢复：读不了的部分用默认值，无法读取的账号跳过，返回恢复的配置和问题列表
+    //文件无法解密或不是json时返回Err
+    pub fn recover_from(path: &Path) -> Result<(Self, Vec<String>), String> {
+        let raw_context = fs::read_to_string(path)
+            .map_err(|e| format!("读取配置文件 {} 失败: {}", path.display(), e))?;
+        let plain_text = decrypt_config(&raw_context)?;
+        let value: Value = serde_json::from_str(&plain_text)
+            .map_err(|e| format!("配置不是有效的json: {}", e))?;
+        Self::recover_from_value(value)
+    }
+
+    pub fn recover_from_value(value: Value) -> Result<(Self, Vec<String>), String> {
+        let mut map = match value {
+            Value::Object(map) => map,
+            other => return Err(format!("配置顶层应为对象，实际为 {}", json_type_name(&other))),
+        };
+        let mut issues = Vec::new();
+        if let Err(e) = migrate(&mut map) {
+            issues.push(e);
+        }
+        let mut config = Self::new();
+        match map.remove("accounts") {
+            Some(Value::Array(accounts)) => {
+                for (idx, account) in accounts.into_iter().enumerate() {
+                    match serde_json::from_value::<Account>(account) {
+                        Ok(account) if config.accounts.iter().any(|a| a.uid == account.uid) => {
+                            issues.push(format!("accounts[{}] uid重复，已跳过", idx));
+                        }
+                        Ok(account) => config.accounts.push(account),
+                        Err(e) => issues.push(format!("accounts[{}] 无法读取，已跳过: {}", idx, e)),
+                    }
+                }
+            }
+            None | Some(Value::Null) => {}
+            Some(other) => issues.push(format!("accounts 应为数组，实际为 {}，账号已清空", json_type_name(&other))),
+        }
+        recover_section(&mut map, "push_config", &mut config.push_config, &mut issues);
+        recover_section(&mut map, "custom_config", &mut config.custom_config, &mut issues);
+        recover_section(&mut map, "watchlist", &mut config.watchlist, &mut issues);
+        recover_section(&mut map, "calendar", &mut config.calendar, &mut issues);
+        recover_section(&mut map, "app_lock", &mut config.app_lock, &mut issues);
+        recover_section(&mut map, "shared_contacts", &mut config.shared_contacts, &mut issues);
+        map.remove("schema_version");
+        config.extra = map;
+        crate::account::sort_accounts(&mut config.accounts);
+        Ok((config, issues))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        self.save_to(&data_dir::config_path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        let json_str = serde_json::to_string_pretty(self).map_err(|e| format!("配置序列化失败: {}", e))?;
+        let content = encrypt_config(json_str.as_bytes())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建配置目录失败: {}", e))?;
+        }
+        fs::write(path, content).map_err(|e| format!("写入配置文件 {} 失败: {}", path.display(), e))
+    }
+
+    //添加账号（uid已存在时覆盖，保留备注名、标签等用户填写的信息）
+    pub fn add_account(&mut self, account: &Account) {
+        match self.accounts.iter_mut().find(|acc| acc.uid == account.uid) {
+            Some(acc) => {
+                let mut account = account.clone();
+                account.copy_metadata_from(acc);
+                *acc = account;
+            }
+            None => {
+                let mut account = account.clone();
+                account.sort_order = crate::account::next_sort_order(&self.accounts);
+                self.accounts.push(account);
+            }
+        }
+    }
+
+    //账号更新（Account更新后调用这个保存,uid唯一寻找标识）
+    pub fn update_account(&mut self, account: &Account) -> bool {
+        match self.accounts.iter_mut().find(|acc| acc.uid == account.uid) {
+            Some(acc) => {
+                *acc = account.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    //删除账号，传uid
+    pub fn delete_account(&mut self, uid: i64) -> bool {
+        let old_len = self.accounts.len();
+        self.accounts.retain(|acc| acc.uid != uid);
+        let remove_flag = self.accounts.len() != old_len;
+        match self.save() {
+            Ok(_) => log::info!("删除账号成功"),
+            Err(e) => log::error!("删除账号失败: {}", e),
+        }
+        remove_flag
+    }
+
+    //配置文件损坏时先备份，避免直接被新配置覆盖
+    pub fn backup_broken(path: &Path) -> Option<std::path::PathBuf> {
+        if !path.exists() {
+            return None;
+        }
+        let backup = path.with_file_name(format!(
+            "config.broken.{}",
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        ));
+        match fs::rename(path, &backup) {
+            Ok(_) => Some(backup),
+            Err(e) => {
+                log::error!("备份损坏的配置文件失败: {}", e);
+                None
+            }
+        }
+    }
+}
+
+pub fn save_config(config: &mut AppConfig, push_config: Option<&PushConfig>, custon_config: Option<&CustomConfig>, account: Option<Account>) -> Result<bool, String> {
+    if let Some(push_config) = push_config {
+        config.push_config = push_config.clone();
+    }
+    if let Some(custon_config) = custon_config {
+        config.custom_config = custon_config.clone();
+    }
+    if let Some(account) = account {
+        config.add_account(&account);
+    }
+
+    match config.save() {
+        Ok(_) => {
+            log::info!("配置文件保存成功");
+            Ok(true)
+        }
+        Err(e) => {
+            log::error!("配置文件保存失败: {}", e);
+            Err(e)
+        }
+    }
+}
+
+fn migrate(map: &mut Map<String, Value>) -> Result<(), String> {
+    let version = match map.get("schema_version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| format!("schema_version 应为非负整数，实际为 {}", v))?,
+    };
+    if version > CURRENT_SCHEMA_VERSION {
+        // 更新版本写入的配置：能认识的字段照常读取，其余字段原样保留
+        log::warn!(
+            "配置文件版本({})高于当前程序支持的版本({})，部分设置可能无法识别",
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+        return Ok(());
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(map).map_err(|e| format!("配置从版本{}迁移失败: {}", from, e))?;
+        map.insert("schema_version".to_string(), json!(from as u32 + 1));
+        log::info!("配置已从版本{}迁移到版本{}", from, from + 1);
+    }
+    Ok(())
+}
+
+// 版本0：旧版无类型配置，各部分可能缺失或缺少字段
+fn migrate_v0_to_v1(map: &mut Map<String, Value>) -> Result<(), String> {
+    match map.get("accounts") {
+        None | Some(Value::Null) => {
+            map.insert("accounts".to_string(), json!([]));
+        }
+        Some(Value::Array(accounts)) => {
+            // 旧版重复登录会追加同一uid的账号，只保留最后一次登录的
+            let mut deduped: Vec<Value> = Vec::new();
+            for account in accounts.iter().rev() {
+                let uid = account.get("uid").and_then(Value::as_i64);
+                if uid.is_some() && deduped.iter().any(|a| a.get("uid").and_then(Value::as_i64) == uid) {
+                    continue;
+                }
+                deduped.push(account.clone());
+            }
+            deduped.reverse();
+            map.insert("accounts".to_string(), Value::Array(deduped));
+        }
+        Some(other) => return Err(format!("accounts 应为数组，实际为 {}", json_type_name(other))),
+    }
+    fill_section_defaults(map, "push_config", json!(PushConfig::new()));
+    fill_section_defaults(map, "custom_config", json!(CustomConfig::new()));
+    // 旧版默认开启了内置的手机UA，没改过的按新默认关闭
+    if let Some(Value::Object(custom_config)) = map.get_mut("custom_config") {
+        if custom_config.get("custom_ua").and_then(Value::as_str) == Some(DEFAULT_CUSTOM_UA) {
+            custom_config.insert("open_custom_ua".to_string(), json!(false));
+        }
+    }
+    Ok(())
+}
+
+// v2：custom_config 里的两个预填手机号移到共享联系人中
+// 旧版预填手机号是全局的，不知道是谁的号码，所有账号都能选；联系人姓名留空，由用户自己填写
+fn migrate_v1_to_v2(map: &mut Map<String, Value>) -> Result<(), String> {
+    for key in ["preinput_phone1", "preinput_phone2"] {
+        let phone = match map.get_mut("custom_config").and_then(Value::as_object_mut).and_then(|c| c.remove(key)) {
+            Some(Value::String(phone)) => phone.trim().to_string(),
+            _ => continue,
+        };
+        if phone.is_empty() {
+            continue;
+        }
+        push_contact(map.entry("shared_contacts".to_string()).or_insert(json!([])), &phone);
+        log::info!("{} 已迁移到共享联系人", key);
+    }
+    Ok(())
+}
+
+// 添加一个姓名为空的联系人，手机号已存在时跳过
+fn push_contact(contacts: &mut Value, phone: &str) {
+    if let Value::Array(contacts) = contacts {
+        if !contacts.iter().any(|c| c.get("phone").and_then(Value::as_str) == Some(phone)) {
+            let is_default = contacts.is_empty();
+            contacts.push(json!({ "name": "", "phone": phone, "is_default": is_default }));
+        }
+    }
+}
+
+// 单独解析一个部分，失败时保留默认值并记录原因
+fn recover_section<T: DeserializeOwned>(map: &mut Map<String, Value>, key: &str, target: &mut T, issues: &mut Vec<String>) {
+    match map.remove(key) {
+        None | Some(Value::Null) => {}
+        Some(section) => match serde_json::from_value(section) {
+            Ok(section) => *target = section,
+            Err(e) => issues.push(format!("{} 无法读取，已使用默认值: {}", key, e)),
+        },
+    }
+}
+
+// 缺失的字段用默认值补齐，已有字段不动
+fn fill_section_defaults(map: &mut Map<String, Value>, key: &str, defaults: Value) {
+    let section = map.entry(key.to_string()).or_insert(Value::Null);
+    if !section.is_object() {
+        if !section.is_null() {
+            log::warn!("{} 格式错误，已重置为默认值", key);
+        }
+        *section = defaults;
+        return;
+    }
+    if let (Value::Object(section), Value::Object(defaults)) = (section, defaults) {
+        for (k, v) in defaults {
+            section.entry(k).or_insert(v);
+        }
+    }
+}
+
+//加载时校验，错误信息带上字段路径
+fn validate(map: &Map<String, Value>) -> Result<(), String> {
+    let mut errors = Vec::new();
+    if let Some(Value::Array(accounts)) = map.get("accounts") {
+        let mut seen = std::collections::HashSet::new();
+        for (idx, account) in accounts.iter().enumerate() {
+            let obj = match account.as_object() {
+                Some(obj) => obj,
+                None => {
+                    errors.push(format!("accounts[{}] 应为对象", idx));
+                    continue;
+                }
+            };
+            match obj.get("uid").and_then(Value::as_i64) {
+                Some(uid) => {
+                    if !seen.insert(uid) {
+                        errors.push(format!("accounts[{}].uid 重复: {}", idx, uid));
+                    }
+                }
+                None => errors.push(format!("accounts[{}].uid 缺失或不是整数", idx)),
+            }
+            for field in ["name", "cookie", "csrf"] {
+                if !obj.get(field).map(Value::is_string).unwrap_or(false) {
+                    errors.push(format!("accounts[{}].{} 缺失或不是字符串", idx, field));
+                }
+            }
+        }
+    }
+    for key in ["push_config", "custom_config", "watchlist", "calendar", "app_lock"] {
+        if let Some(section) = map.get(key) {
+            if !section.is_object() {
+                errors.push(format!("{} 应为对象，实际为 {}", key, json_type_name(section)));
+            }
+        }
+    }
+    if let Some(mode) = map.get("custom_config").and_then(|c| c.get("captcha_mode")) {
+        if mode.as_u64().is_none() {
+            errors.push(format!("custom_config.captcha_mode 应为非负整数，实际为 {}", mode));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("配置校验失败:\n{}", errors.join("\n")))
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "布尔值",
+        Value::Number(_) => "数字",
+        Value::String(_) => "字符串",
+        Value::Array(_) => "数组",
+        Value::Object(_) => "对象",
+    }
+}
+
+// 文件格式：base64(iv) + "%" + base64(AES-128-CBC密文)
+fn decrypt_config(raw_context: &str) -> Result<String, String> {
+    // 设置了启动口令的配置
+    if app_lock::is_locked_content(raw_context) {
+        return app_lock::decrypt(raw_context);
+    }
+    let (iv, encrypted) = raw_context
+        .split_once('%')
+        .ok_or_else(|| "配置文件格式错误".to_string())?;
+    let iv = BASE64.decode(iv.trim()).map_err(|e| format!("配置文件iv解码失败: {}", e))?;
+    let decoded = BASE64.decode(encrypted.trim()).map_err(|e| format!("配置文件解码失败: {}", e))?;
+    let decrypted = decrypt_data(iv, &decoded).map_err(|e| format!("配置文件解密失败: {}", e))?;
+    String::from_utf8(decrypted).map_err(|e| format!("配置文件内容不是utf8: {}", e))
+}
+
+fn encrypt_config(data: &[u8]) -> Result<String, String> {
+    if let Some(locked) = app_lock::encrypt(data)? {
+        return Ok(locked);
+    }
+    let (iv, encrypted) = encrypt_data(data).map_err(|e| format!("配置加密失败: {}", e))?;
+    Ok(BASE64.encode(&iv) + "%" + &BASE64.encode(&encrypted))
+}
+
+fn gen_machine_id_bytes_128b() -> Vec<u8> {
+    let id: String = machine_uid::get().unwrap();
+    id[..16].as_bytes().to_vec()
+}
+
+// 加密函数
+fn encrypt_data(data: &[u8]) -> Result<(Vec<u8>, Vec<u8>), block_modes::BlockModeError> {
+    type Aes128Cbc = Cbc<Aes128, Pkcs7>;
+    let mut iv = [0u8; 16];
+    rand::thread_rng().fill(&mut iv[..]); // 填充 16 字节的随机数据
+    let cipher = Aes128Cbc::new_from_slices(&gen_machine_id_bytes_128b(), &iv)
+        .map_err(|_| block_modes::BlockModeError)?; // 将 InvalidKeyIvLength 转换为 BlockModeError
+
+    Ok((iv.to_vec(), cipher.encrypt_vec(data)))
+}
+
+fn decrypt_data(iv: Vec<u8>, encrypted: &[u8]) -> Result<Vec<u8>, block_modes::BlockModeError> {
+    type Aes128Cbc = Cbc<Aes128, Pkcs7>;
+    let cipher = Aes128Cbc::new_from_slices(&gen_machine_id_bytes_128b(), &iv)
+        .map_err(|_| block_modes::BlockModeError)?; // 将 InvalidKeyIvLength 转换为 BlockModeError
+
+    cipher.decrypt_vec(encrypted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v0_default_ua_is_turned_off() {
+        let mut map = match json!({ "custom_config": { "open_custom_ua": true, "custom_ua": DEFAULT_CUSTOM_UA } }) {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        migrate(&mut map).unwrap();
+        assert_eq!(map["custom_config"]["open_custom_ua"], false);
+        assert_eq!(map["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+        assert_eq!(map["accounts"], json!([]));
+    }
+
+    #[test]
+    fn broken_sections_fall_back_to_defaults() {
+        let (config, issues) = AppConfig::recover_from_value(json!({
+            "schema_version": CURRENT_SCHEMA_VERSION,
+            "accounts": "not an array",
+            "push_config": 42,
+        }))
+        .unwrap();
+        assert!(config.accounts.is_empty());
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+    }
+
+    #[test]
+    fn v1_phones_move_to_shared_contacts() {
+        let mut map = match json!({
+            "schema_version": 1,
+            "accounts": [{ "uid": 1 }, { "uid": 2 }],
+            "custom_config": { "preinput_phone1": " 13800138000 ", "preinput_phone2": "13900139000" },
+        }) {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        migrate(&mut map).unwrap();
+        assert_eq!(
+            map["shared_contacts"],
+            json!([
+                { "name": "", "phone": "13800138000", "is_default": true },
+                { "name": "", "phone": "13900139000", "is_default": false },
+            ])
+        );
+        // 不再按位置分给账号
+        assert!(map["accounts"].as_array().unwrap().iter().all(|a| a.get("contacts").is_none()));
+        assert!(map["custom_config"].get("preinput_phone1").is_none());
+        assert!(map["custom_config"].get("preinput_phone2").is_none());
+    }
+
+    #[test]
+    fn v1_duplicate_and_empty_phones_are_skipped() {
+        let mut map = match json!({
+            "schema_version": 1,
+            "accounts": [],
+            "custom_config": { "preinput_phone1": "13800138000", "preinput_phone2": "13800138000" },
+        }) {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        migrate(&mut map).unwrap();
+        assert_eq!(map["shared_contacts"].as_array().unwrap().len(), 1);
+
+        let mut map = match json!({ "schema_version": 1, "custom_config": { "preinput_phone1": "  " } }) {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        migrate(&mut map).unwrap();
+        assert!(map.get("shared_contacts").is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::account::Account;
+
+//联系人：非实名票的联系人、短信登录的手机号从这里选
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Contact {
+    pub name: String,
+    pub phone: String,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+impl Contact {
+    //下拉列表里显示的文字
+    pub fn label(&self) -> String {
+        let name = if self.name.trim().is_empty() { "未命名" } else { self.name.trim() };
+        if self.is_default {
+            format!("{} {}（默认）", name, self.phone)
+        } else {
+            format!("{} {}", name, self.phone)
+        }
+    }
+}
+
+//默认联系人，没有设置默认时取第一个
+pub fn default_contact(contacts: &[Contact]) -> Option<&Contact> {
+    contacts.iter().find(|c| c.is_default).or_else(|| contacts.first())
+}
+
+//设为默认（同时只有一个默认联系人）
+pub fn set_default(contacts: &mut [Contact], index: usize) {
+    for (i, contact) in contacts.iter_mut().enumerate() {
+        contact.is_default = i == index;
+    }
+}
+
+//添加联系人，手机号已存在时更新姓名；第一个联系人自动设为默认
+pub fn add_contact(contacts: &mut Vec<Contact>, name: &str, phone: &str) -> Result<(), String> {
+    let name = name.trim();
+    let phone = phone.trim();
+    if phone.is_empty() {
+        return Err("手机号不能为空".to_string());
+    }
+    if !phone.chars().all(|c| c.is_ascii_digit() || c == '+' || c == '-') {
+        return Err(format!("手机号格式错误: {}", phone));
+    }
+    match contacts.iter_mut().find(|c| c.phone == phone) {
+        Some(contact) => contact.name = name.to_string(),
+        None => contacts.push(Contact {
+            name: name.to_string(),
+            phone: phone.to_string(),
+            is_default: contacts.is_empty(),
+        }),
+    }
+    Ok(())
+}
+
+//删除联系人，删掉默认联系人后把第一个设为默认
+pub fn remove_contact(contacts: &mut Vec<Contact>, index: usize) {
+    if index >= contacts.len() {
+        return;
+    }
+    let was_default = contacts.remove(index).is_default;
+    if was_default {
+        if let Some(first) = contacts.first_mut() {
+            first.is_default = true;
+        }
+    }
+}
+
+//所有账号的联系人加上共享联系人（按手机号去重），短信登录时选手机号用
+pub fn all_contacts(accounts: &[Account], shared: &[Contact]) -> Vec<Contact> {
+    dedup_by_phone(accounts.iter().flat_map(|a| a.contacts.iter()).chain(shared.iter()))
+}
+
+//账号自己的联系人在前，再加上共享联系人（按手机号去重），下单选联系人用
+pub fn account_contacts(account: Option<&Account>, shared: &[Contact]) -> Vec<Contact> {
+    let mut contacts = dedup_by_phone(account.into_iter().flat_map(|a| a.contacts.iter()).chain(shared.iter()));
+    // 账号有自己的默认联系人时，共享联系人的默认标记不再生效
+    if let Some(first_default) = contacts.iter().position(|c| c.is_default) {
+        set_default(&mut contacts, first_default);
+    }
+    contacts
+}
+
+fn dedup_by_phone<'a>(source: impl Iterator<Item = &'a Contact>) -> Vec<Contact> {
+    let mut contacts: Vec<Contact> = Vec::new();
+    for contact in source {
+        if !contacts.iter().any(|c| c.phone == contact.phone) {
+            contacts.push(contact.clone());
+        }
+    }
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(name: &str, phone: &str, is_default: bool) -> Contact {
+        Contact { name: name.to_string(), phone: phone.to_string(), is_default }
+    }
+
+    #[test]
+    fn shared_contacts_follow_account_contacts() {
+        let shared = vec![contact("", "13800138000", true), contact("", "13900139000", false)];
+        let mut account: Account = serde_json::from_value(serde_json::json!({
+            "uid": 1,
+            "name": "测试账号",
+            "level": "6",
+            "cookie": "",
+            "csrf": "",
+            "is_login": true,
+            "account_status": "正常",
+            "vip_label": "",
+            "is_active": false,
+            "avatar_url": null,
+        }))
+        .unwrap();
+
+        // 没有自己的联系人时用共享联系人的默认
+        let contacts = account_contacts(Some(&account), &shared);
+        assert_eq!(default_contact(&contacts).unwrap().phone, "13800138000");
+
+        account.contacts = vec![contact("张三", "13700137000", true), contact("李四", "13900139000", false)];
+        let contacts = account_contacts(Some(&account), &shared);
+        let phones: Vec<&str> = contacts.iter().map(|c| c.phone.as_str()).collect();
+        assert_eq!(phones, vec!["13700137000", "13900139000", "13800138000"]);
+        assert_eq!(contacts.iter().filter(|c| c.is_default).count(), 1);
+        assert_eq!(default_contact(&contacts).unwrap().name, "张三");
+
+        assert_eq!(account_contacts(None, &shared), shared);
+    }
+}
@@ -2,11 +2,14 @@
 use reqwest::cookie::Jar;
 use cookie::Cookie;
 use std::collections::HashMap;
+use reqwest::ResponseBuilderExt;
 use std::sync::{Arc, Mutex}; //?有用到吗
 use rand::seq::SliceRandom;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 use crate::web_ck_obfuscated::{*};
+use crate::cookie_refresh::set_cookie_pairs;
+use crate::http_recorder::{self, HarContent, HarEntry, HarPostData, HarRequest, HarResponse};
 
 
 #[derive(Debug, Clone)]
@@ -487,20 +490,130 @@ impl CookieManager {
     
     //处理响应中的 cookie
     pub async fn execute(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, reqwest::Error> {
+        // 开启了记录/回放时走单独的流程
+        if http_recorder::is_active() {
+            return self.execute_recorded(request).await;
+        }
         let response = request.send().await?;
         
         // 从响应中提取并更新 cookies
-        let cookies = response.headers().get_all(reqwest::header::SET_COOKIE) ;
-            for cookie_header in cookies {
-                if let Ok(cookie_str) = cookie_header.to_str() {
-                    log::debug!("从响应中获取到 cookie: {}", cookie_str);
-                    self.update_cookies(cookie_str);
-                }
-            }
-        
+        self.apply_set_cookies(response.headers());
         
         Ok(response)
     }
+
+    //只取 Set-Cookie 的 key=value 部分，Path/Domain/Expires 等属性不能当成cookie
+    fn apply_set_cookies(&self, headers: &reqwest::header::HeaderMap) {
+        for pair in set_cookie_pairs(headers) {
+            log::debug!("从响应中获取到 cookie: {}", pair);
+            self.update_cookies(&pair);
+        }
+    }
+
+    //记录/回放：回放时直接返回记录中的响应；记录时读出完整响应写入记录后再重新组装返回
+    async fn execute_recorded(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, reqwest::Error> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+
+        if http_recorder::is_replaying() {
+            return Ok(match http_recorder::replay(&method, &url) {
+                Some(entry) => {
+                    log::debug!("回放请求: {} {}", method, url);
+                    replay_response(&url, &entry.response)
+                }
+                None => {
+                    log::warn!("回放记录中没有该请求: {} {}", method, url);
+                    let content = HarContent {
+                        size: 0,
+                        mime_type: "application/json".to_string(),
+                        text: r#"{"code":-404,"message":"回放记录中没有该请求"}"#.to_string(),
+                    };
+                    replay_response(&url, &HarResponse { status: 404, headers: Vec::new(), content })
+                }
+            });
+        }
+
+        let request_headers = http_recorder::redact_headers(
+            request.headers().iter().map(|(k, v)| (k.as_str(), v.to_str().unwrap_or(""))),
+        );
+        let post_data = request.body().and_then(|body| body.as_bytes()).map(|bytes| HarPostData {
+            mime_type: request
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string(),
+            text: http_recorder::redact_body(&String::from_utf8_lossy(bytes)),
+        });
+        let started = chrono::Local::now();
+        let response = client.execute(request).await?;
+
+        self.apply_set_cookies(response.headers());
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let response_url = response.url().clone();
+        let bytes = response.bytes().await?;
+
+        let text = String::from_utf8_lossy(&bytes);
+        http_recorder::record(HarEntry {
+            started_date_time: started.to_rfc3339(),
+            time: (chrono::Local::now() - started).num_milliseconds().max(0) as u64,
+            request: HarRequest {
+                method,
+                url: http_recorder::redact_url(&url),
+                headers: request_headers,
+                post_data,
+            },
+            response: HarResponse {
+                status: status.as_u16(),
+                headers: http_recorder::redact_headers(headers.iter().map(|(k, v)| (k.as_str(), v.to_str().unwrap_or("")))),
+                content: HarContent {
+                    size: bytes.len(),
+                    mime_type: headers
+                        .get(reqwest::header::CONTENT_TYPE)
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("")
+                        .to_string(),
+                    text: http_recorder::redact_body(&text),
+                },
+            },
+        });
+
+        let mut builder = http::Response::builder().status(status).version(version).url(response_url);
+        for (name, value) in headers.iter() {
+            builder = builder.header(name, value);
+        }
+        // 响应已读完，组装失败时退回只带正文的响应，不能让抢票线程panic
+        Ok(builder.body(bytes.clone()).map(reqwest::Response::from).unwrap_or_else(|e| {
+            log::error!("重新组装响应失败: {}", e);
+            let mut fallback = http::Response::new(bytes);
+            *fallback.status_mut() = status;
+            reqwest::Response::from(fallback)
+        }))
+    }
+}
+
+//用记录中的响应构造 reqwest::Response，解析流程与真实请求相同
+fn replay_response(url: &str, recorded: &HarResponse) -> reqwest::Response {
+    let mut builder = http::Response::builder().status(recorded.status);
+    if let Ok(url) = reqwest::Url::parse(url) {
+        builder = builder.url(url);
+    }
+    for header in &recorded.headers {
+        // 记录的是解压后的正文
+        let name = header.name.to_ascii_lowercase();
+        if name == "content-encoding" || name == "content-length" {
+            continue;
+        }
+        builder = builder.header(header.name.as_str(), header.value.as_str());
+    }
+    builder
+        .body(recorded.content.text.clone())
+        .map(reqwest::Response::from)
+        .unwrap_or_else(|_| reqwest::Response::from(http::Response::new(recorded.content.text.clone())))
 }
 
 
@@ -0,0 +1,55 @@
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Oaep, RsaPublicKey};
+use sha2::Sha256;
+
+// 每隔多久检查一次是否需要刷新登录态
+pub const REFRESH_CHECK_INTERVAL_SECS: u64 = 6 * 3600;
+
+// b站 web 端刷新 cookie 时生成 CorrespondPath 用的公钥
+const CORRESPOND_PUBLIC_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQKBgQDLgd2OAkcGVtoE3ThUREbio0Eg
+Uc/prcajMKXvkCKFCWhJYJcLkcM2DKKcSeFpD/j6Boy538YXnR6VhcuUJOhH2x71
+nzPjfdTcqMz7djHum0qSZA0AyCBDABUqCrfNgCiJ00Ra7GmRj+YCK1NJEuewlb40
+JNrRuoEUXpabUzGB8QIDAQAB
+-----END PUBLIC KEY-----";
+
+//刷新后的登录凭据
+#[derive(Clone, Debug)]
+pub struct RefreshedCookie {
+    pub cookie: String,
+    pub csrf: String,
+    pub refresh_token: String,
+}
+
+//CorrespondPath：用公钥加密 "refresh_<毫秒时间戳>" 后转十六进制
+pub fn correspond_path(timestamp_ms: i64) -> Result<String, String> {
+    let key = RsaPublicKey::from_public_key_pem(CORRESPOND_PUBLIC_KEY).map_err(|e| format!("解析公钥失败: {}", e))?;
+    let mut rng = rand::thread_rng();
+    let encrypted = key
+        .encrypt(&mut rng, Oaep::new::<Sha256>(), format!("refresh_{}", timestamp_ms).as_bytes())
+        .map_err(|e| format!("生成CorrespondPath失败: {}", e))?;
+    Ok(hex::encode(encrypted))
+}
+
+//从 correspond 页面中取出 refresh_csrf（<div id="1-name">xxx</div>）
+pub fn parse_refresh_csrf(html: &str) -> Option<String> {
+    let start = html.find("<div id=\"1-name\">")? + "<div id=\"1-name\">".len();
+    let end = html[start..].find("</div>")? + start;
+    let refresh_csrf = html[start..end].trim();
+    if refresh_csrf.is_empty() {
+        None
+    } else {
+        Some(refresh_csrf.to_string())
+    }
+}
+
+//从响应头中取出 Set-Cookie 的 key=value 部分
+pub fn set_cookie_pairs(headers: &reqwest::header::HeaderMap) -> Vec<String> {
+    headers
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .map(|cookie| cookie.split(';').next().unwrap_or("").trim().to_string())
+        .filter(|pair| pair.contains('='))
+        .collect()
+}
@@ -0,0 +1,308 @@
+use std::backtrace::Backtrace;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Value};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::account::Account;
+use crate::config::AppConfig;
+use crate::data_dir;
+use crate::http_recorder::{REDACTED, SECRET_FIELDS};
+
+// 界面定时写入的运行状态，崩溃时随报告一起保存
+static SNAPSHOT: Lazy<Mutex<Option<StateSnapshot>>> = Lazy::new(|| Mutex::new(None));
+static APP_VERSION: Lazy<Mutex<String>> = Lazy::new(|| Mutex::new(String::new()));
+
+//运行状态摘要（已脱敏）
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StateSnapshot {
+    pub active_profile: String,
+    pub locked: bool,
+    pub accounts: Vec<AccountSummary>,
+    pub active_tasks: Vec<String>, //任务类型和状态
+    pub config: Value,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AccountSummary {
+    pub uid: String, //只保留后4位
+    pub is_login: bool,
+    pub is_active: bool,
+    pub status: String,
+    pub has_cookie: bool,
+}
+
+impl AccountSummary {
+    pub fn from_account(account: &Account) -> Self {
+        Self {
+            uid: mask_uid(account.uid),
+            is_login: account.is_login,
+            is_active: account.is_active,
+            status: account.account_status.clone(),
+            has_cookie: !account.cookie.is_empty(),
+        }
+    }
+}
+
+fn mask_uid(uid: i64) -> String {
+    let uid = uid.to_string();
+    let keep = uid.len().saturating_sub(4);
+    format!("{}{}", "*".repeat(keep), &uid[keep..])
+}
+
+pub fn set_app_version(version: &str) {
+    *APP_VERSION.lock().unwrap() = version.to_string();
+}
+
+pub fn update_snapshot(snapshot: StateSnapshot) {
+    if let Ok(mut current) = SNAPSHOT.try_lock() {
+        *current = Some(snapshot);
+    }
+}
+
+//配置摘要：账号只保留数量，字符串只标记是否填写，数字和开关原样保留
+pub fn sanitize_config(config: &AppConfig) -> Value {
+    let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+    if let Some(map) = value.as_object_mut() {
+        map.insert("accounts".to_string(), json!({ "count": config.accounts.len() }));
+    }
+    mask_strings(&mut value);
+    value
+}
+
+fn mask_strings(value: &mut Value) {
+    match value {
+        Value::String(text) => {
+            if !text.is_empty() {
+                *text = "<已填写>".to_string();
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(mask_strings),
+        Value::Object(map) => map.values_mut().for_each(mask_strings),
+        _ => {}
+    }
+}
+
+//日志脱敏：敏感字段的值、手机号、身份证号和较长的令牌串
+pub fn redact_log_line(line: &str) -> String {
+    let line = redact_secret_values(line);
+    redact_long_runs(&line)
+}
+
+// 日志里额外需要脱敏的字段：收货地址的各部分（Debug输出和接口json中都会出现）
+const LOG_SECRET_FIELDS: [&str; 5] = ["prov", "city", "area", "address", "address_detail"];
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_FIELDS.contains(&key.as_str()) || LOG_SECRET_FIELDS.contains(&key.as_str())
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_value_end(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ';' | '&' | ',' | '"' | '\'' | '}' | ']' | ')')
+}
+
+// 替换 key=value、key: value、"key":"value"、Debug输出 key: Some("value") 中的值
+// 只匹配完整的字段名，hotel、screen_name 之类包含敏感词的字段不处理
+fn redact_secret_values(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut pos = 0;
+    let mut cursor = 0;
+    while let Some(c) = line[cursor..].chars().next() {
+        if !is_key_char(c) {
+            cursor += c.len_utf8();
+            continue;
+        }
+        let rest = &line[cursor..];
+        let key_len = rest.find(|c| !is_key_char(c)).unwrap_or(rest.len());
+        let key = &rest[..key_len];
+        cursor += key_len;
+        if !is_secret_key(key) {
+            continue;
+        }
+        let rest = &line[cursor..];
+        let after_key = cursor + rest.len() - rest.trim_start_matches(['"', '\'', '\\']).len();
+        let rest = &line[after_key..];
+        if !rest.starts_with('=') && !rest.starts_with(':') {
+            continue;
+        }
+        let mut start = after_key + 1;
+        let rest = &line[start..];
+        start += rest.len() - rest.trim_start_matches(' ').len();
+        if line[start..].starts_with("Some(") {
+            start += "Some(".len();
+        }
+        let rest = &line[start..];
+        let value = rest.trim_start_matches(['"', '\'', '\\']);
+        let quote = rest[..rest.len() - value.len()].chars().find(|c| *c != '\\');
+        start += rest.len() - value.len();
+        // 带引号的值一直取到对应的引号，姓名、地址中可能有空格
+        let mut value_len = match quote {
+            Some(quote) => value.find(quote).unwrap_or(value.len()),
+            None => value.find(is_value_end).unwrap_or(value.len()),
+        };
+        value_len -= value[..value_len].len() - value[..value_len].trim_end_matches('\\').len();
+        if value_len > 0 {
+            out.push_str(&line[pos..start]);
+            out.push_str(REDACTED);
+            pos = start + value_len;
+        }
+        cursor = start + value_len;
+    }
+    out.push_str(&line[pos..]);
+    out
+}
+
+// 手机号、身份证号打码；32位以上的字母数字串（token、cookie值等）整体替换
+fn redact_long_runs(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut run = String::new();
+    let flush = |run: &mut String, out: &mut String| {
+        let digits = run.chars().all(|c| c.is_ascii_digit());
+        let id_like = run.len() == 18 && run[..17].chars().all(|c| c.is_ascii_digit());
+        if digits && run.len() == 11 && run.starts_with('1') {
+            out.push_str(&format!("{}****{}", &run[..3], &run[7..]));
+        } else if id_like {
+            out.push_str(&format!("{}************{}", &run[..4], &run[16..]));
+        } else if run.len() >= 32 {
+            out.push_str(REDACTED);
+        } else {
+            out.push_str(run);
+        }
+        run.clear();
+    };
+    for c in line.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '%' {
+            run.push(c);
+        } else {
+            flush(&mut run, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut run, &mut out);
+    out
+}
+
+fn reports_dir() -> PathBuf {
+    data_dir::data_dir().join("crash_reports")
+}
+
+fn environment_info() -> String {
+    format!(
+        "版本: {}\n系统: {} {}\n时间: {}\n数据目录: {}\n",
+        APP_VERSION.try_lock().map(|v| v.clone()).unwrap_or_default(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        data_dir::data_dir().display(),
+    )
+}
+
+fn state_json() -> String {
+    let snapshot = SNAPSHOT.try_lock().ok().and_then(|s| s.clone());
+    // 崩溃的线程可能正持有这些锁，只尝试获取，取不到时留空
+    let drift = crate::schema_drift::try_reports();
+    let recorder = crate::http_recorder::try_status().map(|status| format!("{:?}", status));
+    let state = json!({
+        "snapshot": snapshot,
+        "api_drift": drift,
+        "http_recorder": recorder,
+    });
+    serde_json::to_string_pretty(&state).unwrap_or_default()
+}
+
+fn redacted_logs() -> String {
+    let mut logs: Vec<String> = crate::record_log::recent_logs().iter().map(|line| redact_log_line(line)).collect();
+    if let Some(file) = crate::record_log::current_log_file() {
+        logs.insert(0, format!("（完整日志文件: {}）", redact_log_line(&file)));
+    }
+    logs.join("\n")
+}
+
+fn write_bundle(name: &str, files: &[(&str, String)]) -> Result<PathBuf, String> {
+    let dir = reports_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    let path = dir.join(format!("{}_{}.zip", name, chrono::Local::now().format("%Y%m%d_%H%M%S")));
+    let file = File::create(&path).map_err(|e| format!("创建文件失败: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (file_name, content) in files {
+        zip.start_file(*file_name, options).map_err(|e| format!("写入压缩包失败: {}", e))?;
+        zip.write_all(content.as_bytes()).map_err(|e| format!("写入压缩包失败: {}", e))?;
+    }
+    zip.finish().map_err(|e| format!("写入压缩包失败: {}", e))?;
+    Ok(path)
+}
+
+//panic时生成崩溃报告：调用栈、版本、脱敏后的最近日志和运行状态
+pub fn write_crash_bundle(panic_message: &str) -> Result<PathBuf, String> {
+    let thread = std::thread::current();
+    let crash = format!(
+        "{}线程: {}\n{}\n\n调用栈:\n{}\n",
+        environment_info(),
+        thread.name().unwrap_or("未命名"),
+        redact_log_line(panic_message),
+        Backtrace::force_capture(),
+    );
+    write_bundle("crash", &[("crash.txt", crash), ("recent_logs.txt", redacted_logs()), ("state.json", state_json())])
+}
+
+//帮助页手动生成的诊断包，提issue时附上
+pub fn write_diagnostic_bundle() -> Result<PathBuf, String> {
+    write_bundle("diagnostic", &[("environment.txt", environment_info()), ("recent_logs.txt", redacted_logs()), ("state.json", state_json())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_whole_keys_only() {
+        assert_eq!(redact_log_line("tel=13800138000&hotel=abc"), format!("tel={}&hotel=abc", REDACTED));
+        assert_eq!(redact_log_line("screen_name: 第一场"), "screen_name: 第一场");
+        assert_eq!(redact_log_line("csrf: abcdef"), format!("csrf: {}", REDACTED));
+    }
+
+    #[test]
+    fn redacts_debug_buyer() {
+        let line = r#"非实名购票人信息: Some(NoBindBuyerInfo { name: "张 三", tel: "13800138000", uid: 12345 })"#;
+        let redacted = redact_log_line(line);
+        assert!(!redacted.contains("张"), "{}", redacted);
+        assert!(!redacted.contains("13800138000"), "{}", redacted);
+        assert!(redacted.contains("uid: 12345"), "{}", redacted);
+    }
+
+    #[test]
+    fn redacts_optional_and_address_fields() {
+        let line = r#"DeliveryAddress { id: 7, name: "李四", phone: "13900139000", prov: "北京市", city: "北京市", area: "东城区", addr: "某街道 1号" } buyer: Some("王五")"#;
+        let redacted = redact_log_line(line);
+        for secret in ["李四", "13900139000", "北京市", "东城区", "某街道", "王五"] {
+            assert!(!redacted.contains(secret), "{} 未脱敏: {}", secret, redacted);
+        }
+        assert!(redacted.contains("id: 7"), "{}", redacted);
+    }
+
+    #[test]
+    fn redacts_json_in_logs() {
+        let line = r#"下单data：{"buyer_info":"[{\"name\":\"张三\",\"personal_id\":\"110101199001011234\"}]","count":1}"#;
+        let redacted = redact_log_line(line);
+        assert!(!redacted.contains("张三"), "{}", redacted);
+        assert!(!redacted.contains("110101199001011234"), "{}", redacted);
+        assert!(redacted.contains(r#""count":1"#), "{}", redacted);
+    }
+
+    #[test]
+    fn masks_phone_and_id_numbers_without_keys() {
+        assert_eq!(redact_log_line("手机号 13800138000"), "手机号 138****8000");
+        assert_eq!(redact_log_line("证件 11010119900101123X"), "证件 1101************3X");
+    }
+}
@@ -0,0 +1,171 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+
+// 数据目录环境变量，优先级低于命令行 --data-dir
+pub const DATA_DIR_ENV: &str = "BTR_DATA_DIR";
+const APP_DIR_NAME: &str = "bili_ticket_rush";
+// 迁移完成标记，存在则不再从工作目录迁移
+const MIGRATED_MARKER: &str = ".migrated";
+// 旧版本放在工作目录下的文件/目录
+// 字体用 include_bytes! 编进程序，运行时不读 resources/fonts，不需要迁移
+const LEGACY_ENTRIES: [&str; 4] = ["config", "config.json", "Log", "permissions"];
+
+static DATA_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+//初始化数据目录，只在启动时调用一次；优先级：命令行 > 环境变量 > 平台默认目录
+pub fn init(cli_override: Option<PathBuf>) -> &'static Path {
+    DATA_DIR.get_or_init(|| {
+        let dir = cli_override
+            .or_else(|| env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()).map(PathBuf::from))
+            .unwrap_or_else(default_data_dir);
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("无法创建数据目录 {:?}: {}", dir, e);
+        }
+        dir
+    })
+}
+
+//从命令行参数中解析 --data-dir <path> 或 --data-dir=<path>
+pub fn parse_cli_override<I: IntoIterator<Item = String>>(args: I) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--data-dir" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--data-dir=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+pub fn data_dir() -> &'static Path {
+    init(None)
+}
+
+//当前配置档案的配置文件
+pub fn config_path() -> PathBuf {
+    crate::profile::config_path_of(&crate::profile::active_profile())
+}
+
+pub fn legacy_json_config_path() -> PathBuf {
+    crate::profile::profile_dir(&crate::profile::active_profile()).join("config.json")
+}
+
+pub fn log_dir() -> PathBuf {
+    data_dir().join("Log")
+}
+
+//本地保存的权限令牌
+pub fn permissions_path() -> PathBuf {
+    data_dir().join("permissions")
+}
+
+pub fn cache_dir() -> PathBuf {
+    data_dir().join("cache")
+}
+
+// Windows: %APPDATA%\bili_ticket_rush
+// macOS: ~/Library/Application Support/bili_ticket_rush
+// Linux: $XDG_DATA_HOME/bili_ticket_rush 或 ~/.local/share/bili_ticket_rush
+fn default_data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+    };
+    match base {
+        Some(base) => base.join(APP_DIR_NAME),
+        // 找不到用户目录时退回到工作目录，保持旧版本行为
+        None => PathBuf::from("."),
+    }
+}
+
+//把旧版本留在工作目录下的配置、日志迁移到数据目录，只执行一次
+//返回迁移记录，由调用方在日志初始化后输出
+pub fn migrate_from_cwd() -> Vec<String> {
+    let mut messages = Vec::new();
+    let dir = data_dir();
+    let marker = dir.join(MIGRATED_MARKER);
+    if marker.exists() {
+        return messages;
+    }
+    let cwd = match env::current_dir() {
+        Ok(cwd) => cwd,
+        Err(e) => {
+            messages.push(format!("无法获取工作目录，跳过迁移: {}", e));
+            return messages;
+        }
+    };
+    let same_dir = match (cwd.canonicalize(), dir.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    // 有迁移失败时不写标记，下次启动重试
+    let mut all_moved = true;
+    if !same_dir {
+        for entry in LEGACY_ENTRIES {
+            let from = cwd.join(entry);
+            let to = dir.join(entry);
+            if !from.exists() {
+                continue;
+            }
+            if to.exists() {
+                messages.push(format!("数据目录中已存在 {}，保留工作目录中的旧文件", entry));
+                continue;
+            }
+            match move_path(&from, &to) {
+                Ok(_) => messages.push(format!("已迁移 {:?} -> {:?}", from, to)),
+                Err(e) => {
+                    all_moved = false;
+                    messages.push(format!("迁移 {:?} 失败: {}，下次启动时重试", from, e));
+                }
+            }
+        }
+    }
+    if !all_moved {
+        return messages;
+    }
+    if let Err(e) = fs::write(&marker, b"") {
+        messages.push(format!("写入迁移标记失败: {}", e));
+    }
+    messages
+}
+
+// 先尝试重命名，跨盘时退回到复制后删除
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_recursive(from, to)?;
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
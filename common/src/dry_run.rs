@@ -0,0 +1,178 @@
+use crate::preflight::{CheckLevel, PreflightReport};
+use crate::ticket::BuyerInfo;
+
+//演练日志统一前缀，方便与真实抢票区分
+pub const DRY_RUN_TAG: &str = "[演练]";
+
+//演练中的一步
+#[derive(Clone, Debug)]
+pub struct DryRunStep {
+    pub level: CheckLevel,
+    pub title: String,
+    pub detail: String,
+}
+
+//演练报告：走完下单前的流程，记录本应提交的订单内容
+#[derive(Clone, Debug, Default)]
+pub struct DryRunReport {
+    pub uid: i64,
+    pub project_id: String,
+    pub project_name: String,
+    pub screen_id: String,
+    pub screen_name: String,
+    pub ticket_id: String,
+    pub ticket_desc: String,
+    pub price: i64, //单价（分）
+    pub count: i16,
+    pub express_fee: i64,
+    pub buyers: Vec<String>,
+    pub steps: Vec<DryRunStep>,
+    pub finished_at: i64,
+}
+
+impl DryRunReport {
+    pub fn new(uid: i64, project_id: &str, screen_id: &str, ticket_id: &str, count: i16) -> Self {
+        Self {
+            uid,
+            project_id: project_id.to_string(),
+            screen_id: screen_id.to_string(),
+            ticket_id: ticket_id.to_string(),
+            count,
+            ..Default::default()
+        }
+    }
+
+    //记录一步并写日志
+    pub fn push(&mut self, level: CheckLevel, title: &str, detail: impl Into<String>) {
+        let detail = detail.into();
+        match level {
+            CheckLevel::Pass => log::info!("{} {}：{}", DRY_RUN_TAG, title, detail),
+            CheckLevel::Warning => log::warn!("{} {}：{}", DRY_RUN_TAG, title, detail),
+            CheckLevel::Error => log::error!("{} {}：{}", DRY_RUN_TAG, title, detail),
+        }
+        self.steps.push(DryRunStep { level, title: title.to_string(), detail });
+    }
+
+    pub fn add_preflight(&mut self, report: &PreflightReport) {
+        for item in &report.items {
+            self.push(item.level, &format!("检查·{}", item.title), item.detail.clone());
+        }
+    }
+
+    pub fn set_buyers(&mut self, buyers: &[BuyerInfo]) {
+        self.buyers = buyers
+            .iter()
+            .map(|b| format!("{} {}", b.name, mask_id(&b.personal_id)))
+            .collect();
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.steps.iter().any(|s| s.level == CheckLevel::Error)
+    }
+
+    //应付总额（分）
+    pub fn total(&self) -> i64 {
+        self.price * self.count.max(0) as i64 + self.express_fee
+    }
+
+    //本应提交的订单内容
+    pub fn order_summary(&self) -> String {
+        let mut lines = vec![
+            format!("项目：{}（{}）", self.project_name, self.project_id),
+            format!("场次：{}（{}）", self.screen_name, self.screen_id),
+            format!("票种：{}（{}）单价 ¥{:.2}", self.ticket_desc, self.ticket_id, self.price as f64 / 100.0),
+            format!("数量：{}张", self.count),
+        ];
+        if self.express_fee > 0 {
+            lines.push(format!("快递费：¥{:.2}", self.express_fee as f64 / 100.0));
+        }
+        lines.push(format!("合计：¥{:.2}", self.total() as f64 / 100.0));
+        if !self.buyers.is_empty() {
+            lines.push(format!("购票人：{}", self.buyers.join("、")));
+        }
+        lines.join("\n")
+    }
+}
+
+fn mask_id(id: &str) -> String {
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() <= 6 {
+        return id.to_string();
+    }
+    let prefix: String = chars[..3].iter().collect();
+    let suffix: String = chars[chars.len() - 3..].iter().collect();
+    format!("{}******{}", prefix, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preflight::CheckItem;
+
+    fn buyer(name: &str, personal_id: &str) -> BuyerInfo {
+        BuyerInfo {
+            id: 1,
+            uid: 1,
+            personal_id: personal_id.to_string(),
+            name: name.to_string(),
+            tel: String::new(),
+            id_type: 0,
+            is_default: 0,
+            id_card_front: String::new(),
+            id_card_back: String::new(),
+            verify_status: 1,
+            isBuyerInfoVerified: true,
+            isBuyerValid: true,
+        }
+    }
+
+    #[test]
+    fn total_includes_express_fee() {
+        let mut report = DryRunReport::new(1, "100", "200", "300", 2);
+        report.price = 38000;
+        assert_eq!(report.total(), 76000);
+        report.express_fee = 1200;
+        assert_eq!(report.total(), 77200);
+        // 异常的负数量不应算出负价
+        report.count = -1;
+        assert_eq!(report.total(), 1200);
+    }
+
+    #[test]
+    fn summary_masks_buyer_ids() {
+        let mut report = DryRunReport::new(1, "100", "200", "300", 2);
+        report.project_name = "测试项目".to_string();
+        report.price = 38000;
+        report.set_buyers(&[buyer("张三", "110101199001011234"), buyer("李四", "12345")]);
+        let summary = report.order_summary();
+        assert!(summary.contains("项目：测试项目（100）"));
+        assert!(summary.contains("合计：¥760.00"));
+        assert!(summary.contains("购票人：张三 110******234、李四 12345"));
+        assert!(!summary.contains("110101199001011234"));
+        assert!(!summary.contains("快递费"));
+
+        report.express_fee = 1000;
+        assert!(report.order_summary().contains("快递费：¥10.00"));
+    }
+
+    #[test]
+    fn preflight_items_become_steps() {
+        let mut report = DryRunReport::new(1, "100", "200", "300", 1);
+        report.push(CheckLevel::Pass, "加载项目", "ok");
+        assert!(!report.has_errors());
+
+        let preflight = PreflightReport {
+            items: vec![CheckItem {
+                level: CheckLevel::Error,
+                title: "购票人",
+                detail: "未选择购票人".to_string(),
+                fix: None,
+            }],
+        };
+        report.add_preflight(&preflight);
+        assert!(report.has_errors());
+        let step = report.steps.last().unwrap();
+        assert_eq!(step.title, "检查·购票人");
+        assert_eq!(step.detail, "未选择购票人");
+    }
+}
@@ -0,0 +1,167 @@
+use aes::Aes256;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use block_modes::block_padding::Pkcs7;
+use block_modes::{BlockMode, Cbc};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+pub const MIN_PASSPHRASE_LEN: usize = 6;
+
+type Aes256Cbc = Cbc<Aes256, Pkcs7>;
+type HmacSha256 = Hmac<Sha256>;
+
+//口令加密的文件：argon2id 由口令派生密钥，AES-256-CBC 加密，HMAC-SHA256 校验
+//账号包和加密配置共用，format 区分用途并参与校验
+#[derive(Serialize, Deserialize)]
+pub struct Envelope {
+    pub format: String,
+    pub version: u32,
+    kdf: String,
+    salt: String,
+    iv: String,
+    data: String,
+    mac: String,
+}
+
+impl Envelope {
+    //按 format 识别，不是该用途的文件返回 None
+    pub fn parse(content: &str, format: &str) -> Option<Self> {
+        let envelope: Envelope = serde_json::from_str(content.trim()).ok()?;
+        if envelope.format == format {
+            Some(envelope)
+        } else {
+            None
+        }
+    }
+
+    pub fn salt(&self) -> Result<Vec<u8>, String> {
+        BASE64.decode(&self.salt).map_err(|e| format!("文件解码失败: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("加密文件序列化失败: {}", e))
+    }
+}
+
+pub fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("口令至少{}位", MIN_PASSPHRASE_LEN));
+    }
+    Ok(())
+}
+
+//由口令派生的密钥，派生较慢（argon2），需要重复使用时保存下来
+#[derive(Clone)]
+pub struct EnvelopeKey {
+    salt: Vec<u8>,
+    enc_key: [u8; 32],
+    mac_key: [u8; 32],
+}
+
+impl EnvelopeKey {
+    pub fn derive(passphrase: &str, salt: Vec<u8>) -> Result<Self, String> {
+        let mut output = [0u8; 64];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut output)
+            .map_err(|e| format!("密钥派生失败: {}", e))?;
+        let mut enc_key = [0u8; 32];
+        let mut mac_key = [0u8; 32];
+        enc_key.copy_from_slice(&output[..32]);
+        mac_key.copy_from_slice(&output[32..]);
+        Ok(Self { salt, enc_key, mac_key })
+    }
+
+    //随机盐派生新密钥
+    pub fn generate(passphrase: &str) -> Result<Self, String> {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill(&mut salt[..]);
+        Self::derive(passphrase, salt.to_vec())
+    }
+
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    fn mac(&self, format: &str, iv: &[u8], data: &[u8]) -> Result<HmacSha256, String> {
+        let mut mac = HmacSha256::new_from_slice(&self.mac_key).map_err(|e| format!("校验初始化失败: {}", e))?;
+        mac.update(format.as_bytes());
+        mac.update(iv);
+        mac.update(data);
+        Ok(mac)
+    }
+
+    //两个密钥是否相同（常量时间比较）
+    pub fn same_as(&self, other: &EnvelopeKey) -> bool {
+        let expected = match self.mac("verify", b"", b"") {
+            Ok(mac) => mac.finalize().into_bytes(),
+            Err(_) => return false,
+        };
+        other.mac("verify", b"", b"").is_ok_and(|mac| mac.verify_slice(&expected).is_ok())
+    }
+
+    pub fn seal(&self, format: &str, version: u32, plain: &[u8]) -> Result<Envelope, String> {
+        let mut iv = [0u8; 16];
+        rand::thread_rng().fill(&mut iv[..]);
+        let cipher = Aes256Cbc::new_from_slices(&self.enc_key, &iv).map_err(|e| format!("加密初始化失败: {}", e))?;
+        let data = cipher.encrypt_vec(plain);
+        let mac = self.mac(format, &iv, &data)?.finalize().into_bytes();
+        Ok(Envelope {
+            format: format.to_string(),
+            version,
+            kdf: "argon2id".to_string(),
+            salt: BASE64.encode(&self.salt),
+            iv: BASE64.encode(iv),
+            data: BASE64.encode(&data),
+            mac: hex::encode(mac),
+        })
+    }
+
+    //先校验再解密，口令错误或文件被修改时返回错误
+    pub fn open(&self, envelope: &Envelope) -> Result<Vec<u8>, String> {
+        if BASE64.encode(&self.salt) != envelope.salt {
+            return Err("文件的口令与当前口令不一致".to_string());
+        }
+        let iv = BASE64.decode(&envelope.iv).map_err(|e| format!("文件解码失败: {}", e))?;
+        let data = BASE64.decode(&envelope.data).map_err(|e| format!("文件解码失败: {}", e))?;
+        let tag = hex::decode(&envelope.mac).map_err(|e| format!("文件解码失败: {}", e))?;
+        self.mac(&envelope.format, &iv, &data)?
+            .verify_slice(&tag)
+            .map_err(|_| "口令错误或文件已损坏".to_string())?;
+        let cipher = Aes256Cbc::new_from_slices(&self.enc_key, &iv).map_err(|e| format!("解密初始化失败: {}", e))?;
+        cipher.decrypt_vec(&data).map_err(|_| "口令错误或文件已损坏".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let key = EnvelopeKey::generate("correct horse").unwrap();
+        let json = key.seal("test.format", 1, b"hello").unwrap().to_json().unwrap();
+        let envelope = Envelope::parse(&json, "test.format").unwrap();
+        assert!(Envelope::parse(&json, "other.format").is_none());
+
+        let again = EnvelopeKey::derive("correct horse", envelope.salt().unwrap()).unwrap();
+        assert!(again.same_as(&key));
+        assert_eq!(again.open(&envelope).unwrap(), b"hello");
+
+        let wrong = EnvelopeKey::derive("wrong horse", envelope.salt().unwrap()).unwrap();
+        assert!(!wrong.same_as(&key));
+        assert!(wrong.open(&envelope).is_err());
+    }
+
+    #[test]
+    fn tampered_envelope_is_rejected() {
+        let key = EnvelopeKey::generate("correct horse").unwrap();
+        let mut envelope = key.seal("test.format", 1, b"hello").unwrap();
+        // format 参与校验，改用途后不能解开
+        envelope.format = "other.format".to_string();
+        assert!(key.open(&envelope).is_err());
+    }
+}
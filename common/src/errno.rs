@@ -0,0 +1,201 @@
+//会员购（show）接口错误码对照表，把 errno/msg 转成用户能看懂的说明和建议
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrnoCategory {
+    SoldOut,          //售罄/停售
+    PurchaseLimit,    //限购
+    BuyerNotVerified, //购票人未实名/未配置
+    SessionExpired,   //登录失效，需要重新登录
+    TokenExpired,     //下单token失效，程序会自动重新获取
+    InvalidParam,     //参数错误
+    ServerBusy,       //限速/服务器繁忙
+    Unknown,
+}
+
+impl ErrnoCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrnoCategory::SoldOut => "已售罄",
+            ErrnoCategory::PurchaseLimit => "达到限购",
+            ErrnoCategory::BuyerNotVerified => "购票人未实名",
+            ErrnoCategory::SessionExpired => "登录已失效",
+            ErrnoCategory::TokenExpired => "token失效",
+            ErrnoCategory::InvalidParam => "参数错误",
+            ErrnoCategory::ServerBusy => "服务器繁忙",
+            ErrnoCategory::Unknown => "未知错误",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ErrnoInfo {
+    pub codes: &'static [i64],
+    pub category: ErrnoCategory,
+    pub explanation: &'static str,
+    pub action: &'static str,
+}
+
+const CATALOGUE: &[ErrnoInfo] = &[
+    ErrnoInfo {
+        codes: &[100001, 429, 900001],
+        category: ErrnoCategory::ServerBusy,
+        explanation: "B站限速，抢票高峰期的正常现象",
+        action: "无需处理，程序会自动重试",
+    },
+    ErrnoInfo {
+        codes: &[3],
+        category: ErrnoCategory::ServerBusy,
+        explanation: "抢票速度过快，被暂时限制约5秒",
+        action: "程序会暂停后自动重试，可适当调低抢票频率",
+    },
+    ErrnoInfo {
+        codes: &[-401, 401],
+        category: ErrnoCategory::ServerBusy,
+        explanation: "触发B站风控，需要人机验证",
+        action: "程序会自动处理验证码，频繁出现时请降低抢票频率",
+    },
+    ErrnoInfo {
+        codes: &[-1],
+        category: ErrnoCategory::ServerBusy,
+        explanation: "网络错误或响应中没有错误码",
+        action: "检查网络连接，程序会自动重试",
+    },
+    ErrnoInfo {
+        codes: &[412],
+        category: ErrnoCategory::ServerBusy,
+        explanation: "请求被B站风控拦截或响应无法解析",
+        action: "稍后重试，频繁出现时请更换网络或降低频率",
+    },
+    ErrnoInfo {
+        codes: &[100009],
+        category: ErrnoCategory::SoldOut,
+        explanation: "当前票种库存不足",
+        action: "程序会降速继续尝试，也可以换其他票种或场次",
+    },
+    ErrnoInfo {
+        codes: &[211],
+        category: ErrnoCategory::SoldOut,
+        explanation: "差一点点抢到票，票已被别人抢走",
+        action: "程序会继续尝试，可能有人退票",
+    },
+    ErrnoInfo {
+        codes: &[100016, 100017],
+        category: ErrnoCategory::SoldOut,
+        explanation: "当前项目/场次/票种已停售",
+        action: "请换其他场次或票种后重新提交任务",
+    },
+    ErrnoInfo {
+        codes: &[100039],
+        category: ErrnoCategory::SoldOut,
+        explanation: "活动已结束售票",
+        action: "请换其他场次后重新提交任务",
+    },
+    ErrnoInfo {
+        codes: &[1],
+        category: ErrnoCategory::PurchaseLimit,
+        explanation: "该项目限购，或抢票参数格式有误",
+        action: "检查购票数量和购票人后重新提交任务",
+    },
+    ErrnoInfo {
+        codes: &[100003, 100079],
+        category: ErrnoCategory::PurchaseLimit,
+        explanation: "购票人存在待付款订单",
+        action: "前往订单列表支付或取消后重新下单",
+    },
+    ErrnoInfo {
+        codes: &[209001],
+        category: ErrnoCategory::PurchaseLimit,
+        explanation: "当前项目每单只能选择一个购票人",
+        action: "只保留一个购票人后重新提交任务",
+    },
+    ErrnoInfo {
+        codes: &[83000004],
+        category: ErrnoCategory::BuyerNotVerified,
+        explanation: "账号没有可用的实名购票人",
+        action: "在B站会员购添加并实名购票人后重新选择",
+    },
+    ErrnoInfo {
+        codes: &[100041, 100050, 900002],
+        category: ErrnoCategory::TokenExpired,
+        explanation: "下单token已失效",
+        action: "无需处理，程序会自动重新获取token",
+    },
+    ErrnoInfo {
+        codes: &[-101],
+        category: ErrnoCategory::SessionExpired,
+        explanation: "账号未登录或登录已过期",
+        action: "重新登录该账号后再提交任务",
+    },
+    ErrnoInfo {
+        codes: &[100080, 100082],
+        category: ErrnoCategory::InvalidParam,
+        explanation: "场次/项目/日期选择有误",
+        action: "重新选择场次和票种后提交任务",
+    },
+    ErrnoInfo {
+        codes: &[999, 919],
+        category: ErrnoCategory::InvalidParam,
+        explanation: "程序内部错误，请求参数不正确",
+        action: "重新下单，仍然出现请附上日志提交issue",
+    },
+    ErrnoInfo {
+        codes: &[737],
+        category: ErrnoCategory::Unknown,
+        explanation: "B站返回了空数据",
+        action: "查看上一条日志中的提示，自行决定是否继续",
+    },
+];
+
+const UNKNOWN: ErrnoInfo = ErrnoInfo {
+    codes: &[],
+    category: ErrnoCategory::Unknown,
+    explanation: "未收录的错误码",
+    action: "可以附上日志提交issue补充该错误码",
+};
+
+pub fn lookup(code: i64) -> &'static ErrnoInfo {
+    CATALOGUE.iter().find(|info| info.codes.contains(&code)).unwrap_or(&UNKNOWN)
+}
+
+//完整说明，用于结果消息、横幅和推送，如"【已售罄】当前票种库存不足（100009）。建议：…"
+pub fn describe(code: i64, msg: &str) -> String {
+    let info = lookup(code);
+    let msg = msg.trim();
+    let detail = if msg.is_empty() || msg == info.explanation {
+        format!("{}（{}）", info.explanation, code)
+    } else if info.category == ErrnoCategory::Unknown {
+        format!("{}（{}）", msg, code)
+    } else {
+        format!("{}（{}，B站提示：{}）", info.explanation, code, msg)
+    };
+    format!("【{}】{}。建议：{}", info.category.label(), detail, info.action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_expiry_is_not_login_expiry() {
+        for code in [100041, 100050, 900002] {
+            assert_eq!(lookup(code).category, ErrnoCategory::TokenExpired);
+        }
+        assert_eq!(lookup(-101).category, ErrnoCategory::SessionExpired);
+    }
+
+    #[test]
+    fn describes_known_and_unknown_codes() {
+        assert_eq!(describe(100009, ""), "【已售罄】当前票种库存不足（100009）。建议：程序会降速继续尝试，也可以换其他票种或场次");
+        assert!(describe(100009, "库存不足啦").contains("B站提示：库存不足啦"));
+        assert!(describe(123456, "奇怪的错误").starts_with("【未知错误】奇怪的错误（123456）"));
+    }
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = Vec::new();
+        for code in CATALOGUE.iter().flat_map(|info| info.codes) {
+            assert!(!seen.contains(code), "错误码 {} 重复", code);
+            seen.push(*code);
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data_dir;
+use crate::errno;
+use crate::time_format;
+
+//抢票各阶段名称
+pub const PHASE_TOKEN: &str = "获取token";
+pub const PHASE_CAPTCHA: &str = "验证码";
+pub const PHASE_CONFIRM: &str = "确认订单";
+pub const PHASE_CREATE: &str = "创建订单";
+pub const PHASE_CHECK: &str = "检测假票";
+const PHASE_ORDER: [&str; 5] = [PHASE_TOKEN, PHASE_CAPTCHA, PHASE_CONFIRM, PHASE_CREATE, PHASE_CHECK];
+
+//网络错误等没有错误码的失败统一记为-1
+pub const ERRNO_NO_CODE: i64 = -1;
+
+// 时间线最多保留的条数，捡漏模式可能跑很久
+const MAX_TIMELINE: usize = 2000;
+
+//时间线上的一次请求
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GrabAttempt {
+    pub at: i64, //开始时间（毫秒时间戳）
+    pub phase: String,
+    pub latency_ms: u64,
+    pub errno: i64, //0为成功
+}
+
+//单个阶段的统计
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub phase: String,
+    pub attempts: usize,
+    pub failures: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+//错误码出现次数
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ErrnoCount {
+    pub phase: String,
+    pub errno: i64,
+    pub count: usize,
+    pub category: String,
+    pub explanation: String,
+}
+
+//一次抢票任务结束后的报告
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GrabReport {
+    pub task_id: String,
+    pub uid: i64,
+    pub project_id: String,
+    pub project_name: String,
+    pub success: bool,
+    pub message: String,
+    pub started_at: i64, //毫秒时间戳
+    pub finished_at: i64,
+    pub first_token_at: Option<i64>,
+    pub order_at: Option<i64>,
+    pub phases: Vec<PhaseStats>,
+    pub errno_histogram: Vec<ErrnoCount>,
+    pub timeline: Vec<GrabAttempt>,
+    pub timeline_dropped: usize, //超出上限未记录的条数
+}
+
+impl GrabReport {
+    pub fn duration_ms(&self) -> i64 {
+        (self.finished_at - self.started_at).max(0)
+    }
+
+    pub fn total_attempts(&self) -> usize {
+        self.phases.iter().map(|p| p.attempts).sum()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("报告序列化失败: {}", e))
+    }
+
+    //保存到数据目录下的 grab_reports，返回文件路径
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let dir = data_dir::data_dir().join("grab_reports");
+        fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+        let path = dir.join(format!("grab_report_{}_{}.json", self.uid, time_format::file_stamp(self.finished_at)));
+        fs::write(&path, self.to_json()?).map_err(|e| format!("保存报告失败: {}", e))?;
+        Ok(path)
+    }
+}
+
+//抢票过程中收集统计，任务结束时生成报告
+#[derive(Debug, Default)]
+pub struct GrabStats {
+    started_at: i64,
+    first_token_at: Option<i64>,
+    order_at: Option<i64>,
+    latencies: BTreeMap<String, Vec<u64>>,
+    failures: BTreeMap<String, usize>,
+    errno_counts: BTreeMap<(String, i64), usize>,
+    timeline: Vec<GrabAttempt>,
+    timeline_dropped: usize,
+    outcome: Option<(bool, String)>,
+    last_error: Option<String>,
+}
+
+impl GrabStats {
+    pub fn new() -> Self {
+        Self { started_at: chrono::Local::now().timestamp_millis(), ..Default::default() }
+    }
+
+    //记录一次请求，errno为0表示成功
+    pub fn record(&mut self, phase: &str, latency: Duration, errno: i64) {
+        let now = chrono::Local::now().timestamp_millis();
+        let latency_ms = latency.as_millis() as u64;
+        self.latencies.entry(phase.to_string()).or_default().push(latency_ms);
+        if errno == 0 {
+            if phase == PHASE_TOKEN && self.first_token_at.is_none() {
+                self.first_token_at = Some(now);
+            }
+            if phase == PHASE_CREATE && self.order_at.is_none() {
+                self.order_at = Some(now);
+            }
+        } else {
+            *self.failures.entry(phase.to_string()).or_default() += 1;
+            *self.errno_counts.entry((phase.to_string(), errno)).or_default() += 1;
+            self.last_error = Some(format!("{}：{}", phase, errno::describe(errno, "")));
+        }
+        if self.timeline.len() < MAX_TIMELINE {
+            self.timeline.push(GrabAttempt {
+                at: now - latency_ms as i64,
+                phase: phase.to_string(),
+                latency_ms,
+                errno,
+            });
+        } else {
+            self.timeline_dropped += 1;
+        }
+    }
+
+    //记录任务的最终结果，只保留第一次
+    pub fn set_outcome(&mut self, success: bool, message: &str) {
+        if self.outcome.is_none() {
+            self.outcome = Some((success, message.to_string()));
+        }
+    }
+
+    pub fn finish(&self, task_id: &str, uid: i64, project_id: &str, project_name: &str) -> GrabReport {
+        let (success, message) = self.outcome.clone().unwrap_or_else(|| {
+            (false, self.last_error.clone().unwrap_or_else(|| "任务结束，未下单".to_string()))
+        });
+        let mut phases: Vec<PhaseStats> = self
+            .latencies
+            .iter()
+            .map(|(phase, samples)| {
+                let mut sorted = samples.clone();
+                sorted.sort_unstable();
+                PhaseStats {
+                    phase: phase.clone(),
+                    attempts: samples.len(),
+                    failures: self.failures.get(phase).copied().unwrap_or(0),
+                    p50_ms: percentile(&sorted, 50.0),
+                    p90_ms: percentile(&sorted, 90.0),
+                    p99_ms: percentile(&sorted, 99.0),
+                    max_ms: sorted.last().copied().unwrap_or(0),
+                }
+            })
+            .collect();
+        // 按抢票流程的先后排列
+        phases.sort_by_key(|p| PHASE_ORDER.iter().position(|name| *name == p.phase).unwrap_or(PHASE_ORDER.len()));
+        let mut errno_histogram: Vec<ErrnoCount> = self
+            .errno_counts
+            .iter()
+            .map(|((phase, code), count)| {
+                let info = errno::lookup(*code);
+                ErrnoCount {
+                    phase: phase.clone(),
+                    errno: *code,
+                    count: *count,
+                    category: info.category.label().to_string(),
+                    explanation: info.explanation.to_string(),
+                }
+            })
+            .collect();
+        errno_histogram.sort_by(|a, b| b.count.cmp(&a.count));
+        GrabReport {
+            task_id: task_id.to_string(),
+            uid,
+            project_id: project_id.to_string(),
+            project_name: project_name.to_string(),
+            success,
+            message,
+            started_at: self.started_at,
+            finished_at: chrono::Local::now().timestamp_millis(),
+            first_token_at: self.first_token_at,
+            order_at: self.order_at,
+            phases,
+            errno_histogram,
+            timeline: self.timeline.clone(),
+            timeline_dropped: self.timeline_dropped,
+        }
+    }
+}
+
+//最近秩法取百分位，输入需已排序
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
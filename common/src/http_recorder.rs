@@ -0,0 +1,498 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 脱敏后的占位值
+pub const REDACTED: &str = "<已脱敏>";
+
+// 需要脱敏的请求头/响应头
+const SECRET_HEADERS: [&str; 3] = ["cookie", "set-cookie", "authorization"];
+
+// 需要脱敏的字段（查询参数、表单、json中的同名字段都会替换）
+pub(crate) const SECRET_FIELDS: [&str; 20] = [
+    "csrf",
+    "refresh_csrf",
+    "refresh_token",
+    "access_token",
+    "access_key",
+    "sessdata",
+    "bili_jct",
+    "dedeuserid",
+    "dedeuserid__ckmd5",
+    "token",
+    "password",
+    "personal_id",
+    "id_card",
+    "tel",
+    "phone",
+    "mobile",
+    "buyer",
+    "addr",
+    "name",
+    "buyer_info",
+];
+
+fn is_secret_field(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_FIELDS.contains(&name.as_str())
+}
+
+//HAR格式的记录文件（只用到其中一部分字段）
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HarFile {
+    pub log: HarLog,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarEntry {
+    #[serde(rename = "startedDateTime")]
+    pub started_date_time: String,
+    pub time: u64, //耗时（毫秒）
+    pub request: HarRequest,
+    pub response: HarResponse,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HarHeader>,
+    #[serde(rename = "postData", default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarPostData {
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarResponse {
+    pub status: u16,
+    pub headers: Vec<HarHeader>,
+    pub content: HarContent,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarContent {
+    pub size: usize,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+impl HarEntry {
+    //回放时用的匹配键：方法 + 去掉查询参数的地址
+    fn matches(&self, method: &str, url: &str) -> bool {
+        self.request.method.eq_ignore_ascii_case(method) && strip_query(&self.request.url) == strip_query(url)
+    }
+}
+
+fn strip_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+enum RecorderMode {
+    Off,
+    // 记录时逐条追加到 <记录文件>.jsonl，停止时再合成HAR
+    Recording { path: PathBuf, journal: fs::File, count: usize },
+    Replaying { path: PathBuf, entries: Vec<HarEntry>, used: Vec<bool> },
+}
+
+static RECORDER: Lazy<Mutex<RecorderMode>> = Lazy::new(|| Mutex::new(RecorderMode::Off));
+
+//当前状态，界面显示用
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecorderStatus {
+    Off,
+    Recording { path: String, count: usize },
+    Replaying { path: String, total: usize, served: usize },
+}
+
+pub fn status() -> RecorderStatus {
+    status_of(&RECORDER.lock().unwrap())
+}
+
+//不等待锁，取不到时返回None；panic时生成崩溃报告用，避免在持锁线程崩溃时卡死
+pub fn try_status() -> Option<RecorderStatus> {
+    RECORDER.try_lock().ok().map(|mode| status_of(&mode))
+}
+
+fn status_of(mode: &RecorderMode) -> RecorderStatus {
+    match mode {
+        RecorderMode::Off => RecorderStatus::Off,
+        RecorderMode::Recording { path, count, .. } => RecorderStatus::Recording {
+            path: path.display().to_string(),
+            count: *count,
+        },
+        RecorderMode::Replaying { path, entries, used } => RecorderStatus::Replaying {
+            path: path.display().to_string(),
+            total: entries.len(),
+            served: used.iter().filter(|u| **u).count(),
+        },
+    }
+}
+
+pub fn is_active() -> bool {
+    !matches!(*RECORDER.lock().unwrap(), RecorderMode::Off)
+}
+
+pub fn is_replaying() -> bool {
+    matches!(*RECORDER.lock().unwrap(), RecorderMode::Replaying { .. })
+}
+
+fn new_har(entries: Vec<HarEntry>) -> HarFile {
+    HarFile {
+        log: HarLog {
+            version: "1.2".to_string(),
+            creator: HarCreator {
+                name: "bili_ticket_rush".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries,
+        },
+    }
+}
+
+fn journal_path(path: &Path) -> PathBuf {
+    let mut journal = path.as_os_str().to_owned();
+    journal.push(".jsonl");
+    PathBuf::from(journal)
+}
+
+//开始记录，之后经过 CookieManager::execute 的请求都会脱敏后写入文件
+pub fn start_recording(path: &Path) -> Result<(), String> {
+    write_har(path, &new_har(Vec::new()))?;
+    let journal = fs::File::create(journal_path(path)).map_err(|e| format!("创建记录文件失败: {}", e))?;
+    *RECORDER.lock().unwrap() = RecorderMode::Recording { path: path.to_path_buf(), journal, count: 0 };
+    log::info!("开始记录HTTP请求到 {}", path.display());
+    Ok(())
+}
+
+//把逐条追加的记录合成HAR并删除 .jsonl，返回条数
+fn finish_recording(path: &Path) -> Result<usize, String> {
+    let journal = journal_path(path);
+    let content = fs::read_to_string(&journal).map_err(|e| format!("读取记录文件失败: {}", e))?;
+    // 程序崩溃时最后一行可能没写完，跳过
+    let entries: Vec<HarEntry> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("跳过无法解析的HTTP记录: {}", e);
+                None
+            }
+        })
+        .collect();
+    let count = entries.len();
+    write_har(path, &new_har(entries))?;
+    if let Err(e) = fs::remove_file(&journal) {
+        log::warn!("删除临时记录文件失败: {}", e);
+    }
+    Ok(count)
+}
+
+//开始回放，之后经过 CookieManager::execute 的请求直接返回记录中的响应，不再联网
+pub fn start_replay(path: &Path) -> Result<usize, String> {
+    // 上次记录没有正常停止（如程序崩溃）时，先把残留的记录合成HAR
+    if journal_path(path).exists() {
+        finish_recording(path)?;
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取记录文件失败: {}", e))?;
+    let har: HarFile = serde_json::from_str(&content).map_err(|e| format!("记录文件格式错误: {}", e))?;
+    let total = har.log.entries.len();
+    *RECORDER.lock().unwrap() = RecorderMode::Replaying {
+        path: path.to_path_buf(),
+        used: vec![false; total],
+        entries: har.log.entries,
+    };
+    log::info!("开始回放 {}，共{}条记录", path.display(), total);
+    Ok(total)
+}
+
+pub fn stop() {
+    let previous = std::mem::replace(&mut *RECORDER.lock().unwrap(), RecorderMode::Off);
+    match previous {
+        RecorderMode::Recording { path, journal, .. } => {
+            drop(journal);
+            match finish_recording(&path) {
+                Ok(count) => log::info!("已停止记录，共{}条，保存在 {}", count, path.display()),
+                Err(e) => log::error!("保存HTTP记录失败: {}", e),
+            }
+        }
+        RecorderMode::Replaying { .. } => log::info!("已停止回放"),
+        RecorderMode::Off => {}
+    }
+}
+
+fn write_har(path: &Path, har: &HarFile) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(har).map_err(|e| format!("记录序列化失败: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("写入记录文件失败: {}", e))
+}
+
+//记录一次请求（已脱敏），每条追加一行立即写盘，程序崩溃也不会丢
+pub fn record(entry: HarEntry) {
+    if !is_active() {
+        return;
+    }
+    // 在锁外序列化，锁内只追加一行
+    let line = match serde_json::to_string(&entry) {
+        Ok(line) => line,
+        Err(e) => {
+            log::error!("HTTP记录序列化失败: {}", e);
+            return;
+        }
+    };
+    let mut mode = RECORDER.lock().unwrap();
+    if let RecorderMode::Recording { journal, count, .. } = &mut *mode {
+        match writeln!(journal, "{}", line) {
+            Ok(_) => *count += 1,
+            Err(e) => log::error!("保存HTTP记录失败: {}", e),
+        }
+    }
+}
+
+//回放：按顺序取出同一地址的下一条记录，用完后重复最后一条
+pub fn replay(method: &str, url: &str) -> Option<HarEntry> {
+    let mut mode = RECORDER.lock().unwrap();
+    let (entries, used) = match &mut *mode {
+        RecorderMode::Replaying { entries, used, .. } => (entries, used),
+        _ => return None,
+    };
+    let index = entries
+        .iter()
+        .enumerate()
+        .position(|(i, e)| !used[i] && e.matches(method, url))
+        .or_else(|| entries.iter().rposition(|e| e.matches(method, url)))?;
+    used[index] = true;
+    Some(entries[index].clone())
+}
+
+//请求头/响应头脱敏
+pub fn redact_headers<'a>(headers: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<HarHeader> {
+    headers
+        .map(|(name, value)| HarHeader {
+            name: name.to_string(),
+            value: if SECRET_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_string()
+            },
+        })
+        .collect()
+}
+
+//地址中的查询参数脱敏
+pub fn redact_url(url: &str) -> String {
+    match url.split_once('?') {
+        Some((base, query)) => format!("{}?{}", base, redact_form(query)),
+        None => url.to_string(),
+    }
+}
+
+//表单（a=1&b=2）脱敏
+pub fn redact_form(form: &str) -> String {
+    form.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if is_secret_field(key) => format!("{}={}", key, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+//正文脱敏：json按字段名替换，保留结构方便排查解析问题；表单按参数替换
+pub fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            serde_json::to_string(&value).unwrap_or_default()
+        }
+        Err(_) if body.contains('=') && !body.contains(char::is_whitespace) => redact_form(body),
+        Err(_) => body.to_string(),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                // 只替换值，不改变类型，避免回放时出现原本没有的解析错误
+                match value {
+                    // 下单接口的 buyer_info 是JSON字符串，先解析后逐字段脱敏再写回
+                    Value::String(text) => {
+                        if !redact_json_string(text) && is_secret_field(key) {
+                            *text = REDACTED.to_string();
+                        }
+                    }
+                    Value::Number(_) if is_secret_field(key) => *value = Value::from(0),
+                    _ => redact_json(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+//字符串本身是JSON对象/数组时按结构脱敏，返回是否已处理
+fn redact_json_string(text: &mut String) -> bool {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        return false;
+    }
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut inner @ (Value::Object(_) | Value::Array(_))) => {
+            redact_json(&mut inner);
+            *text = inner.to_string();
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_create_order_body() {
+        // 与 api::create_order 组装的结构一致：buyer_info 是JSON字符串
+        let buyer_info = json!([{
+            "id": 1001,
+            "uid": 12345,
+            "personal_id": "110101199001011234",
+            "name": "张三",
+            "tel": "13800138000",
+            "id_type": 0,
+            "is_default": 1,
+        }]);
+        let body = json!({
+            "project_id": 85939,
+            "screen_id": 153744,
+            "sku_id": 466120,
+            "token": "abcdef",
+            "buyer_info": buyer_info.to_string(),
+            "clickPosition": {"x": 1, "y": 2},
+            "count": 1,
+            "pay_money": 38000,
+        })
+        .to_string();
+
+        let redacted = redact_body(&body);
+        for secret in ["110101199001011234", "张三", "13800138000", "abcdef"] {
+            assert!(!redacted.contains(secret), "{} 未脱敏: {}", secret, redacted);
+        }
+
+        // 结构保留：外层字段不变，内层仍是可解析的JSON字符串
+        let value: Value = serde_json::from_str(&redacted).unwrap();
+        assert_eq!(value["project_id"], 85939);
+        assert_eq!(value["pay_money"], 38000);
+        let buyers: Value = serde_json::from_str(value["buyer_info"].as_str().unwrap()).unwrap();
+        assert_eq!(buyers[0]["name"], REDACTED);
+        assert_eq!(buyers[0]["id"], 1001);
+    }
+
+    #[test]
+    fn redacts_unparsable_secret_string() {
+        let redacted = redact_body(r#"{"buyer_info":"[not json","name":"张三"}"#);
+        let value: Value = serde_json::from_str(&redacted).unwrap();
+        assert_eq!(value["buyer_info"], REDACTED);
+        assert_eq!(value["name"], REDACTED);
+    }
+
+    #[test]
+    fn redacts_form_and_url() {
+        assert_eq!(redact_form("csrf=abc&page=1"), format!("csrf={}&page=1", REDACTED));
+        assert_eq!(
+            redact_url("https://api.bilibili.com/x?access_key=k&id=1"),
+            format!("https://api.bilibili.com/x?access_key={}&id=1", REDACTED)
+        );
+    }
+
+    #[test]
+    fn redacts_secret_headers() {
+        let headers = redact_headers([("Cookie", "SESSDATA=x"), ("Accept", "*/*")].into_iter());
+        assert_eq!(headers[0].value, REDACTED);
+        assert_eq!(headers[1].value, "*/*");
+    }
+
+    fn entry(url: &str) -> HarEntry {
+        HarEntry {
+            started_date_time: "2025-05-01T20:00:00+08:00".to_string(),
+            time: 12,
+            request: HarRequest { method: "GET".to_string(), url: url.to_string(), headers: Vec::new(), post_data: None },
+            response: HarResponse {
+                status: 200,
+                headers: Vec::new(),
+                content: HarContent { size: 2, mime_type: "application/json".to_string(), text: "{}".to_string() },
+            },
+        }
+    }
+
+    #[test]
+    fn try_status_does_not_wait_for_lock() {
+        let guard = RECORDER.lock().unwrap();
+        assert!(try_status().is_none());
+        drop(guard);
+        assert!(try_status().is_some());
+    }
+
+    // 会改动全局记录状态，记录和崩溃恢复放在同一个测试里顺序执行
+    #[test]
+    fn recording_appends_and_recovers_after_crash() {
+        let dir = std::env::temp_dir().join(format!("http_recorder_test_{}", std::process::id()));
+        let path = dir.join("record.har");
+
+        start_recording(&path).unwrap();
+        record(entry("https://show.bilibili.com/a"));
+        record(entry("https://show.bilibili.com/b"));
+        assert_eq!(status(), RecorderStatus::Recording { path: path.display().to_string(), count: 2 });
+        stop();
+        assert!(!journal_path(&path).exists());
+        let har: HarFile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(har.log.entries.len(), 2);
+        assert_eq!(har.log.entries[1].request.url, "https://show.bilibili.com/b");
+
+        // 模拟崩溃：留下 .jsonl，最后一行没写完
+        let line = serde_json::to_string(&entry("https://show.bilibili.com/c")).unwrap();
+        fs::write(journal_path(&path), format!("{}\n{}", line, &line[..10])).unwrap();
+        assert_eq!(start_replay(&path).unwrap(), 1);
+        assert!(replay("GET", "https://show.bilibili.com/c?x=1").is_some());
+        stop();
+        assert!(!journal_path(&path).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
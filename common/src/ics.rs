@@ -0,0 +1,135 @@
+use chrono::{TimeZone, Utc};
+
+// 生成的日历文件标识
+const PRODID: &str = "-//biliticket//bili_ticket_rush//CN";
+
+// 一个日历事件，时间均为unix时间戳（秒）
+#[derive(Clone, Debug)]
+pub struct IcsEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub location: String,
+    pub start: i64,
+    pub end: Option<i64>,
+    pub alarm_minutes: Option<u32>, //提前提醒分钟数
+}
+
+//生成 RFC 5545 格式的 .ics 内容，时间统一写成UTC
+pub fn to_ics(events: &[IcsEvent]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    let stamp = format_utc(Utc::now().timestamp());
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_utc(event.start)));
+        // 没有结束时间的按半小时处理，日历里才能显示成一个时间段
+        let end = event.end.filter(|end| *end > event.start).unwrap_or(event.start + 1800);
+        lines.push(format!("DTEND:{}", format_utc(end)));
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        }
+        if !event.location.is_empty() {
+            lines.push(format!("LOCATION:{}", escape(&event.location)));
+        }
+        if let Some(minutes) = event.alarm_minutes {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{}", escape(&event.summary)));
+            lines.push(format!("TRIGGER:-PT{}M", minutes));
+            lines.push("END:VALARM".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold(&line));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+fn format_utc(timestamp: i64) -> String {
+    match Utc.timestamp_opt(timestamp, 0) {
+        chrono::LocalResult::Single(dt) => dt.format("%Y%m%dT%H%M%SZ").to_string(),
+        _ => "19700101T000000Z".to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// 每行不超过75字节，续行以空格开头；不能从多字节字符中间截断
+fn fold(line: &str) -> String {
+    let mut result = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            result.push_str("\r\n ");
+            width = 1;
+        }
+        result.push(c);
+        width += len;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(summary: &str, alarm_minutes: Option<u32>) -> IcsEvent {
+        IcsEvent {
+            uid: "85939-SaleStart-1746100800@bili_ticket_rush".to_string(),
+            summary: summary.to_string(),
+            description: "场次：晚场\n数量：1张".to_string(),
+            location: String::new(),
+            start: 1746100800,
+            end: None,
+            alarm_minutes,
+        }
+    }
+
+    #[test]
+    fn writes_utc_times_and_default_duration() {
+        let ics = to_ics(&[event("开售", Some(15))]);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20250501T120000Z\r\n"));
+        assert!(ics.contains("DTEND:20250501T123000Z\r\n"));
+        assert!(ics.contains("TRIGGER:-PT15M\r\n"));
+        assert!(!ics.contains("LOCATION:"));
+    }
+
+    #[test]
+    fn escapes_text_fields() {
+        let ics = to_ics(&[event("A,B;C", None)]);
+        assert!(ics.contains("SUMMARY:A\\,B\\;C\r\n"));
+        assert!(ics.contains("DESCRIPTION:场次：晚场\\n数量：1张\r\n"));
+    }
+
+    #[test]
+    fn folds_long_lines_on_char_boundaries() {
+        let ics = to_ics(&[event(&"演出".repeat(40), None)]);
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "{}", line);
+        }
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:{}", "演出".repeat(40))));
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use eframe::egui::{self, ColorImage, TextureHandle};
+use tokio::runtime::Handle;
+
+use crate::cookie_manager::CookieManager;
+
+// 磁盘缓存默认上限 64MB
+pub const DEFAULT_MAX_DISK_BYTES: u64 = 64 * 1024 * 1024;
+// 加载失败后的重试间隔
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+// 内存中最多保留的纹理数，超出时淘汰最久未使用的
+pub const MAX_TEXTURES: usize = 256;
+
+//图片加载服务：内存纹理缓存 + 磁盘字节缓存 + 同一url的请求去重
+pub struct ImageCache {
+    cache_dir: PathBuf,
+    max_disk_bytes: u64,
+    runtime: Handle, //任务管理器的运行时
+    textures: Mutex<TextureLru>,
+    pending: Mutex<HashSet<String>>,
+    failed: Mutex<HashMap<String, Instant>>,
+    placeholder: Mutex<Option<TextureHandle>>,
+}
+
+impl ImageCache {
+    pub fn new(cache_dir: PathBuf, max_disk_bytes: u64, runtime: Handle) -> Self {
+        if let Err(e) = fs::create_dir_all(&cache_dir) {
+            log::warn!("无法创建图片缓存目录 {:?}: {}", cache_dir, e);
+        }
+        Self {
+            cache_dir,
+            max_disk_bytes,
+            runtime,
+            textures: Mutex::new(TextureLru::new(MAX_TEXTURES)),
+            pending: Mutex::new(HashSet::new()),
+            failed: Mutex::new(HashMap::new()),
+            placeholder: Mutex::new(None),
+        }
+    }
+
+    //获取图片纹理，未就绪时返回None并在后台加载（先读磁盘缓存，再走网络）
+    pub fn get(
+        self: &Arc<Self>,
+        ctx: &egui::Context,
+        cookie_manager: Option<Arc<CookieManager>>,
+        url: &str,
+    ) -> Option<TextureHandle> {
+        if url.is_empty() {
+            return None;
+        }
+        if let Some(texture) = self.textures.lock().unwrap().get(url) {
+            return Some(texture);
+        }
+        if let Some(failed_at) = self.failed.lock().unwrap().get(url) {
+            if failed_at.elapsed() < RETRY_AFTER {
+                return None;
+            }
+        }
+        // 同一url只发起一次请求
+        if !self.pending.lock().unwrap().insert(url.to_string()) {
+            return None;
+        }
+
+        let cache = Arc::clone(self);
+        let ctx = ctx.clone();
+        let url = url.to_string();
+        self.runtime.spawn(async move {
+            let bytes = cache.load_bytes(cookie_manager, &url).await;
+            // 解码较慢，放到阻塞线程池
+            let decoded = match bytes {
+                Some(bytes) => {
+                    let decode_url = url.clone();
+                    tokio::task::spawn_blocking(move || decode_image(&bytes, &decode_url)).await.ok().flatten()
+                }
+                None => None,
+            };
+            match decoded {
+                Some(image) => {
+                    let texture = ctx.load_texture(url.clone(), image, Default::default());
+                    cache.textures.lock().unwrap().insert(url.clone(), texture);
+                    cache.failed.lock().unwrap().remove(&url);
+                    log::debug!("加载图片成功: {}", url);
+                }
+                None => {
+                    // 缓存文件可能已损坏，删掉下次重新下载
+                    let _ = fs::remove_file(cache.cache_path(&url));
+                    cache.failed.lock().unwrap().insert(url.clone(), Instant::now());
+                }
+            }
+            cache.pending.lock().unwrap().remove(&url);
+            ctx.request_repaint();
+        });
+        None
+    }
+
+    //图片加载中时显示的占位图
+    pub fn placeholder(&self, ctx: &egui::Context) -> TextureHandle {
+        let mut placeholder = self.placeholder.lock().unwrap();
+        if let Some(texture) = placeholder.as_ref() {
+            return texture.clone();
+        }
+        let texture = ctx.load_texture(
+            "image_cache_placeholder",
+            ColorImage::new([8, 8], egui::Color32::from_rgb(225, 225, 235)),
+            Default::default(),
+        );
+        *placeholder = Some(texture.clone());
+        texture
+    }
+
+    pub fn is_loading(&self, url: &str) -> bool {
+        self.pending.lock().unwrap().contains(url)
+    }
+
+    fn cache_path(&self, url: &str) -> PathBuf {
+        self.cache_dir.join(format!("{:x}", md5::compute(url.as_bytes())))
+    }
+
+    async fn load_bytes(&self, cookie_manager: Option<Arc<CookieManager>>, url: &str) -> Option<Vec<u8>> {
+        let path = self.cache_path(url);
+        if let Ok(bytes) = tokio::fs::read(&path).await {
+            if !bytes.is_empty() {
+                return Some(bytes);
+            }
+        }
+        let bytes = fetch_bytes(cookie_manager, url).await?;
+        match tokio::fs::write(&path, &bytes).await {
+            Ok(_) => self.evict(),
+            Err(e) => log::warn!("写入图片缓存失败: {}", e),
+        }
+        Some(bytes)
+    }
+
+    //超出容量时按修改时间从旧到新删除缓存文件
+    fn evict(&self) {
+        let entries = match fs::read_dir(&self.cache_dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        let mut files: Vec<(PathBuf, u64, SystemTime)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                if !meta.is_file() {
+                    return None;
+                }
+                Some((entry.path(), meta.len(), meta.modified().unwrap_or(SystemTime::UNIX_EPOCH)))
+            })
+            .collect();
+        let mut total: u64 = files.iter().map(|(_, len, _)| *len).sum();
+        if total <= self.max_disk_bytes {
+            return;
+        }
+        files.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in files {
+            if total <= self.max_disk_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total = total.saturating_sub(len);
+            }
+        }
+        log::debug!("图片缓存已清理，当前占用 {} 字节", total);
+    }
+}
+
+//内存纹理缓存，按最近使用时间淘汰
+struct TextureLru {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (TextureHandle, u64)>,
+}
+
+impl TextureLru {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, tick: 0, entries: HashMap::new() }
+    }
+
+    fn get(&mut self, url: &str) -> Option<TextureHandle> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(url).map(|(texture, used)| {
+            *used = tick;
+            texture.clone()
+        })
+    }
+
+    fn insert(&mut self, url: String, texture: TextureHandle) {
+        self.tick += 1;
+        self.entries.insert(url, (texture, self.tick));
+        while self.entries.len() > self.capacity {
+            let oldest = self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(url, _)| url.clone());
+            match oldest {
+                Some(url) => self.entries.remove(&url),
+                None => break,
+            };
+        }
+    }
+}
+
+async fn fetch_bytes(cookie_manager: Option<Arc<CookieManager>>, url: &str) -> Option<Vec<u8>> {
+    let request = match &cookie_manager {
+        Some(cookie_manager) => cookie_manager.get(url).await,
+        None => reqwest::Client::new().get(url),
+    };
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(err) => {
+            log::error!("HTTP请求失败: {}", err);
+            return None;
+        }
+    };
+    if !resp.status().is_success() {
+        log::warn!("图片请求失败: {}，url:{}", resp.status(), url);
+        return None;
+    }
+    match resp.bytes().await {
+        Ok(bytes) => Some(bytes.to_vec()),
+        Err(err) => {
+            log::error!("读取响应体失败: {}", err);
+            None
+        }
+    }
+}
+
+fn decode_image(bytes: &[u8], url: &str) -> Option<ColorImage> {
+    match image::load_from_memory(bytes) {
+        Ok(image) => {
+            let size = [image.width() as usize, image.height() as usize];
+            let image_buffer = image.to_rgba8();
+            let pixels = image_buffer.as_flat_samples();
+            Some(ColorImage::from_rgba_unmultiplied(size, pixels.as_slice()))
+        }
+        Err(err) => {
+            log::warn!("加载图片至内存失败: {}，url:{}", err, url);
+            None
+        }
+    }
+}
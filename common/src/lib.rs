@@ -14,6 +14,31 @@ pub mod cookie_manager;
 pub mod web_ck_obfuscated;
 pub mod machine_id;
 pub mod gen_cp;
+pub mod image_cache;
+pub mod data_dir;
+pub mod profile;
+pub mod config;
+pub mod settings_transfer;
+pub mod link_parser;
+pub mod project_search;
+pub mod project_detail;
+pub mod watchlist;
+pub mod ics;
+pub mod calendar;
+pub mod time_format;
+pub mod address;
+pub mod preflight;
+pub mod dry_run;
+pub mod cookie_refresh;
+pub mod envelope;
+pub mod account_transfer;
+pub mod contact;
+pub mod app_lock;
+pub mod http_recorder;
+pub mod schema_drift;
+pub mod errno;
+pub mod grab_report;
+pub mod crash_report;
 // 重导出日志收集器
 pub use record_log::LOG_COLLECTOR;
 pub use record_log::init as init_logger;
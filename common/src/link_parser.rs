@@ -0,0 +1,152 @@
+use reqwest::Url;
+
+// 短链接域名，需要联网跟随跳转后才能拿到项目id
+const SHORT_LINK_HOSTS: [&str; 3] = ["b23.tv", "b23.wtf", "bili2233.cn"];
+// 链接里可能携带项目id的参数名
+const ID_PARAMS: [&str; 3] = ["id", "project_id", "projectId"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsedLink {
+    ProjectId(String),
+    ShortLink(String),
+}
+
+//解析首页输入：纯数字id、演出/会员购链接（含手机端）、bilibili://深链接、b23短链接
+//分享文案里夹带的链接也能识别
+pub fn parse_input(input: &str) -> Result<ParsedLink, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("请输入项目ID或票务链接".to_string());
+    }
+    if is_project_id(input) {
+        return Ok(ParsedLink::ProjectId(input.to_string()));
+    }
+    let link = match find_link(input) {
+        Some(link) => link,
+        // 省略了协议头的链接，如 b23.tv/xxxx
+        None if !input.contains(char::is_whitespace)
+            && (input.contains("bilibili.com") || SHORT_LINK_HOSTS.iter().any(|h| input.starts_with(h))) =>
+        {
+            format!("https://{}", input)
+        }
+        None => return Err(format!("无法识别的输入: {}，请输入项目ID或票务链接", input)),
+    };
+    let url = Url::parse(&link).map_err(|e| format!("链接格式错误: {}", e))?;
+    let host = url.host_str().unwrap_or("").to_lowercase();
+    if SHORT_LINK_HOSTS.iter().any(|h| host == *h || host.ends_with(&format!(".{}", h))) {
+        return Ok(ParsedLink::ShortLink(link));
+    }
+    extract_from_url(&url).map(ParsedLink::ProjectId)
+}
+
+//从完整链接中提取项目id，短链接跳转后的地址也用这个解析
+pub fn extract_project_id(link: &str) -> Result<String, String> {
+    let url = Url::parse(link.trim()).map_err(|e| format!("链接格式错误: {}", e))?;
+    extract_from_url(&url)
+}
+
+fn extract_from_url(url: &Url) -> Result<String, String> {
+    match url.scheme() {
+        "bilibili" => {
+            // bilibili://mall/web?url=<网页链接>，先解析内层链接
+            if let Some((_, inner)) = url.query_pairs().find(|(k, _)| k == "url") {
+                if let Ok(inner_url) = Url::parse(&inner) {
+                    if let Ok(id) = extract_from_url(&inner_url) {
+                        return Ok(id);
+                    }
+                }
+            }
+        }
+        "http" | "https" => {
+            let host = url.host_str().unwrap_or("").to_lowercase();
+            if host != "bilibili.com" && !host.ends_with(".bilibili.com") {
+                return Err(format!("不是b站会员购链接: {}", host));
+            }
+        }
+        scheme => return Err(format!("不支持的链接类型: {}", scheme)),
+    }
+    for key in ID_PARAMS {
+        if let Some((_, value)) = url.query_pairs().find(|(k, _)| k == key) {
+            if is_project_id(&value) {
+                return Ok(value.to_string());
+            }
+        }
+    }
+    // 部分链接把id放在路径里，如 /ticket/detail/12345
+    if let Some(segment) = url.path_segments().and_then(|mut s| s.next_back()) {
+        let segment = segment.trim_end_matches(".html");
+        if is_project_id(segment) {
+            return Ok(segment.to_string());
+        }
+    }
+    // 哈希路由的单页链接，id在#之后
+    if let Some(fragment) = url.fragment() {
+        if let Some(pos) = fragment.find('?') {
+            if let Ok(query) = Url::parse(&format!("http://localhost/?{}", &fragment[pos + 1..])) {
+                for (key, value) in query.query_pairs() {
+                    if ID_PARAMS.contains(&key.as_ref()) && is_project_id(&value) {
+                        return Ok(value.to_string());
+                    }
+                }
+            }
+        }
+    }
+    Err("链接中未找到项目id".to_string())
+}
+
+// 项目id为纯数字
+fn is_project_id(text: &str) -> bool {
+    !text.is_empty() && text.len() <= 12 && text.chars().all(|c| c.is_ascii_digit())
+}
+
+// 从分享文案中取出第一个链接
+fn find_link(text: &str) -> Option<String> {
+    let start = ["https://", "http://", "bilibili://"]
+        .iter()
+        .filter_map(|prefix| text.find(prefix))
+        .min()?;
+    let link: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_graphic() && !"\"'<>".contains(*c))
+        .collect();
+    Some(link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str) -> Result<ParsedLink, String> {
+        Ok(ParsedLink::ProjectId(id.to_string()))
+    }
+
+    #[test]
+    fn parses_ids_and_links() {
+        assert_eq!(parse_input(" 85939 "), project("85939"));
+        assert_eq!(parse_input("https://show.bilibili.com/platform/detail.html?id=85939&from=pc"), project("85939"));
+        assert_eq!(parse_input("https://mall.bilibili.com/neul-next/ticket/detail.html?project_id=85939"), project("85939"));
+        assert_eq!(parse_input("show.bilibili.com/platform/detail.html?id=85939"), project("85939"));
+    }
+
+    #[test]
+    fn parses_share_text_and_deep_links() {
+        assert_eq!(parse_input("快来看看 https://show.bilibili.com/platform/detail.html?id=85939 一起去吧"), project("85939"));
+        assert_eq!(
+            parse_input("bilibili://mall/web?url=https%3A%2F%2Fshow.bilibili.com%2Fplatform%2Fdetail.html%3Fid%3D85939"),
+            project("85939")
+        );
+    }
+
+    #[test]
+    fn keeps_short_links_for_resolving() {
+        assert_eq!(parse_input("https://b23.tv/AbCdEf"), Ok(ParsedLink::ShortLink("https://b23.tv/AbCdEf".to_string())));
+        assert_eq!(parse_input("b23.tv/AbCdEf"), Ok(ParsedLink::ShortLink("https://b23.tv/AbCdEf".to_string())));
+    }
+
+    #[test]
+    fn rejects_other_sites() {
+        assert!(parse_input("https://example.com/detail.html?id=85939").is_err());
+        assert!(parse_input("随便写点什么").is_err());
+        assert!(parse_input("").is_err());
+    }
+}
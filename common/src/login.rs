@@ -22,12 +22,19 @@ pub struct QrCodeLoginTask {
     pub status: QrCodeLoginStatus,
 }
 
+//登录成功后得到的凭据
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoginCredential {
+    pub cookie: String,
+    pub refresh_token: Option<String>, //用于之后刷新web登录态
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum QrCodeLoginStatus {
     Pending,
     Scanning,
     Confirming,
-    Success(String), //成功时返回cookie信息
+    Success(LoginCredential), //成功时返回cookie信息
     Failed(String),  //失败时返回错误信息
     Expired,
 }
@@ -136,7 +143,7 @@ pub async fn send_loginsms(phone: &str, client: &Client, custom_config: CustomCo
     
 }
 
-pub async fn sms_login(phone: &str, sms_code: &str, captcha_key:&str, client: &Client) -> Result<String, String> {
+pub async fn sms_login(phone: &str, sms_code: &str, captcha_key:&str, client: &Client) -> Result<LoginCredential, String> {
     let data = serde_json::json!({
         "cid": 86,
         "tel": phone.parse::<i64>().unwrap_or(0),
@@ -173,7 +180,8 @@ pub async fn sms_login(phone: &str, sms_code: &str, captcha_key:&str, client: &C
     if json_response["code"].as_i64() == Some(0) {
         log::info!("短信登录成功！");
        log::info!("登录cookie：{:?}", all_cookies);
-       return Ok(all_cookies.to_vec().join(";"));
+       let refresh_token = json_response["data"]["refresh_token"].as_str().filter(|t| !t.is_empty()).map(str::to_string);
+       return Ok(LoginCredential { cookie: all_cookies.to_vec().join(";"), refresh_token });
         
     }
     Err("短信登录失败".to_string())
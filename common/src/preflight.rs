@@ -0,0 +1,469 @@
+use crate::address::needs_delivery;
+use crate::push::PushConfig;
+use crate::ticket::{BilibiliTicket, BuyerInfo, ScreenInfo, ScreenTicketInfo};
+use crate::time_format::{format_beijing, format_relative};
+
+// 登录态剩余不足该时长时提醒
+const SESSION_WARN_SECS: i64 = 3 * 86400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckLevel {
+    Pass,
+    Warning,
+    Error, //阻止开始抢票
+}
+
+//检查项对应的修复入口
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixAction {
+    Relogin,
+    ReselectTicket,
+    AddBuyer,
+    PushSettings,
+}
+
+impl FixAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FixAction::Relogin => "重新登录",
+            FixAction::ReselectTicket => "重新选择场次",
+            FixAction::AddBuyer => "添加购票人",
+            FixAction::PushSettings => "前往推送设置",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckItem {
+    pub level: CheckLevel,
+    pub title: &'static str,
+    pub detail: String,
+    pub fix: Option<FixAction>,
+}
+
+impl CheckItem {
+    fn pass(title: &'static str, detail: impl Into<String>) -> Self {
+        Self { level: CheckLevel::Pass, title, detail: detail.into(), fix: None }
+    }
+
+    fn warning(title: &'static str, detail: impl Into<String>, fix: Option<FixAction>) -> Self {
+        Self { level: CheckLevel::Warning, title, detail: detail.into(), fix }
+    }
+
+    fn error(title: &'static str, detail: impl Into<String>, fix: Option<FixAction>) -> Self {
+        Self { level: CheckLevel::Error, title, detail: detail.into(), fix }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PreflightReport {
+    pub items: Vec<CheckItem>,
+}
+
+impl PreflightReport {
+    pub fn has_errors(&self) -> bool {
+        self.items.iter().any(|item| item.level == CheckLevel::Error)
+    }
+
+    pub fn count(&self, level: CheckLevel) -> usize {
+        self.items.iter().filter(|item| item.level == level).count()
+    }
+}
+
+//开始抢票前需要检查的内容
+pub struct PreflightInput<'a> {
+    pub biliticket: &'a BilibiliTicket,
+    pub screen: Option<&'a ScreenInfo>,
+    pub ticket_id: Option<i64>,
+    pub count: i32,
+    pub buyers: &'a [BuyerInfo],
+    pub push_config: &'a PushConfig,
+    pub now: i64,
+}
+
+pub fn run_preflight(input: &PreflightInput) -> PreflightReport {
+    let mut items = Vec::new();
+    check_session(input, &mut items);
+    let ticket = check_selection(input, &mut items);
+    check_count(input, ticket, &mut items);
+    check_buyers(input, &mut items);
+    check_address(input, &mut items);
+    check_push(input, &mut items);
+    PreflightReport { items }
+}
+
+fn check_session(input: &PreflightInput, items: &mut Vec<CheckItem>) {
+    let account = &input.biliticket.account;
+    if !account.is_login || !account.has_session_cookie() {
+        items.push(CheckItem::error("账号登录态", "cookie 缺少 SESSDATA 或 bili_jct，请重新登录", Some(FixAction::Relogin)));
+        return;
+    }
+    match account.session_expiry() {
+        Some(expiry) if expiry <= input.now => {
+            items.push(CheckItem::error(
+                "账号登录态",
+                format!("登录已于 {} 过期", format_beijing(expiry)),
+                Some(FixAction::Relogin),
+            ));
+        }
+        Some(expiry) if expiry - input.now < SESSION_WARN_SECS => {
+            items.push(CheckItem::warning(
+                "账号登录态",
+                format!("登录将在{}过期", format_relative(expiry, input.now)),
+                Some(FixAction::Relogin),
+            ));
+        }
+        Some(expiry) => items.push(CheckItem::pass("账号登录态", format!("有效期至 {}", format_beijing(expiry)))),
+        None => items.push(CheckItem::pass("账号登录态", "已登录（无法读取过期时间）")),
+    }
+}
+
+//检查项目、场次、票种，返回所选票种
+fn check_selection<'a>(input: &PreflightInput<'a>, items: &mut Vec<CheckItem>) -> Option<&'a ScreenTicketInfo> {
+    let project = match &input.biliticket.project_info {
+        Some(project) => project,
+        None => {
+            items.push(CheckItem::error("项目信息", "项目信息未加载", Some(FixAction::ReselectTicket)));
+            return None;
+        }
+    };
+    if project.sale_end > 0 && project.sale_end <= input.now {
+        items.push(CheckItem::error(
+            "售票时间",
+            format!("项目已于 {} 停止售票", format_beijing(project.sale_end)),
+            None,
+        ));
+    } else if project.sale_begin > input.now {
+        items.push(CheckItem::pass(
+            "售票时间",
+            format!("{} 开售（{}）", format_beijing(project.sale_begin), format_relative(project.sale_begin, input.now)),
+        ));
+    } else {
+        items.push(CheckItem::pass("售票时间", project.sale_flag.clone()));
+    }
+
+    let screen = match input.screen {
+        Some(screen) => screen,
+        None => {
+            items.push(CheckItem::error("场次", "未选择场次", Some(FixAction::ReselectTicket)));
+            return None;
+        }
+    };
+    items.push(CheckItem::pass("场次", screen.name.clone()));
+
+    let ticket_id = match input.ticket_id {
+        Some(id) if id >= 0 => id as usize,
+        _ => {
+            items.push(CheckItem::error("票种", "未选择票种", Some(FixAction::ReselectTicket)));
+            return None;
+        }
+    };
+    let ticket = match screen.ticket_list.iter().find(|t| t.id == ticket_id) {
+        Some(ticket) => ticket,
+        None => {
+            items.push(CheckItem::error(
+                "票种",
+                format!("票种ID {} 不在场次「{}」中", ticket_id, screen.name),
+                Some(FixAction::ReselectTicket),
+            ));
+            return None;
+        }
+    };
+    if ticket.clickable {
+        items.push(CheckItem::pass("票种", format!("{} ¥{:.2}", ticket.desc, ticket.price as f64 / 100.0)));
+    } else {
+        items.push(CheckItem::warning(
+            "票种",
+            format!("{} 当前状态：{}", ticket.desc, ticket.sale_flag.display_name),
+            None,
+        ));
+    }
+    Some(ticket)
+}
+
+fn check_count(input: &PreflightInput, ticket: Option<&ScreenTicketInfo>, items: &mut Vec<CheckItem>) {
+    if input.count < 1 {
+        items.push(CheckItem::error("购票数量", "购票数量至少为1", None));
+        return;
+    }
+    let limit = ticket
+        .and_then(|t| t.static_limit.as_ref())
+        .map(|limit| limit.num)
+        .filter(|num| *num > 0);
+    match limit {
+        Some(limit) if input.count as usize > limit => {
+            items.push(CheckItem::error(
+                "购票数量",
+                format!("该票种每单限购{}张，当前为{}张", limit, input.count),
+                None,
+            ));
+        }
+        _ => items.push(CheckItem::pass("购票数量", format!("{}张", input.count))),
+    }
+}
+
+fn check_buyers(input: &PreflightInput, items: &mut Vec<CheckItem>) {
+    let id_bind = input.biliticket.project_info.as_ref().map_or(input.biliticket.id_bind, |p| p.id_bind);
+    match id_bind {
+        0 => match &input.biliticket.no_bind_buyer_info {
+            Some(info) if !info.name.trim().is_empty() && is_phone(&info.tel) => {
+                items.push(CheckItem::pass("联系人", format!("{} {}", info.name, info.tel)));
+            }
+            _ => items.push(CheckItem::error("联系人", "非实名项目需要填写联系人姓名和11位手机号", None)),
+        },
+        1 | 2 => {
+            let needed = if id_bind == 2 { input.count.max(1) as usize } else { 1 };
+            let selected = input.buyers.len();
+            let count_ok = if id_bind == 2 { selected == needed } else { selected >= 1 };
+            if count_ok {
+                items.push(CheckItem::pass("购票人", format!("已选{}人", selected)));
+            } else {
+                let fix = if input.biliticket.all_buyer_info.as_ref().map_or(0, |d| d.list.len()) < needed {
+                    Some(FixAction::AddBuyer)
+                } else {
+                    None
+                };
+                let detail = if id_bind == 2 {
+                    format!("一票一证项目需要选择{}位购票人，当前已选{}位", needed, selected)
+                } else {
+                    "实名项目至少需要选择1位购票人".to_string()
+                };
+                items.push(CheckItem::error("购票人", detail, fix));
+            }
+            let unverified: Vec<&str> = input
+                .buyers
+                .iter()
+                .filter(|b| b.verify_status != 1 && !b.isBuyerInfoVerified)
+                .map(|b| b.name.as_str())
+                .collect();
+            // 实名项目用未认证的购票人下单必定失败
+            if !unverified.is_empty() {
+                items.push(CheckItem::error(
+                    "实名认证",
+                    format!("以下购票人未通过实名认证：{}", unverified.join("、")),
+                    Some(FixAction::AddBuyer),
+                ));
+            } else if selected > 0 {
+                items.push(CheckItem::pass("实名认证", "所选购票人均已认证"));
+            }
+        }
+        other => items.push(CheckItem::warning("购票人", format!("未知的实名类型({})，将直接尝试下单", other), None)),
+    }
+}
+
+fn check_address(input: &PreflightInput, items: &mut Vec<CheckItem>) {
+    let needs = match (&input.biliticket.project_info, input.screen) {
+        (Some(project), Some(screen)) => needs_delivery(project, screen),
+        _ => false,
+    };
+    if needs {
+        // 下单接口带地址的格式未验证，不提交地址
+        items.push(CheckItem::warning("收货地址", "纸质票场次，本工具不会提交收货地址，可能下单失败，建议在网页端下单", None));
+    }
+}
+
+fn check_push(input: &PreflightInput, items: &mut Vec<CheckItem>) {
+    if !input.push_config.enabled {
+        items.push(CheckItem::warning("推送通知", "未开启推送，抢到票后可能错过支付时间", Some(FixAction::PushSettings)));
+        return;
+    }
+    match input.push_config.configured_channels() {
+        0 => items.push(CheckItem::warning("推送通知", "已开启推送但没有填写任何渠道", Some(FixAction::PushSettings))),
+        n => items.push(CheckItem::pass("推送通知", format!("已配置{}个渠道", n))),
+    }
+}
+
+fn is_phone(tel: &str) -> bool {
+    let tel = tel.trim();
+    tel.len() == 11 && tel.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::address::DELIVERY_TYPE_EXPRESS;
+    use crate::ticket::TicketInfo;
+    use crate::utility::CustomConfig;
+    use serde_json::json;
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn account(expiry: i64) -> Account {
+        serde_json::from_value(json!({
+            "uid": 12345,
+            "name": "测试账号",
+            "level": "6",
+            "cookie": format!("SESSDATA=abc%2C{}%2Cxyz; bili_jct=csrf", expiry),
+            "csrf": "csrf",
+            "is_login": true,
+            "account_status": "正常",
+            "vip_label": "",
+            "is_active": true,
+            "avatar_url": null,
+        }))
+        .unwrap()
+    }
+
+    fn project(id_bind: usize, delivery_type: usize) -> TicketInfo {
+        let ticket = json!({
+            "saleStart": NOW - 3600,
+            "saleEnd": NOW + 86400,
+            "id": 466120,
+            "project_id": 85939,
+            "price": 38000,
+            "desc": "内场",
+            "sale_start": "",
+            "sale_end": "",
+            "type": 1,
+            "sale_type": 1,
+            "is_sale": 0,
+            "num": 1,
+            "sale_flag": { "number": 2, "display_name": "预售中" },
+            "clickable": true,
+            "sale_flag_number": 2,
+            "screen_name": "5月1日 晚场",
+            "static_limit": { "num": 2, "msg": "" },
+        });
+        let screen = json!({
+            "id": 153744,
+            "start_time": NOW + 86400,
+            "name": "5月1日 晚场",
+            "ticket_type": 1,
+            "screen_type": 1,
+            "delivery_type": delivery_type,
+            "pick_seat": 0,
+            "clickable": true,
+            "sale_end": NOW + 86400,
+            "sale_start": NOW - 3600,
+            "sale_flag_number": 2,
+            "show_date": "",
+            "ticket_list": [ticket],
+        });
+        serde_json::from_value(json!({
+            "id": 85939,
+            "name": "测试演出",
+            "is_sale": 1,
+            "start_time": NOW + 86400,
+            "end_time": NOW + 90000,
+            "pick_seat": 0,
+            "project_type": 1,
+            "express_fee": 1000,
+            "sale_begin": NOW - 3600,
+            "sale_end": NOW + 86400,
+            "count_down": 0,
+            "sale_flag_number": 2,
+            "sale_flag": "预售中",
+            "is_free": false,
+            "performance_desc": null,
+            "id_bind": id_bind,
+            "hotProject": false,
+            "screen_list": [screen],
+        }))
+        .unwrap()
+    }
+
+    fn buyer(name: &str, verified: bool) -> BuyerInfo {
+        BuyerInfo {
+            id: 1,
+            uid: 12345,
+            personal_id: "110101199001011234".to_string(),
+            name: name.to_string(),
+            tel: "13800138000".to_string(),
+            id_type: 0,
+            is_default: 0,
+            id_card_front: String::new(),
+            id_card_back: String::new(),
+            verify_status: if verified { 1 } else { 0 },
+            isBuyerInfoVerified: verified,
+            isBuyerValid: true,
+        }
+    }
+
+    fn ticket(project: TicketInfo, expiry: i64) -> BilibiliTicket {
+        let mut ticket = BilibiliTicket::new(
+            &0,
+            &"test".to_string(),
+            &CustomConfig::new(),
+            &account(expiry),
+            &PushConfig::new(),
+            &0,
+            "85939",
+        );
+        ticket.id_bind = project.id_bind;
+        ticket.project_info = Some(project);
+        ticket
+    }
+
+    fn run(biliticket: &BilibiliTicket, count: i32, buyers: &[BuyerInfo]) -> PreflightReport {
+        let screen = biliticket.project_info.as_ref().unwrap().screen_list.first();
+        run_preflight(&PreflightInput {
+            biliticket,
+            screen,
+            ticket_id: Some(466120),
+            count,
+            buyers,
+            push_config: &PushConfig::new(),
+            now: NOW,
+        })
+    }
+
+    fn item<'a>(report: &'a PreflightReport, title: &str) -> &'a CheckItem {
+        report.items.iter().find(|i| i.title == title).unwrap()
+    }
+
+    #[test]
+    fn ready_ticket_has_no_errors() {
+        let biliticket = ticket(project(1, 1), NOW + 30 * 86400);
+        let report = run(&biliticket, 1, &[buyer("张三", true)]);
+        assert!(!report.has_errors(), "{:?}", report.items);
+        assert_eq!(item(&report, "实名认证").level, CheckLevel::Pass);
+        assert!(report.items.iter().all(|i| i.title != "收货地址"));
+    }
+
+    #[test]
+    fn unverified_buyer_blocks_real_name_project() {
+        let biliticket = ticket(project(1, 1), NOW + 30 * 86400);
+        let report = run(&biliticket, 1, &[buyer("张三", false)]);
+        let check = item(&report, "实名认证");
+        assert_eq!(check.level, CheckLevel::Error);
+        assert!(check.detail.contains("张三"));
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn one_buyer_per_ticket_needs_matching_count() {
+        let biliticket = ticket(project(2, 1), NOW + 30 * 86400);
+        let report = run(&biliticket, 2, &[buyer("张三", true)]);
+        let check = item(&report, "购票人");
+        assert_eq!(check.level, CheckLevel::Error);
+        // 账号里的购票人不够时引导去添加
+        assert_eq!(check.fix, Some(FixAction::AddBuyer));
+    }
+
+    #[test]
+    fn count_over_limit_is_an_error() {
+        let biliticket = ticket(project(1, 1), NOW + 30 * 86400);
+        let report = run(&biliticket, 3, &[buyer("张三", true)]);
+        assert_eq!(item(&report, "购票数量").level, CheckLevel::Error);
+    }
+
+    #[test]
+    fn session_expiry_levels() {
+        let expired = run(&ticket(project(1, 1), NOW - 1), 1, &[buyer("张三", true)]);
+        let check = item(&expired, "账号登录态");
+        assert_eq!(check.level, CheckLevel::Error);
+        assert_eq!(check.fix, Some(FixAction::Relogin));
+
+        let expiring = run(&ticket(project(1, 1), NOW + 3600), 1, &[buyer("张三", true)]);
+        assert_eq!(item(&expiring, "账号登录态").level, CheckLevel::Warning);
+    }
+
+    #[test]
+    fn express_screen_warns_about_address() {
+        let biliticket = ticket(project(1, DELIVERY_TYPE_EXPRESS), NOW + 30 * 86400);
+        let report = run(&biliticket, 1, &[buyer("张三", true)]);
+        assert_eq!(item(&report, "收货地址").level, CheckLevel::Warning);
+        assert!(!report.has_errors());
+    }
+}
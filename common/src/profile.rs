@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+
+use crate::data_dir::data_dir;
+use crate::config::AppConfig;
+
+// 默认档案，沿用数据目录根下的config，兼容旧版本
+pub const DEFAULT_PROFILE: &str = "default";
+// 记录上次使用的档案
+const LAST_PROFILE_FILE: &str = "last_profile";
+
+static ACTIVE_PROFILE: Lazy<RwLock<String>> = Lazy::new(|| RwLock::new(DEFAULT_PROFILE.to_string()));
+
+pub fn profiles_root() -> PathBuf {
+    data_dir().join("profiles")
+}
+
+pub fn profile_dir(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        data_dir().to_path_buf()
+    } else {
+        profiles_root().join(name)
+    }
+}
+
+pub fn config_path_of(name: &str) -> PathBuf {
+    profile_dir(name).join("config")
+}
+
+pub fn active_profile() -> String {
+    ACTIVE_PROFILE.read().unwrap().clone()
+}
+
+//启动时恢复上次使用的档案，档案不存在时回到默认档案
+pub fn init() -> String {
+    let last = fs::read_to_string(data_dir().join(LAST_PROFILE_FILE))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let name = if !last.is_empty() && profile_exists(&last) {
+        last
+    } else {
+        DEFAULT_PROFILE.to_string()
+    };
+    *ACTIVE_PROFILE.write().unwrap() = name.clone();
+    log::info!("当前配置档案: {}", name);
+    name
+}
+
+pub fn profile_exists(name: &str) -> bool {
+    name == DEFAULT_PROFILE || profile_dir(name).is_dir()
+}
+
+pub fn list_profiles() -> Vec<String> {
+    let mut profiles = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(entries) = fs::read_dir(profiles_root()) {
+        let mut others: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name != DEFAULT_PROFILE)
+            .collect();
+        others.sort();
+        profiles.extend(others);
+    }
+    profiles
+}
+
+//档案名会作为目录名，禁止路径分隔符等字符
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("档案名不能为空".to_string());
+    }
+    if name.chars().count() > 32 {
+        return Err("档案名不能超过32个字符".to_string());
+    }
+    if name.starts_with('.') {
+        return Err("档案名不能以.开头".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| "/\\:*?\"<>|".contains(*c) || c.is_control()) {
+        return Err(format!("档案名包含非法字符: {}", c));
+    }
+    Ok(())
+}
+
+pub fn create_profile(name: &str) -> Result<(), String> {
+    let name = name.trim();
+    validate_name(name)?;
+    if profile_exists(name) {
+        return Err(format!("档案 {} 已存在", name));
+    }
+    fs::create_dir_all(profile_dir(name)).map_err(|e| format!("创建档案目录失败: {}", e))?;
+    AppConfig::new()
+        .save_to(&config_path_of(name))
+        .map_err(|e| format!("写入档案配置失败: {}", e))?;
+    log::info!("已创建配置档案: {}", name);
+    Ok(())
+}
+
+//复制档案（账号、推送、自定义设置一并复制）
+pub fn duplicate_profile(source: &str, target: &str) -> Result<(), String> {
+    let target = target.trim();
+    validate_name(target)?;
+    if !profile_exists(source) {
+        return Err(format!("档案 {} 不存在", source));
+    }
+    if profile_exists(target) {
+        return Err(format!("档案 {} 已存在", target));
+    }
+    fs::create_dir_all(profile_dir(target)).map_err(|e| format!("创建档案目录失败: {}", e))?;
+    let source_config = config_path_of(source);
+    let result = if source_config.exists() {
+        fs::copy(&source_config, config_path_of(target)).map(|_| ()).map_err(|e| e.to_string())
+    } else {
+        AppConfig::new().save_to(&config_path_of(target))
+    };
+    result.map_err(|e| format!("复制档案配置失败: {}", e))?;
+    log::info!("已复制配置档案: {} -> {}", source, target);
+    Ok(())
+}
+
+pub fn delete_profile(name: &str) -> Result<(), String> {
+    if name == DEFAULT_PROFILE {
+        return Err("默认档案不能删除".to_string());
+    }
+    if name == active_profile() {
+        return Err("不能删除正在使用的档案，请先切换到其他档案".to_string());
+    }
+    if !profile_exists(name) {
+        return Err(format!("档案 {} 不存在", name));
+    }
+    fs::remove_dir_all(profile_dir(name)).map_err(|e| format!("删除档案失败: {}", e))?;
+    log::info!("已删除配置档案: {}", name);
+    Ok(())
+}
+
+//切换档案并记住，调用方负责重新加载配置
+pub fn switch_profile(name: &str) -> Result<(), String> {
+    if !profile_exists(name) {
+        return Err(format!("档案 {} 不存在", name));
+    }
+    *ACTIVE_PROFILE.write().unwrap() = name.to_string();
+    fs::write(data_dir().join(LAST_PROFILE_FILE), name)
+        .map_err(|e| format!("记录当前档案失败: {}", e))?;
+    log::info!("已切换到配置档案: {}", name);
+    Ok(())
+}
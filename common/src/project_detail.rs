@@ -0,0 +1,203 @@
+use serde_json::Value;
+
+use crate::ticket::{BaseInfoItem, TicketInfo};
+
+// 票价矩阵：行是票种，列是场次，没有该票种的场次为None
+#[derive(Clone, Debug, Default)]
+pub struct PriceMatrix {
+    pub ticket_names: Vec<String>,
+    pub screen_names: Vec<String>,
+    pub cells: Vec<Vec<Option<PriceCell>>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PriceCell {
+    pub price: usize, //分
+    pub status: String,
+    pub clickable: bool,
+}
+
+// 项目详情里的说明模块（购票须知、观演须知等）
+#[derive(Clone, Debug)]
+pub struct DescSection {
+    pub title: String,
+    pub text: String,
+}
+
+//实名制说明，id_bind 与确认下单页的处理保持一致
+pub fn id_bind_text(id_bind: usize) -> &'static str {
+    match id_bind {
+        0 => "非实名，只需填写联系人",
+        1 => "一单一证，每单选择一位实名购票人",
+        2 => "一票一证，每张票对应一位实名购票人",
+        _ => "未知",
+    }
+}
+
+//base_info 模块的标题/内容列表（实名认证、退票规则、入场规则等）
+pub fn base_info_items(info: &TicketInfo) -> Vec<BaseInfoItem> {
+    let mut items = Vec::new();
+    let desc = match &info.performance_desc {
+        Some(desc) => desc,
+        None => return items,
+    };
+    for module in desc.list.iter().filter(|m| m.module == "base_info") {
+        if let Some(array) = module.details.as_array() {
+            for detail in array {
+                let title = detail.get("title").and_then(Value::as_str).unwrap_or("");
+                let content = detail.get("content").and_then(Value::as_str).unwrap_or("");
+                if title.is_empty() && content.is_empty() {
+                    continue;
+                }
+                items.push(BaseInfoItem {
+                    title: title.to_string(),
+                    content: html_to_text(content),
+                });
+            }
+        }
+    }
+    items
+}
+
+//base_info 以外的模块，转换成纯文本
+pub fn desc_sections(info: &TicketInfo) -> Vec<DescSection> {
+    let mut sections = Vec::new();
+    let desc = match &info.performance_desc {
+        Some(desc) => desc,
+        None => return sections,
+    };
+    for module in desc.list.iter().filter(|m| m.module != "base_info") {
+        let text = match &module.details {
+            Value::String(html) => html_to_text(html),
+            Value::Array(array) => array
+                .iter()
+                .map(|item| {
+                    let title = item.get("title").and_then(Value::as_str).unwrap_or("");
+                    let content = item.get("content").and_then(Value::as_str).unwrap_or("");
+                    if title.is_empty() {
+                        html_to_text(content)
+                    } else {
+                        format!("{}：{}", title, html_to_text(content))
+                    }
+                })
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+        let title = module
+            .module_name
+            .clone()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| module_title(&module.module).to_string());
+        sections.push(DescSection { title, text });
+    }
+    sections
+}
+
+//限购信息：票种自带的限购 + base_info 中的限购说明
+pub fn purchase_limits(info: &TicketInfo) -> Vec<String> {
+    let mut limits = Vec::new();
+    for screen in &info.screen_list {
+        for ticket in &screen.ticket_list {
+            if let Some(limit) = &ticket.static_limit {
+                if limit.num == 0 {
+                    continue;
+                }
+                let line = format!("{} {}: 每单限购{}张", screen.name, ticket.desc, limit.num);
+                if !limits.contains(&line) {
+                    limits.push(line);
+                }
+            }
+        }
+    }
+    for item in base_info_items(info) {
+        if item.title.contains("限购") || item.content.contains("限购") {
+            limits.push(format!("{}：{}", item.title, item.content));
+        }
+    }
+    limits
+}
+
+pub fn price_matrix(info: &TicketInfo) -> PriceMatrix {
+    let mut matrix = PriceMatrix::default();
+    for screen in &info.screen_list {
+        matrix.screen_names.push(screen.name.clone());
+        for ticket in &screen.ticket_list {
+            if !matrix.ticket_names.contains(&ticket.desc) {
+                matrix.ticket_names.push(ticket.desc.clone());
+            }
+        }
+    }
+    for name in &matrix.ticket_names {
+        let row = info
+            .screen_list
+            .iter()
+            .map(|screen| {
+                screen.ticket_list.iter().find(|t| &t.desc == name).map(|t| PriceCell {
+                    price: t.price,
+                    status: t.sale_flag.display_name.clone(),
+                    clickable: t.clickable,
+                })
+            })
+            .collect();
+        matrix.cells.push(row);
+    }
+    matrix
+}
+
+fn module_title(module: &str) -> &str {
+    match module {
+        "activity_content" => "活动详情",
+        "purchase_notice" => "购票须知",
+        "watching_notice" => "观演须知",
+        "refund_notice" => "退票说明",
+        _ => module,
+    }
+}
+
+//详情内容是html片段，去掉标签并保留换行
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut tag = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                if matches!(name.as_str(), "br" | "p" | "div" | "li" | "h1" | "h2" | "h3" | "h4")
+                    && !text.ends_with('\n')
+                {
+                    text.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ => text.push(c),
+        }
+    }
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,102 @@
+use serde_json::Value;
+
+// 关键词搜索的单条结果（演出/展览）
+#[derive(Clone, Debug, Default)]
+pub struct ProjectSearchItem {
+    pub id: String,
+    pub name: String,
+    pub city: String,
+    pub venue: String,
+    pub show_time: String,
+    pub price_low: i64,  // 单位：分
+    pub price_high: i64, // 单位：分
+    pub sale_status: String,
+}
+
+impl ProjectSearchItem {
+    pub fn price_range_text(&self) -> String {
+        match (self.price_low, self.price_high) {
+            (0, 0) => "价格待定".to_string(),
+            (low, high) if high <= low => format!("¥{}", format_price(low)),
+            (low, high) => format!("¥{} - ¥{}", format_price(low), format_price(high)),
+        }
+    }
+
+    pub fn location_text(&self) -> String {
+        match (self.city.is_empty(), self.venue.is_empty()) {
+            (false, false) => format!("{} · {}", self.city, self.venue),
+            (false, true) => self.city.clone(),
+            (true, false) => self.venue.clone(),
+            (true, true) => "场馆待定".to_string(),
+        }
+    }
+}
+
+//解析搜索接口返回，字段名在不同版本接口间不完全一致，逐个兜底
+pub fn parse_search_response(json: &Value) -> Result<Vec<ProjectSearchItem>, String> {
+    let errno = json["errno"].as_i64().or_else(|| json["code"].as_i64()).unwrap_or(-1);
+    if errno != 0 {
+        let msg = json["msg"].as_str().or_else(|| json["message"].as_str()).unwrap_or("未知错误");
+        return Err(format!("搜索失败({}): {}", errno, msg));
+    }
+    let list = json["data"]["result"]
+        .as_array()
+        .or_else(|| json["data"]["list"].as_array())
+        .cloned()
+        .unwrap_or_default();
+    let items = list
+        .iter()
+        .filter_map(|item| {
+            let id = value_text(item, &["id", "project_id"]);
+            if id.is_empty() {
+                return None;
+            }
+            Some(ProjectSearchItem {
+                id,
+                name: strip_tags(&value_text(item, &["project_name", "name", "title"])),
+                city: value_text(item, &["city", "city_name"]),
+                venue: value_text(item, &["venue_name", "venue"]),
+                show_time: value_text(item, &["show_time", "start_time", "sale_time"]),
+                price_low: item["price_low"].as_i64().unwrap_or(0),
+                price_high: item["price_high"].as_i64().unwrap_or(0),
+                sale_status: value_text(item, &["sale_flag", "sale_status"]),
+            })
+        })
+        .collect();
+    Ok(items)
+}
+
+// 取第一个存在的字段，数字也转成字符串
+fn value_text(item: &Value, keys: &[&str]) -> String {
+    for key in keys {
+        match &item[*key] {
+            Value::String(s) if !s.is_empty() => return s.clone(),
+            Value::Number(n) => return n.to_string(),
+            _ => {}
+        }
+    }
+    String::new()
+}
+
+// 搜索结果的标题会带高亮标签，如 <em class="keyword">BW</em>
+fn strip_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+fn format_price(cents: i64) -> String {
+    if cents % 100 == 0 {
+        (cents / 100).to_string()
+    } else {
+        format!("{:.2}", cents as f64 / 100.0)
+    }
+}
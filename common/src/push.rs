@@ -15,7 +15,8 @@ pub struct PushConfig{
     pub wechat_token: String,
     pub gotify_config: GotifyConfig,
     pub smtp_config: SmtpConfig,
-
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>, //不认识的字段，保存时原样写回
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -55,6 +56,7 @@ impl PushConfig{
             wechat_token: String::new(),
             gotify_config: GotifyConfig::new(),
             smtp_config: SmtpConfig::new(),
+            extra: serde_json::Map::new(),
         }
     }
 
@@ -81,6 +83,22 @@ impl PushConfig{
 
     }
 
+    //已填写的推送渠道数（与 push_all_async 的判断一致）
+    pub fn configured_channels(&self) -> usize {
+        [
+            !self.bark_token.is_empty(),
+            !self.pushplus_token.is_empty(),
+            !self.fangtang_token.is_empty(),
+            !self.dingtalk_token.is_empty(),
+            !self.wechat_token.is_empty(),
+            !self.smtp_config.smtp_server.is_empty(),
+            !self.gotify_config.gotify_token.is_empty(),
+        ]
+        .iter()
+        .filter(|configured| **configured)
+        .count()
+    }
+
     pub async fn push_all_async(&self, title:&str, message: &str, jump_url:&Option<String>) -> (bool,String){
         let mut success_count = 0;
         let mut failure_count = 0;
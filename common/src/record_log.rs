@@ -1,9 +1,9 @@
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use log::{Record, Level, Metadata, LevelFilter, SetLoggerError};
 use once_cell::sync::Lazy;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
-use std::path::Path;
 
 
 // 日志文件处理相关内容
@@ -11,18 +11,36 @@ lazy_static::lazy_static! {
     static ref LOG_FILE: Mutex<Option<(String, File)>> = Mutex::new(None);
 }
 
+// 崩溃报告/诊断包中附带的最近日志条数
+const RECENT_LOG_LIMIT: usize = 1000;
+
+static RECENT_LOGS: Lazy<Mutex<VecDeque<String>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+//最近的日志（未脱敏），用于生成崩溃报告
+pub fn recent_logs() -> Vec<String> {
+    match RECENT_LOGS.try_lock() {
+        Ok(logs) => logs.iter().cloned().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+//当前日志文件路径
+pub fn current_log_file() -> Option<String> {
+    LOG_FILE.try_lock().ok().and_then(|file| file.as_ref().map(|(name, _)| name.clone()))
+}
+
 // 创建新的日志文件
 fn create_log_file() -> Option<(String, File)> {
     // 确保日志目录存在
-    let log_dir = Path::new("Log");
-    if let Err(e) = fs::create_dir_all(log_dir) {
+    let log_dir = crate::data_dir::log_dir();
+    if let Err(e) = fs::create_dir_all(&log_dir) {
         eprintln!("无法创建日志目录: {}", e);
         return None;
     }
     
     // 创建带有时间戳的文件名
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("Log/log_{}.log", timestamp);
+    let filename = log_dir.join(format!("log_{}.log", timestamp)).to_string_lossy().to_string();
     
     // 打开文件
     match OpenOptions::new()
@@ -114,7 +132,7 @@ impl log::Log for CollectorLogger{
     
     fn log(&self,record: &Record){
         if self.enabled(record.metadata()){
-            let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S:%3f");
+            let timestamp = chrono::Local::now().format(crate::time_format::LOG_FORMAT);
             let log_message = format!("[{}] {}: {}", 
                 timestamp, record.level(), record.args());
 
@@ -122,6 +140,12 @@ impl log::Log for CollectorLogger{
                     if let Ok(mut collector) = LOG_COLLECTOR.try_lock() { // 使用 try_lock 避免长时间等待
                         collector.add(log_message.clone());
                     }
+                    if let Ok(mut recent) = RECENT_LOGS.try_lock() {
+                        if recent.len() >= RECENT_LOG_LIMIT {
+                            recent.pop_front();
+                        }
+                        recent.push_back(log_message.clone());
+                    }
                 }
             
 
@@ -0,0 +1,711 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::Serialize;
+use serde_json::{Error, Map, Value};
+
+use crate::data_dir;
+
+// 同一次解析最多修补多少次缺失/出错的字段，防止死循环
+const MAX_REPAIRS: usize = 64;
+// 已见过的多余字段记录文件，只有相对它新增的字段才算"新字段"
+const BASELINE_FILE: &str = "api_fields.json";
+
+//一次解析的接口变化报告，字段路径中的数组下标统一写成 []
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DriftReport {
+    pub endpoint: String,
+    pub missing: Vec<String>,              //缺失的必需字段（已按默认值处理）
+    pub type_changed: Vec<(String, String)>, //(字段路径, 说明) 类型变化（已尽量转换）
+    pub unexpected: Vec<String>,           //新出现的字段
+    pub at: i64,
+}
+
+impl DriftReport {
+    //缺字段或类型变化，解析结果可能不完整
+    pub fn is_breaking(&self) -> bool {
+        !self.missing.is_empty() || !self.type_changed.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.is_breaking() && self.unexpected.is_empty()
+    }
+
+    //缺失或类型变化的字段中涉及关键字段的部分（上级字段出错也算）
+    pub fn broken_fields(&self, critical: &[&str]) -> Vec<String> {
+        self.missing
+            .iter()
+            .chain(self.type_changed.iter().map(|(path, _)| path))
+            .filter(|path| {
+                critical.iter().any(|field| {
+                    *field == path.as_str()
+                        || field.strip_prefix(path.as_str()).is_some_and(|rest| rest.starts_with('.') || rest.starts_with("[]"))
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    //抢票/下单用到的id、价格、时间等字段出错时不能按默认值继续，返回错误
+    pub fn ensure_fields(&self, critical: &[&str]) -> Result<(), String> {
+        let broken = self.broken_fields(critical);
+        if broken.is_empty() {
+            return Ok(());
+        }
+        Err(format!("{}关键字段异常（{}），B站接口可能已变化，为避免按错误数据下单已停止", self.endpoint, broken.join("、")))
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.missing.is_empty() {
+            parts.push(format!("缺少字段 {}", self.missing.join("、")));
+        }
+        if !self.type_changed.is_empty() {
+            let changed: Vec<&str> = self.type_changed.iter().map(|(path, _)| path.as_str()).collect();
+            parts.push(format!("类型变化 {}", changed.join("、")));
+        }
+        if !self.unexpected.is_empty() {
+            parts.push(format!("新字段 {}", self.unexpected.join("、")));
+        }
+        format!("{}：{}", self.endpoint, parts.join("；"))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Seg {
+    Key(String),
+    Index(usize),
+}
+
+fn display_path(path: &[Seg]) -> String {
+    let mut out = String::new();
+    for seg in path {
+        match seg {
+            Seg::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            Seg::Index(_) => out.push_str("[]"),
+        }
+    }
+    if out.is_empty() {
+        "(根)".to_string()
+    } else {
+        out
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    type_changed: BTreeMap<String, String>,
+    unexpected: BTreeSet<String>,
+    failure: Option<(Vec<Seg>, String)>, //最内层出错的位置，外层据此修补后重试
+}
+
+//带路径记录、能容忍类型变化的反序列化器
+#[derive(Clone)]
+struct Tolerant<'a> {
+    value: &'a Value,
+    path: Vec<Seg>,
+    collector: &'a RefCell<Collector>,
+}
+
+impl<'a> Tolerant<'a> {
+    fn child(&self, value: &'a Value, seg: Seg) -> Self {
+        let mut path = self.path.clone();
+        path.push(seg);
+        Tolerant { value, path, collector: self.collector }
+    }
+
+    fn changed(&self, expected: &str) {
+        let actual = json_type_name(self.value);
+        self.collector
+            .borrow_mut()
+            .type_changed
+            .entry(display_path(&self.path))
+            .or_insert_with(|| format!("应为{}，实际为{}", expected, actual));
+    }
+
+    //记录最内层的错误位置
+    fn fail<T>(&self, result: Result<T, Error>) -> Result<T, Error> {
+        if let Err(e) = &result {
+            let mut collector = self.collector.borrow_mut();
+            if collector.failure.is_none() {
+                collector.failure = Some((self.path.clone(), e.to_string()));
+            }
+        }
+        result
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self.value {
+            Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+            Value::String(s) => s.trim().parse::<f64>().ok().map(|f| f as i64),
+            Value::Bool(b) => Some(*b as i64),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self.value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse::<f64>().ok(),
+            Value::Bool(b) => Some(*b as i64 as f64),
+            _ => None,
+        }
+    }
+
+    fn signed<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        if let Some(n) = self.value.as_i64() {
+            return self.fail(visitor.visit_i64(n));
+        }
+        self.changed("整数");
+        let n = self.as_i64().unwrap_or(0);
+        self.fail(visitor.visit_i64(n))
+    }
+
+    fn unsigned<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        if let Some(n) = self.value.as_u64() {
+            return self.fail(visitor.visit_u64(n));
+        }
+        self.changed("非负整数");
+        let n = self.as_i64().unwrap_or(0).max(0) as u64;
+        self.fail(visitor.visit_u64(n))
+    }
+
+    fn float<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        if !self.value.is_number() {
+            self.changed("数字");
+        }
+        let n = self.as_f64().unwrap_or(0.0);
+        self.fail(visitor.visit_f64(n))
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "布尔",
+        Value::Number(_) => "数字",
+        Value::String(_) => "字符串",
+        Value::Array(_) => "数组",
+        Value::Object(_) => "对象",
+    }
+}
+
+macro_rules! forward_signed {
+    ($($method:ident)*) => {
+        $(fn $method<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> { self.signed(visitor) })*
+    };
+}
+macro_rules! forward_unsigned {
+    ($($method:ident)*) => {
+        $(fn $method<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> { self.unsigned(visitor) })*
+    };
+}
+
+impl<'a> de::Deserializer<'a> for Tolerant<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        let result = de::Deserializer::deserialize_any(self.value, visitor);
+        self.fail(result)
+    }
+
+    forward_signed!(deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64);
+    forward_unsigned!(deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64);
+
+    fn deserialize_f32<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.float(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.float(visitor)
+    }
+
+    fn deserialize_bool<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        let b = match self.value {
+            Value::Bool(b) => return self.fail(visitor.visit_bool(*b)),
+            Value::String(s) => s == "true" || s == "1",
+            _ => self.as_i64().unwrap_or(0) != 0,
+        };
+        self.changed("布尔");
+        self.fail(visitor.visit_bool(b))
+    }
+
+    fn deserialize_char<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        let text = match self.value {
+            Value::String(s) => return self.fail(visitor.visit_borrowed_str(s)),
+            Value::Null => String::new(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            other => other.to_string(),
+        };
+        self.changed("字符串");
+        self.fail(visitor.visit_string(text))
+    }
+
+    fn deserialize_string<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        let result = de::Deserializer::deserialize_bytes(self.value, visitor);
+        self.fail(result)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        let result = de::Deserializer::deserialize_byte_buf(self.value, visitor);
+        self.fail(result)
+    }
+
+    fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.value.is_null() {
+            self.fail(visitor.visit_none())
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.fail(visitor.visit_unit())
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'a>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        self.fail(visitor.visit_unit())
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'a>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        static EMPTY: Vec<Value> = Vec::new();
+        let items = match self.value {
+            Value::Array(items) => items,
+            _ => {
+                self.changed("数组");
+                &EMPTY
+            }
+        };
+        let access = TolerantSeq { parent: self.clone(), items: items.iter().enumerate() };
+        self.fail(visitor.visit_seq(access))
+    }
+
+    fn deserialize_tuple<V: Visitor<'a>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'a>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        static EMPTY: Lazy<Map<String, Value>> = Lazy::new(Map::new);
+        let map = match self.value {
+            Value::Object(map) => map,
+            _ => {
+                self.changed("对象");
+                &EMPTY
+            }
+        };
+        let access = TolerantMap { parent: self.clone(), entries: map.iter(), pending: None };
+        self.fail(visitor.visit_map(access))
+    }
+
+    fn deserialize_struct<V: Visitor<'a>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        if let Value::Object(map) = self.value {
+            let mut collector = self.collector.borrow_mut();
+            for key in map.keys().filter(|key| !fields.contains(&key.as_str())) {
+                let mut path = self.path.clone();
+                path.push(Seg::Key(key.clone()));
+                collector.unexpected.insert(display_path(&path));
+            }
+        }
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'a>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        let result = de::Deserializer::deserialize_enum(self.value, name, variants, visitor);
+        self.fail(result)
+    }
+
+    fn deserialize_identifier<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct TolerantSeq<'a> {
+    parent: Tolerant<'a>,
+    items: std::iter::Enumerate<std::slice::Iter<'a, Value>>,
+}
+
+impl<'a> SeqAccess<'a> for TolerantSeq<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        match self.items.next() {
+            Some((index, value)) => seed.deserialize(self.parent.child(value, Seg::Index(index))).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct TolerantMap<'a> {
+    parent: Tolerant<'a>,
+    entries: serde_json::map::Iter<'a>,
+    pending: Option<(&'a String, &'a Value)>,
+}
+
+impl<'a> MapAccess<'a> for TolerantMap<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'a>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.pending = Some((key, value));
+                seed.deserialize(BorrowedStrDeserializer::<Error>::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'a>>(&mut self, seed: S) -> Result<S::Value, Error> {
+        match self.pending.take() {
+            Some((key, value)) => seed.deserialize(self.parent.child(value, Seg::Key(key.clone()))),
+            None => seed.deserialize(().into_deserializer()),
+        }
+    }
+}
+
+//按路径取出可修改的值
+fn value_at_mut<'v>(root: &'v mut Value, path: &[Seg]) -> Option<&'v mut Value> {
+    let mut current = root;
+    for seg in path {
+        current = match seg {
+            Seg::Key(key) => current.as_object_mut()?.get_mut(key)?,
+            Seg::Index(index) => current.as_array_mut()?.get_mut(*index)?,
+        };
+    }
+    Some(current)
+}
+
+fn missing_field_name(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("missing field `")?;
+    Some(rest[..rest.find('`')?].to_string())
+}
+
+//宽容解析：缺少的必需字段按默认值补上，类型不对的字段尽量转换，出错的字段置空后重试；
+//同时记录缺失、类型变化和新出现的字段。只有整体仍无法解析时才返回错误
+pub fn parse_tolerant<T: DeserializeOwned>(endpoint: &str, text: &str) -> Result<(T, DriftReport), String> {
+    parse_with_baseline(endpoint, text, new_unexpected_fields)
+}
+
+//new_fields 从多余字段中筛出需要报告的新字段，默认读写数据目录中的基准文件
+fn parse_with_baseline<T: DeserializeOwned>(
+    endpoint: &str,
+    text: &str,
+    mut new_fields: impl FnMut(&str, BTreeSet<String>) -> Vec<String>,
+) -> Result<(T, DriftReport), String> {
+    let mut value: Value = serde_json::from_str(text).map_err(|e| format!("{} 返回的不是有效json: {}", endpoint, e))?;
+    let mut missing = BTreeSet::new();
+    let mut type_changed = BTreeMap::new();
+    for _ in 0..MAX_REPAIRS {
+        let collector = RefCell::new(Collector::default());
+        let result = T::deserialize(Tolerant { value: &value, path: Vec::new(), collector: &collector });
+        let collector = collector.into_inner();
+        type_changed.extend(collector.type_changed);
+        let (path, message) = match result {
+            Ok(parsed) => {
+                let unexpected = new_fields(endpoint, collector.unexpected);
+                let report = finish_report(endpoint, missing, type_changed, unexpected);
+                return Ok((parsed, report));
+            }
+            Err(e) => collector.failure.unwrap_or_else(|| (Vec::new(), e.to_string())),
+        };
+        // 修补出错的位置后重试
+        let target = match value_at_mut(&mut value, &path) {
+            Some(target) => target,
+            None => return Err(format!("{} 解析失败（{}）: {}", endpoint, display_path(&path), message)),
+        };
+        if let Some(field) = missing_field_name(&message) {
+            // 对象变成了数组等其他类型时已按空对象解析过（类型变化已记录），换成空对象后逐个补字段
+            if !target.is_object() {
+                *target = Value::Object(Map::new());
+            }
+            let mut field_path = path.clone();
+            field_path.push(Seg::Key(field.clone()));
+            missing.insert(display_path(&field_path));
+            if let Value::Object(map) = target {
+                map.insert(field, Value::Null);
+            }
+        } else if !target.is_null() {
+            type_changed.insert(display_path(&path), message);
+            *target = Value::Null;
+        } else {
+            return Err(format!("{} 解析失败（{}）: {}", endpoint, display_path(&path), message));
+        }
+    }
+    Err(format!("{} 解析失败：需要修补的字段过多", endpoint))
+}
+
+fn finish_report(endpoint: &str, missing: BTreeSet<String>, type_changed: BTreeMap<String, String>, unexpected: Vec<String>) -> DriftReport {
+    // 补上的字段在重试时会以null出现，不再算作类型变化
+    let type_changed = type_changed.into_iter().filter(|(path, _)| !missing.contains(path)).collect();
+    let report = DriftReport {
+        endpoint: endpoint.to_string(),
+        missing: missing.into_iter().collect(),
+        type_changed,
+        unexpected,
+        at: chrono::Local::now().timestamp(),
+    };
+    if !report.is_empty() {
+        if report.is_breaking() {
+            log::error!("B站接口可能已变化，{}", report.summary());
+        } else {
+            log::warn!("B站接口出现新字段，{}", report.summary());
+        }
+        record(report.clone());
+    }
+    report
+}
+
+//多余字段很多是本来就不需要的，第一次解析某接口时记为基准，之后只报告新增的
+type FieldBaseline = BTreeMap<String, BTreeSet<String>>; //接口 -> 已见过的多余字段
+static BASELINE: Lazy<Mutex<Option<FieldBaseline>>> = Lazy::new(|| Mutex::new(None));
+
+fn new_unexpected_fields(endpoint: &str, unexpected: BTreeSet<String>) -> Vec<String> {
+    let path = data_dir::data_dir().join(BASELINE_FILE);
+    let mut baseline = BASELINE.lock().unwrap();
+    let baseline = baseline.get_or_insert_with(|| {
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    });
+    let (new_fields, changed) = filter_new_fields(baseline, endpoint, unexpected);
+    if changed {
+        match serde_json::to_string_pretty(&*baseline) {
+            Ok(content) => {
+                if let Err(e) = fs::write(&path, content) {
+                    log::warn!("保存接口字段记录失败: {}", e);
+                }
+            }
+            Err(e) => log::warn!("保存接口字段记录失败: {}", e),
+        }
+    }
+    new_fields
+}
+
+//把多余字段并入基准，返回(需要报告的新字段, 基准是否有变化)；第一次见到的接口只记基准不报告
+fn filter_new_fields(baseline: &mut FieldBaseline, endpoint: &str, unexpected: BTreeSet<String>) -> (Vec<String>, bool) {
+    let first_seen = !baseline.contains_key(endpoint);
+    let known = baseline.entry(endpoint.to_string()).or_default();
+    let new_fields: Vec<String> = unexpected.into_iter().filter(|field| known.insert(field.clone())).collect();
+    let changed = first_seen || !new_fields.is_empty();
+    if first_seen {
+        (Vec::new(), changed)
+    } else {
+        (new_fields, changed)
+    }
+}
+
+//本次运行中发现的接口变化，每个接口保留最近一次
+static REPORTS: Lazy<Mutex<Vec<DriftReport>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static UNACKNOWLEDGED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+fn record(report: DriftReport) {
+    let mut reports = REPORTS.lock().unwrap();
+    reports.retain(|r| r.endpoint != report.endpoint);
+    if report.is_breaking() {
+        *UNACKNOWLEDGED.lock().unwrap() = true;
+    }
+    reports.push(report);
+}
+
+pub fn reports() -> Vec<DriftReport> {
+    REPORTS.lock().unwrap().clone()
+}
+
+//不等待锁，取不到时返回None；生成崩溃报告用
+pub fn try_reports() -> Option<Vec<DriftReport>> {
+    REPORTS.try_lock().ok().map(|reports| reports.clone())
+}
+
+//有未查看的接口变化警告
+pub fn has_unacknowledged() -> bool {
+    *UNACKNOWLEDGED.lock().unwrap()
+}
+
+pub fn acknowledge() {
+    *UNACKNOWLEDGED.lock().unwrap() = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::show_orderlist::OrderResponse;
+    use crate::ticket::InfoResponse;
+    use serde_json::json;
+
+    fn report(missing: &[&str], type_changed: &[&str]) -> DriftReport {
+        DriftReport {
+            endpoint: "项目详情".to_string(),
+            missing: missing.iter().map(|s| s.to_string()).collect(),
+            type_changed: type_changed.iter().map(|s| (s.to_string(), "应为整数，实际为字符串".to_string())).collect(),
+            unexpected: Vec::new(),
+            at: 0,
+        }
+    }
+
+    const CRITICAL: [&str; 3] = ["data.id", "data.screen_list[].id", "data.screen_list[].ticket_list[].price"];
+
+    #[test]
+    fn critical_field_drift_is_an_error() {
+        assert!(report(&["data.id"], &[]).ensure_fields(&CRITICAL).is_err());
+        assert!(report(&[], &["data.screen_list[].ticket_list[].price"]).ensure_fields(&CRITICAL).is_err());
+    }
+
+    #[test]
+    fn parent_drift_counts_as_critical() {
+        assert_eq!(report(&["data"], &[]).broken_fields(&CRITICAL), vec!["data".to_string()]);
+        assert_eq!(report(&[], &["data.screen_list"]).broken_fields(&CRITICAL), vec!["data.screen_list".to_string()]);
+    }
+
+    fn info_fixture() -> Value {
+        let ticket = json!({
+            "saleStart": 1746000000, "saleEnd": 1746100000, "id": 466120, "project_id": 85939,
+            "price": 38000, "desc": "内场", "sale_start": "", "sale_end": "", "type": 1,
+            "sale_type": 1, "is_sale": 0, "num": 1, "sale_flag": { "number": 2, "display_name": "预售中" },
+            "clickable": true, "sale_flag_number": 2, "screen_name": "晚场",
+        });
+        let screen = json!({
+            "id": 153744, "start_time": 1746100000, "name": "晚场", "ticket_type": 1, "screen_type": 1,
+            "delivery_type": 1, "pick_seat": 0, "clickable": true, "sale_end": 1746100000,
+            "sale_start": 1746000000, "sale_flag_number": 2, "show_date": "", "ticket_list": [ticket],
+        });
+        let data = json!({
+            "id": 85939, "name": "测试演出", "is_sale": 1, "start_time": 1746100000, "end_time": 1746110000,
+            "pick_seat": 0, "project_type": 1, "express_fee": 0, "sale_begin": 1746000000, "sale_end": 1746100000,
+            "count_down": 0, "sale_flag_number": 2, "sale_flag": "预售中", "is_free": false,
+            "performance_desc": null, "id_bind": 1, "hotProject": false, "screen_list": [screen],
+        });
+        json!({ "errno": 0, "errtag": 0, "msg": "", "code": 0, "data": data })
+    }
+
+    fn order_fixture() -> Value {
+        let order = json!({
+            "order_id": "1001", "order_type": 1, "item_id": 85939,
+            "item_info": {
+                "name": "测试演出", "image": null, "screen_id": "153744", "screen_name": "晚场",
+                "screen_start_time": "2025-05-01 19:30:00", "screen_end_time": "2025-05-01 22:00:00",
+            },
+            "total_money": 38000, "count": 1, "pay_money": 38000, "pay_channel": null, "status": 2,
+            "sub_status": 0, "ctime": "2025-04-01 20:00:01", "img": { "url": "" }, "sub_status_name": "已完成",
+        });
+        json!({ "errno": 0, "errtag": 0, "msg": "", "data": { "total": 1, "list": [order] } })
+    }
+
+    //不读写数据目录的基准文件
+    fn parse<T: DeserializeOwned>(endpoint: &str, value: &Value, baseline: &mut FieldBaseline) -> (T, DriftReport) {
+        parse_with_baseline(endpoint, &value.to_string(), |endpoint, unexpected| filter_new_fields(baseline, endpoint, unexpected).0).unwrap()
+    }
+
+    #[test]
+    fn unchanged_fixtures_parse_cleanly() {
+        let mut baseline = BTreeMap::new();
+        let (info, report) = parse::<InfoResponse>("项目详情", &info_fixture(), &mut baseline);
+        assert!(report.is_empty(), "{:?}", report);
+        assert_eq!(info.data.screen_list[0].ticket_list[0].price, 38000);
+        let (orders, report) = parse::<OrderResponse>("订单列表", &order_fixture(), &mut baseline);
+        assert!(report.is_empty(), "{:?}", report);
+        assert_eq!(orders.data.list[0].order_id, "1001");
+    }
+
+    #[test]
+    fn missing_field_defaults_and_is_reported() {
+        let mut value = info_fixture();
+        value["data"]["screen_list"][0]["ticket_list"][0].as_object_mut().unwrap().remove("price");
+        value["data"].as_object_mut().unwrap().remove("hotProject");
+        let (info, report) = parse::<InfoResponse>("项目详情", &value, &mut BTreeMap::new());
+        assert_eq!(info.data.screen_list[0].ticket_list[0].price, 0);
+        assert!(!info.data.hot_project);
+        assert_eq!(report.missing, vec!["data.hotProject".to_string(), "data.screen_list[].ticket_list[].price".to_string()]);
+        assert!(report.type_changed.is_empty(), "{:?}", report.type_changed);
+
+        let mut value = order_fixture();
+        value["data"]["list"][0].as_object_mut().unwrap().remove("pay_money");
+        let (orders, report) = parse::<OrderResponse>("订单列表", &value, &mut BTreeMap::new());
+        assert_eq!(orders.data.list[0].pay_money, 0);
+        assert_eq!(report.missing, vec!["data.list[].pay_money".to_string()]);
+    }
+
+    #[test]
+    fn changed_types_are_converted_and_reported() {
+        let mut value = info_fixture();
+        value["data"]["id"] = json!("85939");
+        value["data"]["name"] = json!(123);
+        value["data"]["venue_info"] = json!([]);
+        let (info, report) = parse::<InfoResponse>("项目详情", &value, &mut BTreeMap::new());
+        assert_eq!(info.data.id, 85939);
+        assert_eq!(info.data.name, "123");
+        assert!(info.data.venue_info.is_some());
+        let changed: Vec<&str> = report.type_changed.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(changed, vec!["data.id", "data.name", "data.venue_info"]);
+        assert!(report.missing.is_empty());
+
+        // 对象变成数组、数字变成字符串
+        let mut value = order_fixture();
+        value["data"]["total"] = json!("1");
+        value["data"]["list"][0]["item_info"] = json!([]);
+        let (orders, report) = parse::<OrderResponse>("订单列表", &value, &mut BTreeMap::new());
+        assert_eq!(orders.data.total, 1);
+        assert_eq!(orders.data.list[0].item_info.name, "");
+        assert!(report.type_changed.iter().any(|(path, _)| path == "data.total"));
+        assert!(report.type_changed.iter().any(|(path, _)| path == "data.list[].item_info"));
+        assert!(report.ensure_fields(&["data.list[].order_id"]).is_ok());
+        assert!(report.ensure_fields(&["data.list[].item_info.screen_start_time"]).is_err());
+    }
+
+    #[test]
+    fn new_fields_are_reported_once_after_baseline() {
+        let mut baseline = BTreeMap::new();
+        let mut value = info_fixture();
+        value["data"]["already_there"] = json!(1);
+        // 第一次只记基准
+        let (_, report) = parse::<InfoResponse>("项目详情", &value, &mut baseline);
+        assert!(report.unexpected.is_empty());
+        assert!(baseline["项目详情"].contains("data.already_there"));
+
+        value["data"]["new_field"] = json!({ "a": 1 });
+        let (info, report) = parse::<InfoResponse>("项目详情", &value, &mut baseline);
+        assert_eq!(info.data.id, 85939);
+        assert_eq!(report.unexpected, vec!["data.new_field".to_string()]);
+        assert!(!report.is_breaking());
+
+        let (_, report) = parse::<InfoResponse>("项目详情", &value, &mut baseline);
+        assert!(report.unexpected.is_empty());
+    }
+
+    #[test]
+    fn unrelated_drift_is_tolerated() {
+        let report = report(&["data.venue_info", "data.screen_list[].name"], &["data.identity"]);
+        assert!(report.is_breaking());
+        assert!(report.ensure_fields(&CRITICAL).is_ok());
+    }
+}
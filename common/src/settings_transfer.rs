@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::account::Account;
+use crate::config::{AppConfig, CURRENT_SCHEMA_VERSION};
+use crate::push::PushConfig;
+use crate::utility::CustomConfig;
+
+// 导出文件标识，导入时用来识别文件类型
+pub const EXPORT_FORMAT: &str = "bili_ticket_rush_settings";
+
+// 推送/识别相关的密钥字段（按json路径），默认不导出
+const SECRET_PATHS: [&str; 8] = [
+    "push_config.bark_token",
+    "push_config.pushplus_token",
+    "push_config.fangtang_token",
+    "push_config.dingtalk_token",
+    "push_config.wechat_token",
+    "push_config.gotify_config.gotify_token",
+    "push_config.smtp_config.smtp_password",
+    "custom_config.ttocr_key",
+];
+// 账号登录凭据，默认不导出
+const ACCOUNT_SECRET_FIELDS: [&str; 3] = ["cookie", "csrf", "refresh_token"];
+// 账号里的个人信息（联系人姓名手机号、备注），默认不导出
+const ACCOUNT_PERSONAL_FIELDS: [&str; 2] = ["contacts", "notes"];
+
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+    pub include_accounts: bool,
+    pub include_cookies: bool,
+    pub include_tokens: bool,
+    pub include_personal: bool, //联系人和备注
+}
+
+//导出为可读的json，密钥字段按选项剔除
+pub fn export_settings(config: &AppConfig, options: &ExportOptions) -> Result<String, String> {
+    let mut doc = Map::new();
+    doc.insert("format".to_string(), json!(EXPORT_FORMAT));
+    doc.insert("schema_version".to_string(), json!(CURRENT_SCHEMA_VERSION));
+    doc.insert(
+        "exported_at".to_string(),
+        json!(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+    );
+    let mut sections = Map::new();
+    sections.insert("push_config".to_string(), to_value(&config.push_config)?);
+    sections.insert("custom_config".to_string(), to_value(&config.custom_config)?);
+    if !options.include_tokens {
+        for path in SECRET_PATHS {
+            remove_path(&mut sections, path);
+        }
+    }
+    doc.extend(sections);
+    // config.extra 是本版本不认识的字段（如新版本写入的设置），原样导出
+    for (key, value) in &config.extra {
+        doc.insert(key.clone(), value.clone());
+    }
+    if options.include_accounts {
+        let mut accounts = Vec::new();
+        for account in &config.accounts {
+            let mut value = to_value(account)?;
+            if let Value::Object(map) = &mut value {
+                if !options.include_cookies {
+                    for field in ACCOUNT_SECRET_FIELDS {
+                        map.remove(field);
+                    }
+                }
+                if !options.include_personal {
+                    for field in ACCOUNT_PERSONAL_FIELDS {
+                        map.remove(field);
+                    }
+                }
+            }
+            accounts.push(value);
+        }
+        doc.insert("accounts".to_string(), Value::Array(accounts));
+    }
+    serde_json::to_string_pretty(&Value::Object(doc)).map_err(|e| format!("导出序列化失败: {}", e))
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingChange {
+    pub path: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct AccountConflict {
+    pub uid: i64,
+    pub name: String,
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    KeepExisting,
+    Overwrite,
+}
+
+//导入预览：列出会改动的设置，以及与现有账号uid冲突的账号
+#[derive(Clone, Debug)]
+pub struct ImportPreview {
+    pub changes: Vec<SettingChange>,
+    pub new_accounts: Vec<(i64, String)>,
+    pub conflicts: Vec<AccountConflict>,
+    pub skipped: Vec<String>,
+    push_config: PushConfig,
+    custom_config: CustomConfig,
+    extra: Map<String, Value>,
+    accounts: Vec<Value>,
+}
+
+pub fn preview_import(config: &AppConfig, content: &str) -> Result<ImportPreview, String> {
+    let doc: Value = serde_json::from_str(content).map_err(|e| format!("导入文件不是有效的json: {}", e))?;
+    let mut doc = match doc {
+        Value::Object(map) => map,
+        _ => return Err("导入文件顶层应为对象".to_string()),
+    };
+    if doc.get("format").and_then(Value::as_str) != Some(EXPORT_FORMAT) {
+        return Err("不是本程序导出的设置文件".to_string());
+    }
+    for key in ["format", "schema_version", "exported_at"] {
+        doc.remove(key);
+    }
+
+    let mut changes = Vec::new();
+    let current_push = to_value(&config.push_config)?;
+    let merged_push = merge(&current_push, doc.remove("push_config"));
+    diff_values("push_config", &current_push, &merged_push, &mut changes);
+    let push_config: PushConfig =
+        serde_json::from_value(merged_push).map_err(|e| format!("push_config 格式错误: {}", e))?;
+
+    let current_custom = to_value(&config.custom_config)?;
+    let merged_custom = merge(&current_custom, doc.remove("custom_config"));
+    diff_values("custom_config", &current_custom, &merged_custom, &mut changes);
+    let custom_config: CustomConfig =
+        serde_json::from_value(merged_custom).map_err(|e| format!("custom_config 格式错误: {}", e))?;
+
+    let mut new_accounts = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut skipped = Vec::new();
+    let mut accounts = Vec::new();
+    if let Some(imported) = doc.remove("accounts") {
+        let imported = match imported {
+            Value::Array(list) => list,
+            _ => return Err("accounts 应为数组".to_string()),
+        };
+        for (idx, account) in imported.into_iter().enumerate() {
+            let uid = match account.get("uid").and_then(Value::as_i64) {
+                Some(uid) => uid,
+                None => {
+                    skipped.push(format!("accounts[{}] 缺少uid，已跳过", idx));
+                    continue;
+                }
+            };
+            let name = account.get("name").and_then(Value::as_str).unwrap_or("").to_string();
+            match config.accounts.iter().find(|a| a.uid == uid) {
+                Some(existing) => {
+                    let current = to_value(existing)?;
+                    let mut fields = Vec::new();
+                    let mut account_changes = Vec::new();
+                    diff_values("", &current, &merge(&current, Some(account.clone())), &mut account_changes);
+                    for change in account_changes {
+                        fields.push(change.path.trim_start_matches('.').to_string());
+                    }
+                    if !fields.is_empty() {
+                        conflicts.push(AccountConflict { uid, name, changed_fields: fields });
+                        accounts.push(account);
+                    }
+                }
+                None => {
+                    let has_cookie = account.get("cookie").and_then(Value::as_str).map(|c| !c.is_empty()).unwrap_or(false);
+                    if !has_cookie {
+                        skipped.push(format!("账号 {}({}) 未包含cookie，无法导入", name, uid));
+                        continue;
+                    }
+                    if serde_json::from_value::<Account>(account.clone()).is_err() {
+                        skipped.push(format!("账号 {}({}) 字段不完整，已跳过", name, uid));
+                        continue;
+                    }
+                    new_accounts.push((uid, name));
+                    accounts.push(account);
+                }
+            }
+        }
+    }
+
+    // 剩下的是其他设置分区（如预设），整体替换
+    let mut extra = config.extra.clone();
+    for (key, value) in doc {
+        let old = config.extra.get(&key).cloned().unwrap_or(Value::Null);
+        if old != value {
+            changes.push(SettingChange {
+                path: key.clone(),
+                old: summarize(&old),
+                new: summarize(&value),
+            });
+        }
+        extra.insert(key, value);
+    }
+
+    Ok(ImportPreview {
+        changes,
+        new_accounts,
+        conflicts,
+        skipped,
+        push_config,
+        custom_config,
+        extra,
+        accounts,
+    })
+}
+
+//按预览结果写入配置；冲突账号按uid查表，未指定的保留现有
+pub fn apply_import(
+    config: &mut AppConfig,
+    preview: &ImportPreview,
+    resolutions: &HashMap<i64, ConflictResolution>,
+) -> Result<(), String> {
+    config.push_config = preview.push_config.clone();
+    config.custom_config = preview.custom_config.clone();
+    config.extra = preview.extra.clone();
+    for account in &preview.accounts {
+        let uid = account.get("uid").and_then(Value::as_i64).unwrap_or_default();
+        match config.accounts.iter().position(|a| a.uid == uid) {
+            Some(idx) => {
+                if resolutions.get(&uid) != Some(&ConflictResolution::Overwrite) {
+                    continue;
+                }
+                let current = to_value(&config.accounts[idx])?;
+                // cookie可能已变化，运行时的client由调用方重新创建
+                let merged: Account = serde_json::from_value(merge(&current, Some(account.clone())))
+                    .map_err(|e| format!("账号 {} 数据格式错误: {}", uid, e))?;
+                config.accounts[idx] = merged;
+            }
+            None => {
+                let new_account: Account = serde_json::from_value(account.clone())
+                    .map_err(|e| format!("账号 {} 数据格式错误: {}", uid, e))?;
+                config.accounts.push(new_account);
+            }
+        }
+    }
+    Ok(())
+}
+
+//预览里密钥只显示是否有值
+pub fn is_secret_path(path: &str) -> bool {
+    SECRET_PATHS.contains(&path) || ACCOUNT_SECRET_FIELDS.iter().any(|f| path.ends_with(f))
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("序列化失败: {}", e))
+}
+
+// 导入文件里没有的字段保持原值（例如未导出的token）
+fn merge(base: &Value, overlay: Option<Value>) -> Value {
+    match (base, overlay) {
+        (_, None) => base.clone(),
+        (Value::Object(base_map), Some(Value::Object(overlay_map))) => {
+            let mut result = base_map.clone();
+            for (key, value) in overlay_map {
+                let merged = match base_map.get(&key) {
+                    Some(base_value) => merge(base_value, Some(value)),
+                    None => value,
+                };
+                result.insert(key, merged);
+            }
+            Value::Object(result)
+        }
+        (_, Some(overlay)) => overlay,
+    }
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, changes: &mut Vec<SettingChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{}.{}", path, key);
+                diff_values(
+                    &child,
+                    old_map.get(key).unwrap_or(&Value::Null),
+                    new_map.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if old != new => {
+            let secret = is_secret_path(path);
+            changes.push(SettingChange {
+                path: path.to_string(),
+                old: if secret { mask(old) } else { summarize(old) },
+                new: if secret { mask(new) } else { summarize(new) },
+            });
+        }
+        _ => {}
+    }
+}
+
+fn mask(value: &Value) -> String {
+    match value {
+        Value::String(s) if !s.is_empty() => "******".to_string(),
+        _ => "(空)".to_string(),
+    }
+}
+
+fn summarize(value: &Value) -> String {
+    let text = match value {
+        Value::Null => "(空)".to_string(),
+        Value::String(s) if s.is_empty() => "(空)".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() > 60 {
+        format!("{}...", text.chars().take(60).collect::<String>())
+    } else {
+        text
+    }
+}
+
+fn remove_path(map: &mut Map<String, Value>, path: &str) {
+    let mut parts: Vec<&str> = path.split('.').collect();
+    let last = match parts.pop() {
+        Some(last) => last,
+        None => return,
+    };
+    let mut current = map;
+    for part in parts {
+        current = match current.get_mut(part) {
+            Some(Value::Object(next)) => next,
+            _ => return,
+        };
+    }
+    current.remove(last);
+}
@@ -46,3 +46,10 @@ pub struct ImageInfo{
     pub url: String,
 
 }
+
+impl Order {
+    //已支付且未取消、未退款
+    pub fn is_paid(&self) -> bool {
+        self.status == 2 && !self.sub_status_name.contains("退") && !self.sub_status_name.contains("取消")
+    }
+}
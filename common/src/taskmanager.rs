@@ -2,11 +2,17 @@ use std::time::Instant;
 use reqwest::Client;
 use std::sync::Arc;
 use crate::cookie_manager::CookieManager;
+use crate::account::Account;
 use crate::ticket::{*};
 use crate::captcha::LocalCaptcha;
 use crate::push::PushConfig;
 use crate::utility::CustomConfig;
 use crate::show_orderlist::OrderResponse;
+use crate::project_search::ProjectSearchItem;
+use crate::address::{AddressInput, DeliveryAddress};
+use crate::dry_run::DryRunReport;
+use crate::grab_report::GrabReport;
+use crate::cookie_refresh::RefreshedCookie;
 
 
 
@@ -41,6 +47,13 @@ pub enum Task {
     GetTicketInfoTask(GetTicketInfoTask),
     GetBuyerInfoTask(GetBuyerInfoTask),
     GrabTicketTask(GrabTicketTask),    
+    SearchProjectTask(SearchProjectTask),
+    ResolveShortLinkTask(ResolveShortLinkTask),
+    WatchProjectTask(WatchProjectTask),
+    GetAddressListTask(GetAddressListTask),
+    SaveAddressTask(SaveAddressTask),
+    RefreshCookieTask(RefreshCookieTask),
+    ImportAccountTask(ImportAccountTask),
 }
 
 // 任务请求枚举
@@ -54,6 +67,13 @@ pub enum TaskRequest {
     GetTicketInfoRequest(GetTicketInfoRequest),
     GetBuyerInfoRequest(GetBuyerInfoRequest),
     GrabTicketRequest(GrabTicketRequest),
+    SearchProjectRequest(SearchProjectRequest),
+    ResolveShortLinkRequest(ResolveShortLinkRequest),
+    WatchProjectRequest(WatchProjectRequest),
+    GetAddressListRequest(GetAddressListRequest),
+    SaveAddressRequest(SaveAddressRequest),
+    RefreshCookieRequest(RefreshCookieRequest),
+    ImportAccountRequest(ImportAccountRequest),
 }
 
 // 任务结果枚举
@@ -68,6 +88,15 @@ pub enum TaskResult {
     GetTicketInfoResult(GetTicketInfoResult),
     GetBuyerInfoResult(GetBuyerInfoResult),
     GrabTicketResult(GrabTicketResult),
+    SearchProjectResult(SearchProjectResult),
+    ResolveShortLinkResult(ResolveShortLinkResult),
+    WatchProjectResult(WatchProjectResult),
+    GetAddressListResult(GetAddressListResult),
+    SaveAddressResult(SaveAddressResult),
+    DryRunResult(DryRunResult),
+    RefreshCookieResult(RefreshCookieResult),
+    GrabReportResult(GrabReportResult),
+    ImportAccountResult(ImportAccountResult),
 }
 //抢票请求
 #[derive(Clone,Debug)]
@@ -87,6 +116,7 @@ pub struct GrabTicketRequest {
     pub is_hot: bool,
     pub local_captcha: LocalCaptcha,
     pub skip_words: Option<Vec<String>>,
+    pub dry_run: bool, //演练：走到下单前为止，不创建订单
     
 }
 #[derive(Clone,Debug)]
@@ -110,6 +140,192 @@ pub struct  GrabTicketResult {
     pub confirm_result: Option<ConfirmTicketResult>,
     pub pay_result: Option<CheckFakeResultData>,
 }
+//演练结果
+#[derive(Clone,Debug)]
+pub struct DryRunResult {
+    pub task_id: String,
+    pub uid: i64,
+    pub report: DryRunReport,
+}
+//抢票任务结束后的统计报告
+#[derive(Clone,Debug)]
+pub struct GrabReportResult {
+    pub task_id: String,
+    pub uid: i64,
+    pub report: GrabReport,
+}
+//关键词搜索项目
+#[derive(Clone,Debug)]
+pub struct SearchProjectRequest {
+    pub task_id: String,
+    pub keyword: String,
+    pub page: u32,
+    pub client: Client,
+}
+
+#[derive(Clone,Debug)]
+pub struct SearchProjectResult {
+    pub task_id: String,
+    pub keyword: String,
+    pub items: Vec<ProjectSearchItem>,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct SearchProjectTask {
+    pub task_id: String,
+    pub keyword: String,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
+//解析b23短链接
+#[derive(Clone,Debug)]
+pub struct ResolveShortLinkRequest {
+    pub task_id: String,
+    pub short_url: String,
+    pub client: Client,
+}
+
+#[derive(Clone,Debug)]
+pub struct ResolveShortLinkResult {
+    pub task_id: String,
+    pub short_url: String,
+    pub project_id: Option<String>,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct ResolveShortLinkTask {
+    pub task_id: String,
+    pub short_url: String,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
+//关注列表刷新项目
+#[derive(Clone,Debug)]
+pub struct WatchProjectRequest {
+    pub task_id: String,
+    pub project_id: String,
+    pub cookie_manager: Arc<CookieManager>,
+}
+
+#[derive(Clone,Debug)]
+pub struct WatchProjectResult {
+    pub task_id: String,
+    pub project_id: String,
+    pub ticket_info: Option<TicketInfo>,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct WatchProjectTask {
+    pub task_id: String,
+    pub project_id: String,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
+//刷新web登录态
+#[derive(Clone,Debug)]
+pub struct RefreshCookieRequest {
+    pub task_id: String,
+    pub uid: i64,
+    pub csrf: String,
+    pub refresh_token: String,
+    pub cookie_manager: Arc<CookieManager>,
+}
+
+#[derive(Clone,Debug)]
+pub struct RefreshCookieResult {
+    pub task_id: String,
+    pub uid: i64,
+    pub success: bool,
+    pub refreshed: Option<RefreshedCookie>, //不需要刷新时为None
+    pub message: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct RefreshCookieTask {
+    pub task_id: String,
+    pub uid: i64,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
+//批量导入账号：用cookie获取账号信息完成校验
+#[derive(Clone,Debug)]
+pub struct ImportAccountRequest {
+    pub task_id: String,
+    pub index: usize, //导入报告中的序号
+    pub cookie: String,
+    pub client: Client,
+    pub user_agent: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct ImportAccountResult {
+    pub task_id: String,
+    pub index: usize,
+    pub cookie: String, //用于确认导入报告没有被替换
+    pub result: Result<Account, String>,
+}
+
+#[derive(Clone,Debug)]
+pub struct ImportAccountTask {
+    pub task_id: String,
+    pub index: usize,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
+//获取收货地址列表
+#[derive(Clone,Debug)]
+pub struct GetAddressListRequest {
+    pub uid: i64,
+    pub task_id: String,
+    pub cookie_manager: Arc<CookieManager>,
+}
+
+#[derive(Clone,Debug)]
+pub struct GetAddressListResult {
+    pub task_id: String,
+    pub uid: i64,
+    pub addresses: Vec<DeliveryAddress>,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct GetAddressListTask {
+    pub uid: i64,
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
+//新增/修改收货地址
+#[derive(Clone,Debug)]
+pub struct SaveAddressRequest {
+    pub uid: i64,
+    pub task_id: String,
+    pub input: AddressInput,
+    pub cookie_manager: Arc<CookieManager>,
+}
+
+#[derive(Clone,Debug)]
+pub struct SaveAddressResult {
+    pub task_id: String,
+    pub uid: i64,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Clone,Debug)]
+pub struct SaveAddressTask {
+    pub uid: i64,
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub start_time: Option<Instant>,
+}
 //获取购票人信息
 #[derive(Clone,Debug)]
 pub struct GetBuyerInfoRequest {
@@ -309,6 +525,7 @@ pub struct TaskQrCodeLoginResult {
     pub task_id: String,
     pub status: crate::login::QrCodeLoginStatus,
     pub cookie: Option<String>,
+    pub refresh_token: Option<String>,
     pub error: Option<String>,
 }
 
@@ -327,6 +544,7 @@ pub struct SubmitSmsLoginResult {
     pub success: bool,
     pub message: String,
     pub cookie: Option<String>,
+    pub refresh_token: Option<String>,
 }
 // 更新 TaskManager trait
 pub trait TaskManager: Send + 'static {
@@ -347,6 +565,9 @@ pub trait TaskManager: Send + 'static {
      
      // 关闭任务管理器
     fn shutdown(&mut self);
+
+    // 任务运行时的句柄，供图片加载等后台请求复用
+    fn runtime_handle(&self) -> tokio::runtime::Handle;
 }
 
 pub const DISCLAIMER_TEXT_ENCODED: &str = "4p2X5pys6aG555uu5a6M5YWo5YWN6LS55byA5rqQ77yM56aB5q2i5ZWG55So5oiW5pS26LS577yM5LuF5L6b5a2m5Lmg5L2/55So77yM6K+35Zyo5LiL6L29MjTlsI/ml7blhoXliKDpmaTvvIzkvb/nlKjmnKzova/ku7bpgKDmiJDnmoTkuIDliIflkI7mnpzor7foh6rooYzmib/mi4U=";
@@ -225,6 +225,8 @@ pub struct TicketInfo {
     pub id_bind: usize, //是否绑定
     #[serde(rename = "hotProject")]
     pub hot_project: bool, //是否热门项目
+    #[serde(default)]
+    pub venue_info: Option<VenueInfo>, //场馆信息
 
     
 
@@ -286,10 +288,30 @@ pub struct ScreenTicketInfo{
     pub clickable: bool, //是否可点（可售）
     pub sale_flag_number: usize, //售票标志位
     pub screen_name: String, //场次名称
+    #[serde(default)]
+    pub static_limit: Option<StaticLimit>, //单票种限购
 
 
 }
 
+#[derive(Clone,Debug,Default,Deserialize,Serialize)]
+pub struct VenueInfo {
+    #[serde(default)]
+    pub id: i64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub address_detail: String, //详细地址
+}
+
+#[derive(Clone,Debug,Default,Deserialize,Serialize)]
+pub struct StaticLimit {
+    #[serde(default)]
+    pub num: usize, //每单限购张数
+    #[serde(default)]
+    pub msg: String,
+}
+
 #[derive(Clone,Debug,Deserialize,Serialize)]
 pub struct DescribeList{
     pub r#type: u8,  // 使用 r# 前缀处理 Rust 关键字
@@ -0,0 +1,236 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone};
+
+// b站的开售、演出时间均以北京时间为准
+pub const BEIJING_OFFSET_SECS: i32 = 8 * 3600;
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+pub const SHORT_FORMAT: &str = "%m-%d %H:%M";
+// 日志时间带毫秒
+pub const LOG_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+pub const MILLIS_FORMAT: &str = "%H:%M:%S%.3f";
+// 导出/报告文件名中的时间
+pub const FILE_STAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+pub fn beijing_offset() -> FixedOffset {
+    FixedOffset::east_opt(BEIJING_OFFSET_SECS).unwrap()
+}
+
+//时间戳（秒）转北京时间，未设置(<=0)或无效时返回None
+pub fn to_beijing(timestamp: i64) -> Option<DateTime<FixedOffset>> {
+    if timestamp <= 0 {
+        return None;
+    }
+    beijing_offset().timestamp_opt(timestamp, 0).single()
+}
+
+pub fn to_local(timestamp: i64) -> Option<DateTime<Local>> {
+    if timestamp <= 0 {
+        return None;
+    }
+    Local.timestamp_opt(timestamp, 0).single()
+}
+
+//毫秒时间戳转本地时间，用于程序自身记录的请求时间线
+pub fn to_local_millis(timestamp_ms: i64) -> Option<DateTime<Local>> {
+    if timestamp_ms <= 0 {
+        return None;
+    }
+    Local.timestamp_millis_opt(timestamp_ms).single()
+}
+
+//本地时区在该时刻是否与北京时间不同（按时刻判断，兼顾夏令时）
+pub fn local_differs(timestamp: i64) -> bool {
+    to_local(timestamp)
+        .map(|dt| dt.offset().fix().local_minus_utc() != BEIJING_OFFSET_SECS)
+        .unwrap_or(false)
+}
+
+//本地时区标签，如 UTC+08:00
+pub fn local_zone_label() -> String {
+    offset_label(Local::now().offset().fix().local_minus_utc())
+}
+
+fn offset_label(offset_secs: i32) -> String {
+    let sign = if offset_secs < 0 { '-' } else { '+' };
+    let abs = offset_secs.abs();
+    format!("UTC{}{:02}:{:02}", sign, abs / 3600, abs % 3600 / 60)
+}
+
+fn unset_or_invalid(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        "未设置".to_string()
+    } else {
+        "无效时间".to_string()
+    }
+}
+
+//北京时间 2025-05-01 20:00:00
+pub fn format_beijing(timestamp: i64) -> String {
+    match to_beijing(timestamp) {
+        Some(dt) => dt.format(DATETIME_FORMAT).to_string(),
+        None => unset_or_invalid(timestamp),
+    }
+}
+
+//本地时间，用于程序自身记录的时间（日志、变更记录等）
+pub fn format_local(timestamp: i64) -> String {
+    match to_local(timestamp) {
+        Some(dt) => dt.format(DATETIME_FORMAT).to_string(),
+        None => unset_or_invalid(timestamp),
+    }
+}
+
+pub fn format_local_short(timestamp: i64) -> String {
+    match to_local(timestamp) {
+        Some(dt) => dt.format(SHORT_FORMAT).to_string(),
+        None => unset_or_invalid(timestamp),
+    }
+}
+
+//本地时间精确到毫秒，如 20:00:00.123
+pub fn format_local_millis(timestamp_ms: i64) -> String {
+    match to_local_millis(timestamp_ms) {
+        Some(dt) => dt.format(MILLIS_FORMAT).to_string(),
+        None => unset_or_invalid(timestamp_ms),
+    }
+}
+
+//文件名用的本地时间，如 20250501_200000
+pub fn file_stamp(timestamp_ms: i64) -> String {
+    to_local_millis(timestamp_ms)
+        .unwrap_or_else(Local::now)
+        .format(FILE_STAMP_FORMAT)
+        .to_string()
+}
+
+//平台时间：以北京时间显示，本地时区不同时附带本地时间
+//如 "2025-05-01 20:00:00 北京 / 05-01 14:00 本地(UTC+02:00)"
+pub fn format_dual(timestamp: i64) -> String {
+    let beijing = format_beijing(timestamp);
+    if !local_differs(timestamp) {
+        return beijing;
+    }
+    match to_local(timestamp) {
+        Some(local) => format!(
+            "{} 北京 / {} 本地({})",
+            beijing,
+            local.format(SHORT_FORMAT),
+            offset_label(local.offset().fix().local_minus_utc())
+        ),
+        None => beijing,
+    }
+}
+
+//相对时间：3小时12分后 / 5分钟前
+pub fn format_relative(timestamp: i64, now: i64) -> String {
+    let diff = timestamp - now;
+    if diff.abs() < 1 {
+        return "现在".to_string();
+    }
+    let suffix = if diff > 0 { "后" } else { "前" };
+    format!("{}{}", format_duration(diff.abs()), suffix)
+}
+
+//时长：2天3小时 / 3小时12分 / 12分30秒 / 45秒
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (days, hours, minutes, seconds) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+    if days > 0 {
+        if hours > 0 { format!("{}天{}小时", days, hours) } else { format!("{}天", days) }
+    } else if hours > 0 {
+        if minutes > 0 { format!("{}小时{}分", hours, minutes) } else { format!("{}小时", hours) }
+    } else if minutes > 0 {
+        if seconds > 0 { format!("{}分{}秒", minutes, seconds) } else { format!("{}分钟", minutes) }
+    } else {
+        format!("{}秒", seconds)
+    }
+}
+
+//平台时间加倒计时，如 "2025-05-01 20:00:00（3小时12分后）"
+pub fn format_with_countdown(timestamp: i64, now: i64) -> String {
+    if to_beijing(timestamp).is_none() {
+        return unset_or_invalid(timestamp);
+    }
+    format!("{}（{}）", format_dual(timestamp), format_relative(timestamp, now))
+}
+
+//b站返回的时间字符串为北京时间，也兼容纯数字时间戳（秒或毫秒）
+pub fn parse_beijing_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(timestamp) = text.parse::<i64>() {
+        return match timestamp {
+            t if t > 100_000_000_000 => Some(t / 1000),
+            t if t > 0 => Some(t),
+            _ => None,
+        };
+    }
+    let normalized = text.replace('/', "-").replace('.', "-");
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(&normalized, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    beijing_offset().from_local_datetime(&naive).single().map(|dt| dt.timestamp())
+}
+
+//接口返回的时间文本：纯数字时间戳转为可读时间，其他原样返回
+pub fn humanize_time_text(text: &str) -> String {
+    let trimmed = text.trim();
+    if !trimmed.is_empty() && trimmed.chars().all(|c| c.is_ascii_digit()) {
+        if let Some(timestamp) = parse_beijing_time(trimmed) {
+            return format_dual(timestamp);
+        }
+    }
+    text.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-05-01 20:00:00 北京时间
+    const SALE_TIME: i64 = 1746100800;
+
+    #[test]
+    fn formats_in_beijing_time() {
+        assert_eq!(format_beijing(SALE_TIME), "2025-05-01 20:00:00");
+        assert_eq!(format_beijing(0), "未设置");
+    }
+
+    #[test]
+    fn parses_beijing_text_and_timestamps() {
+        assert_eq!(parse_beijing_time("2025-05-01 20:00:00"), Some(SALE_TIME));
+        assert_eq!(parse_beijing_time("2025/05/01 20:00"), Some(SALE_TIME));
+        assert_eq!(parse_beijing_time("1746100800000"), Some(SALE_TIME));
+        assert_eq!(parse_beijing_time("1746100800"), Some(SALE_TIME));
+        assert_eq!(parse_beijing_time(""), None);
+        assert_eq!(parse_beijing_time("明天"), None);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(45), "45秒");
+        assert_eq!(format_duration(750), "12分30秒");
+        assert_eq!(format_duration(3 * 3600 + 12 * 60), "3小时12分");
+        assert_eq!(format_duration(2 * 86400), "2天");
+        assert_eq!(format_relative(SALE_TIME, SALE_TIME - 300), "5分钟后");
+        assert_eq!(format_relative(SALE_TIME, SALE_TIME + 30), "30秒前");
+    }
+
+    #[test]
+    fn labels_offsets() {
+        assert_eq!(offset_label(8 * 3600), "UTC+08:00");
+        assert_eq!(offset_label(-(3 * 3600 + 1800)), "UTC-03:30");
+    }
+
+    #[test]
+    fn formats_millis() {
+        assert_eq!(format_local_millis(0), "未设置");
+        assert!(format_local_millis(SALE_TIME * 1000 + 123).ends_with(".123"));
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Serialize, Deserialize};
 
+// 自定义UA的预填值，默认不启用
+pub const DEFAULT_CUSTOM_UA: &str = "Mozilla/5.0 (Linux; Android 6.0; Nexus 5 Build/MRA58N) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Mobile Safari/537.36";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CustomConfig{
@@ -7,21 +9,22 @@ pub struct CustomConfig{
     pub custom_ua: String,      //自定义UA
     pub captcha_mode: usize,     //验证码模式   //0:本地打码  1：ttocr
     pub ttocr_key: String,      //ttocr key
-    pub preinput_phone1: String, //预填账号1手机号
-    pub preinput_phone2: String, //预填账号2手机号
-    
+    #[serde(default)]
+    pub experimental_address: bool, //实验功能：收货地址（地址簿接口未经抓包验证）
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>, //不认识的字段，保存时原样写回
 
 }
 
 impl CustomConfig{
     pub fn new() -> Self{
         Self{
-            open_custom_ua: true,
-            custom_ua: String::from("Mozilla/5.0 (Linux; Android 6.0; Nexus 5 Build/MRA58N) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Mobile Safari/537.36"),
+            open_custom_ua: false,
+            custom_ua: String::from(DEFAULT_CUSTOM_UA),
             captcha_mode: 0,
             ttocr_key: String::new(),
-            preinput_phone1: String::new(),
-            preinput_phone2: String::new(),
+            experimental_address: false,
+            extra: serde_json::Map::new(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ticket::TicketInfo;
+use crate::time_format::format_dual;
+
+// 刷新间隔下限，避免频繁请求被风控
+pub const MIN_REFRESH_INTERVAL_SECS: u64 = 120;
+pub const DEFAULT_REFRESH_INTERVAL_SECS: u64 = 600;
+// 相邻两次刷新请求之间至少间隔的秒数，关注项目多时逐个错开
+pub const REQUEST_GAP_SECS: u64 = 10;
+// 应用内变更记录最多保留条数
+pub const MAX_EVENTS: usize = 200;
+
+//关注列表配置，保存在 AppConfig.watchlist
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WatchlistConfig {
+    #[serde(default)]
+    pub projects: Vec<WatchedProject>,
+    #[serde(default = "default_refresh_interval")]
+    pub refresh_interval_secs: u64,
+    #[serde(default = "default_true")]
+    pub push_enabled: bool, //有变化时推送（需同时开启推送设置）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Default for WatchlistConfig {
+    fn default() -> Self {
+        Self {
+            projects: Vec::new(),
+            refresh_interval_secs: DEFAULT_REFRESH_INTERVAL_SECS,
+            push_enabled: true,
+            enabled: true,
+        }
+    }
+}
+
+fn default_refresh_interval() -> u64 {
+    DEFAULT_REFRESH_INTERVAL_SECS
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WatchedProject {
+    pub project_id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub last_checked: i64, //上次刷新的时间戳（秒）
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub snapshot: Option<ProjectSnapshot>, //上次刷新时的状态，用于对比
+}
+
+impl WatchlistConfig {
+    pub fn interval(&self) -> u64 {
+        self.refresh_interval_secs.max(MIN_REFRESH_INTERVAL_SECS)
+    }
+
+    pub fn contains(&self, project_id: &str) -> bool {
+        self.projects.iter().any(|p| p.project_id == project_id)
+    }
+
+    pub fn add(&mut self, project_id: &str) -> Result<(), String> {
+        let project_id = project_id.trim();
+        if project_id.is_empty() || !project_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("项目ID无效: {}", project_id));
+        }
+        if self.contains(project_id) {
+            return Err(format!("项目 {} 已在关注列表中", project_id));
+        }
+        self.projects.push(WatchedProject {
+            project_id: project_id.to_string(),
+            name: String::new(),
+            last_checked: 0,
+            last_error: None,
+            snapshot: None,
+        });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, project_id: &str) -> bool {
+        let before = self.projects.len();
+        self.projects.retain(|p| p.project_id != project_id);
+        self.projects.len() != before
+    }
+
+    //最久未刷新且已到期的项目
+    pub fn next_due(&self, now: i64) -> Option<String> {
+        let interval = self.interval() as i64;
+        self.projects
+            .iter()
+            .filter(|p| now - p.last_checked >= interval)
+            .min_by_key(|p| p.last_checked)
+            .map(|p| p.project_id.clone())
+    }
+
+    //写入刷新结果，返回相对上次的变化；首次刷新只记录状态，不算变化
+    pub fn apply_refresh(&mut self, project_id: &str, info: &TicketInfo, now: i64) -> Vec<String> {
+        let project = match self.projects.iter_mut().find(|p| p.project_id == project_id) {
+            Some(project) => project,
+            None => return Vec::new(),
+        };
+        let snapshot = ProjectSnapshot::from_info(info);
+        let changes = match &project.snapshot {
+            Some(old) => diff_snapshots(old, &snapshot),
+            None => Vec::new(),
+        };
+        project.name = info.name.clone();
+        project.last_checked = now;
+        project.last_error = None;
+        project.snapshot = Some(snapshot);
+        changes
+    }
+
+    pub fn mark_failed(&mut self, project_id: &str, error: &str, now: i64) {
+        if let Some(project) = self.projects.iter_mut().find(|p| p.project_id == project_id) {
+            project.last_checked = now;
+            project.last_error = Some(error.to_string());
+        }
+    }
+}
+
+// 只保存需要对比的字段，避免配置文件过大
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSnapshot {
+    pub name: String,
+    #[serde(default)]
+    pub venue: String,
+    pub sale_flag: String,
+    pub sale_begin: i64,
+    pub sale_end: i64,
+    pub screens: Vec<ScreenSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScreenSnapshot {
+    pub id: usize,
+    pub name: String,
+    pub start_time: usize,
+    pub sale_flag: String,
+    pub tickets: Vec<TicketSnapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TicketSnapshot {
+    pub id: usize,
+    pub desc: String,
+    pub price: usize,
+    pub sale_flag: String,
+}
+
+impl ProjectSnapshot {
+    pub fn from_info(info: &TicketInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            venue: info.venue_info.as_ref().map(|v| v.name.clone()).unwrap_or_default(),
+            sale_flag: info.sale_flag.clone(),
+            sale_begin: info.sale_begin,
+            sale_end: info.sale_end,
+            screens: info
+                .screen_list
+                .iter()
+                .map(|screen| ScreenSnapshot {
+                    id: screen.id,
+                    name: screen.name.clone(),
+                    start_time: screen.start_time,
+                    sale_flag: screen.sale_flag.display_name.clone(),
+                    tickets: screen
+                        .ticket_list
+                        .iter()
+                        .map(|ticket| TicketSnapshot {
+                            id: ticket.id,
+                            desc: ticket.desc.clone(),
+                            price: ticket.price,
+                            sale_flag: ticket.sale_flag.display_name.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+//对比两次刷新的状态：新场次、新票种、售票状态变化、开售时间变化、价格变化
+pub fn diff_snapshots(old: &ProjectSnapshot, new: &ProjectSnapshot) -> Vec<String> {
+    let mut changes = Vec::new();
+    if old.sale_flag != new.sale_flag {
+        changes.push(format!("售票状态：{} → {}", old.sale_flag, new.sale_flag));
+    }
+    if old.sale_begin != new.sale_begin {
+        changes.push(format!(
+            "开售时间变更：{} → {}",
+            format_dual(old.sale_begin),
+            format_dual(new.sale_begin)
+        ));
+    }
+    if old.sale_end != new.sale_end {
+        changes.push(format!(
+            "截止时间变更：{} → {}",
+            format_dual(old.sale_end),
+            format_dual(new.sale_end)
+        ));
+    }
+    for screen in &new.screens {
+        let old_screen = match old.screens.iter().find(|s| s.id == screen.id) {
+            Some(old_screen) => old_screen,
+            None => {
+                changes.push(format!("新增场次：{}（{}）", screen.name, screen.sale_flag));
+                continue;
+            }
+        };
+        if old_screen.sale_flag != screen.sale_flag {
+            changes.push(format!("场次 {}：{} → {}", screen.name, old_screen.sale_flag, screen.sale_flag));
+        }
+        if old_screen.start_time != screen.start_time {
+            changes.push(format!(
+                "场次 {} 时间变更：{} → {}",
+                screen.name,
+                format_dual(old_screen.start_time as i64),
+                format_dual(screen.start_time as i64)
+            ));
+        }
+        for ticket in &screen.tickets {
+            let old_ticket = match old_screen.tickets.iter().find(|t| t.id == ticket.id) {
+                Some(old_ticket) => old_ticket,
+                None => {
+                    changes.push(format!(
+                        "场次 {} 新增票种：{} ¥{:.2}（{}）",
+                        screen.name,
+                        ticket.desc,
+                        ticket.price as f64 / 100.0,
+                        ticket.sale_flag
+                    ));
+                    continue;
+                }
+            };
+            if old_ticket.sale_flag != ticket.sale_flag {
+                changes.push(format!(
+                    "场次 {} {}：{} → {}",
+                    screen.name, ticket.desc, old_ticket.sale_flag, ticket.sale_flag
+                ));
+            }
+            if old_ticket.price != ticket.price {
+                changes.push(format!(
+                    "场次 {} {} 价格变更：¥{:.2} → ¥{:.2}",
+                    screen.name,
+                    ticket.desc,
+                    old_ticket.price as f64 / 100.0,
+                    ticket.price as f64 / 100.0
+                ));
+            }
+        }
+    }
+    for screen in &old.screens {
+        if !new.screens.iter().any(|s| s.id == screen.id) {
+            changes.push(format!("场次已下架：{}", screen.name));
+        }
+    }
+    changes
+}
+
+//应用内变更记录
+#[derive(Clone, Debug)]
+pub struct WatchEvent {
+    pub time: i64,
+    pub project_id: String,
+    pub project_name: String,
+    pub message: String,
+}
@@ -15,18 +15,30 @@ use crate::ui;
 use crate::windows;
 use crate::windows::login_windows::LoginTexture;
 use crate::windows::add_buyer::AddBuyerInput;
+use crate::windows::confirm_ticket::PendingGrab;
+use crate::windows::account_meta::AccountMetaDraft;
 use crate::ui::error_banner::render_error_banner;
 
 use common::LOG_COLLECTOR;
 use common::account::{Account,add_account};
-use common::utils::{*};
 use common::utility::CustomConfig;
+use common::config::{AppConfig, save_config};
+use common::settings_transfer::{ConflictResolution, ExportOptions, ImportPreview};
 use common::push::{*};
 use common::login::LoginInput;
 use common::taskmanager::{*};
 use common::captcha::LocalCaptcha;
-use common::show_orderlist::OrderResponse;
+use common::show_orderlist::{Order, OrderResponse};
+use common::calendar::{entries_from_orders, entries_from_watchlist, upcoming, CalendarEntry};
 use common::cookie_manager::CookieManager;
+use common::image_cache::{ImageCache, DEFAULT_MAX_DISK_BYTES};
+use common::project_search::ProjectSearchItem;
+use common::address::{AddressInput, DeliveryAddress};
+use common::dry_run::{DryRunReport, DRY_RUN_TAG};
+use common::grab_report::GrabReport;
+use common::cookie_refresh::REFRESH_CHECK_INTERVAL_SECS;
+use common::account_transfer::{ImportItem, ImportStatus};
+use common::watchlist::{WatchEvent, MAX_EVENTS, REQUEST_GAP_SECS};
 use common::taskmanager::GetAllorderRequest;
 use common::taskmanager::TaskRequest;
 use common::ticket::{*};
@@ -57,6 +69,9 @@ pub struct Myapp{
     //用户信息
     
     pub default_avatar_texture: Option<egui::TextureHandle>, // 默认头像
+
+    //头像、订单图片等网络图片的缓存加载
+    pub image_cache: Arc<ImageCache>,
         
     //错误提醒横幅
     pub error_banner_active: bool,
@@ -72,6 +87,36 @@ pub struct Myapp{
 
     //抢票id
     pub ticket_id: String,
+
+    //首页项目搜索
+    pub project_search_keyword: String,
+    pub project_search_results: Vec<ProjectSearchItem>,
+    pub project_search_loading: bool,
+    pub short_link_resolving: bool, //正在解析b23短链接
+
+    //关注列表
+    pub watch_input: String,
+    pub watch_events: Vec<WatchEvent>, //变更记录，最新的在最后
+    pub watch_requesting: Option<String>, //正在刷新的项目ID
+    pub watch_last_request: Option<std::time::Instant>,
+
+    //开售日历
+    pub show_calendar_window: bool,
+    pub calendar_export_path: String,
+    pub calendar_orders: HashMap<String, Vec<Order>>, //各账号已加载的订单，账号uid -> 订单
+    pub calendar_last_reminder_check: Option<std::time::Instant>,
+    pub cookie_refresh_last_check: Option<std::time::Instant>, //上次检查登录态的时间
+    pub cookie_refreshing: Vec<i64>, //正在检查/刷新登录态的账号
+    pub project_venues: HashMap<String, String>, //已获取过的项目场馆，项目ID -> 场馆名
+
+    //收货地址
+    pub show_address_window: Option<String>, //账号uid
+    pub address_input: AddressInput,
+    pub address_lists: HashMap<i64, Vec<DeliveryAddress>>, //账号uid -> 地址列表
+    pub address_loading: Option<i64>, //正在获取地址的账号
+    pub address_errors: HashMap<i64, String>, //获取地址失败的账号，点重试前不再自动请求
+    pub address_last_request: Option<std::time::Instant>,
+    pub address_saving: bool,
    
    //任务管理
    pub task_manager: Box<dyn TaskManager>,
@@ -81,7 +126,60 @@ pub struct Myapp{
    pub push_config: PushConfig,
 
    //config
-    pub config: Config,
+    pub config: AppConfig,
+
+    //配置档案
+    pub active_profile: String,
+    pub profile_list: Vec<String>,
+    pub profile_name_input: String,
+    pub profile_delete_confirm: Option<String>, //等待确认删除的档案
+
+    //设置导入导出
+    pub settings_export_options: ExportOptions,
+    pub settings_transfer_path: String,
+    pub settings_import_preview: Option<ImportPreview>, //有值时显示导入预览窗口
+    pub settings_import_resolutions: HashMap<i64, ConflictResolution>,
+
+    //账号导入导出
+    pub show_account_transfer: bool,
+    pub account_bundle_path: String, //加密账号包路径
+    pub account_cookie_file_path: String, //批量导入的cookie文本路径
+    pub account_bundle_passphrase: String,
+    pub account_export_selected: Vec<i64>, //勾选导出的账号uid
+    pub account_import_items: Vec<ImportItem>, //导入报告，Pending的项逐帧校验
+
+    //账号备注名、标签
+    pub account_meta_draft: Option<AccountMetaDraft>, //有值时显示编辑窗口
+    pub account_tag_filter: Option<String>, //按标签筛选账号
+
+    //联系人
+    pub show_contacts_window: Option<i64>, //编辑联系人的账号uid
+    pub contact_name_input: String,
+    pub contact_phone_input: String,
+    pub shared_contact_name_input: String,
+    pub shared_contact_phone_input: String,
+
+    //启动口令
+    pub locked: bool, //锁定时只显示解锁界面
+    pub lock_passphrase_input: String, //解锁/修改口令时输入的当前口令
+    pub lock_new_passphrase: String,
+    pub lock_confirm_passphrase: String,
+    pub lock_reset_confirm: bool,
+    pub lock_error: Option<String>,
+    pub last_activity: std::time::Instant, //最后一次操作时间，用于自动锁定
+
+    //配置文件读取异常说明，非空时弹窗提示
+    pub config_load_error: Option<String>,
+
+    //HTTP记录/回放（排查接口变化）
+    pub http_record_path: String,
+
+    //B站接口变化提示
+    pub show_api_drift: bool,
+
+    //崩溃报告/诊断包
+    pub crash_snapshot_last_update: Option<std::time::Instant>, //上次更新运行状态摘要的时间
+    pub diagnostic_bundle_path: Option<String>, //最近生成的诊断包
 
     //自定义配置
     pub custom_config: CustomConfig,
@@ -136,6 +234,10 @@ pub struct Myapp{
 
     pub orderlist_last_request_time: Option<std::time::Instant>,  // 上次请求的时间
     pub orderlist_requesting: bool,  // 是否正在请求中
+    pub preflight: Option<PendingGrab>, //开抢前检查结果，有值时显示检查窗口
+    pub dry_run_running: bool, //演练进行中
+    pub dry_run_report: Option<DryRunReport>, //最近一次演练报告，有值时显示报告窗口
+    pub grab_report: Option<GrabReport>, //最近一次抢票任务的统计报告，有值时显示报告窗口
 
     //抢票相关
     pub status_delay: usize, //延迟时间
@@ -147,6 +249,8 @@ pub struct Myapp{
 
     pub ticket_info: Option<TicketInfo>,  //根据projectid获取的项目详情
 
+    pub project_detail_info: Option<TicketInfo>, //有值时显示项目详情窗口
+
     pub show_screen_info: Option<i64>, //开启显示场次窗口（获取到project信息后）
 
     pub selected_screen_index: Option<usize>,  // 当前选中的场次索引
@@ -200,58 +304,12 @@ impl Myapp{
         
         //中文字体
         ui::fonts::configure_fonts(&cc.egui_ctx);
-        let config = match Config::load_config() {
-            Ok(load_config) => {
-                log::info!("配置文件加载成功");
-                load_config
-            },
-            Err(e) => {
-                log::error!("配置文件加载失败: {}", e);
-                log::info!("尝试迁移json配置");
-                 match Config::load_json_config() {
-                    Ok(load_config) => {
-                        log::info!("配置文件加载成功");
-                        match load_config.save_config() {
-                            Ok(_) => {
-                                log::info!("配置文件保存成功");
-                                match Config::delete_json_config() {
-                                    Ok(_) => {
-                                        log::info!("旧配置文件删除成功");
-                                    },
-                                    Err(e) => {
-                                        log::error!("旧配置文件删除失败: {}", e);
-                                    }
-                                }
-                                log::info!("迁移成功");
-                            },
-                            Err(e) => {
-                                log::error!("配置文件保存失败: {}", e);
-                            }
+        // 设置了启动口令时先不读取配置，解锁后再加载
+        let locked = common::app_lock::prepare(&common::data_dir::config_path());
+        let (config, config_load_error) = if locked { (AppConfig::new(), None) } else { load_app_config() };
+        let task_manager = TaskManagerImpl::new();
+        let image_cache = Arc::new(ImageCache::new(common::data_dir::cache_dir().join("images"), DEFAULT_MAX_DISK_BYTES, task_manager.runtime_handle()));
 
-                        }
-                        load_config
-                    }
-                    Err(e) => {
-                        log::error!("迁移失败: {}", e);
-                        let cfg =Config::new();
-                        match cfg.save_config() {
-                            Ok(_) => {
-                                log::info!("配置文件保存成功");
-                            },
-                            Err(e) => {
-                                log::error!("配置文件保存失败: {}", e);
-                            }
-
-                        }
-                        cfg
-                    }
-                
-            }
-            }
-        };
-        
-        
-        
         let mut app = Self {
             app: String::from("BRT"),
             version: String::from("6.6.1"),
@@ -267,33 +325,74 @@ impl Myapp{
             logs: Vec::new(),
             client: Client::new(),
             default_avatar_texture: None,
+            image_cache,
             running_status: String::from("空闲ing"),
             ticket_id: String::from(""),
+            project_search_keyword: String::new(),
+            project_search_results: Vec::new(),
+            project_search_loading: false,
+            short_link_resolving: false,
+            watch_input: String::new(),
+            watch_events: Vec::new(),
+            watch_requesting: None,
+            watch_last_request: None,
+            show_calendar_window: false,
+            calendar_export_path: common::data_dir::data_dir().join("calendar.ics").to_string_lossy().to_string(),
+            calendar_orders: HashMap::new(),
+            calendar_last_reminder_check: None,
+            cookie_refresh_last_check: None,
+            cookie_refreshing: Vec::new(),
+            project_venues: HashMap::new(),
+            show_address_window: None,
+            address_input: AddressInput::default(),
+            address_lists: HashMap::new(),
+            address_errors: HashMap::new(),
+            address_loading: None,
+            address_last_request: None,
+            address_saving: false,
              // 初始化任务管理器
-             task_manager: Box::new(TaskManagerImpl::new()),
+             task_manager: Box::new(task_manager),
              account_manager: AccountManager {
-                 accounts: Config::load_all_accounts(),
+                 accounts: config.accounts.clone(),
                  active_tasks: HashMap::new(),
              },
              
-            push_config : match serde_json::from_value::<PushConfig>(config["push_config"].clone()) {
-                Ok(config) => config,
-                Err(e) => {
-                    log::warn!("无法解析推送配置: {}, 使用默认值", e);
-                    PushConfig::new()
-                }
-            },
-        
-            
-               
-            custom_config: match serde_json::from_value::<CustomConfig>(config["custom_config"].clone()) {
-                Ok(config) => config,
-                Err(e) => {
-                    log::warn!("无法解析自定义配置: {}, 使用默认值", e);
-                    CustomConfig::new()
-                }
-            },
+            push_config: config.push_config.clone(),
+            custom_config: config.custom_config.clone(),
             config: config.clone(),
+            active_profile: common::profile::active_profile(),
+            profile_list: common::profile::list_profiles(),
+            profile_name_input: String::new(),
+            profile_delete_confirm: None,
+            settings_export_options: ExportOptions::default(),
+            settings_transfer_path: common::data_dir::data_dir().join("settings_export.json").to_string_lossy().to_string(),
+            settings_import_preview: None,
+            settings_import_resolutions: HashMap::new(),
+            show_account_transfer: false,
+            account_bundle_path: common::data_dir::data_dir().join("accounts_export.json").to_string_lossy().to_string(),
+            account_cookie_file_path: common::data_dir::data_dir().join("cookies.txt").to_string_lossy().to_string(),
+            account_bundle_passphrase: String::new(),
+            account_export_selected: Vec::new(),
+            account_import_items: Vec::new(),
+            account_meta_draft: None,
+            account_tag_filter: None,
+            show_contacts_window: None,
+            contact_name_input: String::new(),
+            contact_phone_input: String::new(),
+            shared_contact_name_input: String::new(),
+            shared_contact_phone_input: String::new(),
+            locked,
+            lock_passphrase_input: String::new(),
+            lock_new_passphrase: String::new(),
+            lock_confirm_passphrase: String::new(),
+            lock_reset_confirm: false,
+            lock_error: None,
+            last_activity: std::time::Instant::now(),
+            config_load_error,
+            http_record_path: common::data_dir::data_dir().join("http_record.har").to_string_lossy().to_string(),
+            show_api_drift: false,
+            crash_snapshot_last_update: None,
+            diagnostic_bundle_path: None,
             login_texture: LoginTexture { left_conrner_texture: None , right_conrner_texture: None},
 
                 login_method: "扫码登录".to_string(),
@@ -328,6 +427,10 @@ impl Myapp{
             orderlist_need_reload: false,
             orderlist_last_request_time: None,
             orderlist_requesting: false,
+            preflight: None,
+            dry_run_running: false,
+            grab_report: None,
+            dry_run_report: None,
             error_banner_active: false,
             error_banner_text: String::new(),
             error_banner_start_time: None,
@@ -341,6 +444,7 @@ impl Myapp{
             selected_account_uid: None,
             bilibiliticket_list: Vec::new(),
             ticket_info: None,
+            project_detail_info: None,
             show_screen_info: None,
             selected_screen_index: None,
             selected_screen_id: None,
@@ -369,17 +473,9 @@ impl Myapp{
     }
 
     //初始化client和ua
-    let random_value = generate_random_string(8);
-    app.default_ua = format!(
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36 Edg/134.0.0.0 {}", 
-        random_value
-    );
-    if config["custom_config"]["enable_custom_ua"].as_bool().unwrap_or(false) && !config["custom_config"]["custom_ua"].is_null() {
-        app.default_ua = config["custom_config"]["custom_ua"].as_str().unwrap_or(&app.default_ua).to_string();
-        
-    }
-    let new_client = create_client(app.default_ua.clone());
-    app.client = new_client;
+    app.default_ua = default_user_agent(&config.custom_config);
+    app.client = create_client(app.default_ua.clone());
+    common::crash_report::set_app_version(&app.version);
         
       
     app
@@ -455,7 +551,7 @@ impl Myapp{
                                             match decode_permissions(permission_token, &self.public_key) {
                                                 Ok(permissions) => {
                                                     
-                                                    if let Ok(mut file) = File::create("permissions") {
+                                                    if let Ok(mut file) = File::create(common::data_dir::permissions_path()) {
                                                         let _ = file.write_all(permission_token.as_bytes());
                                                     }
                                                     self.policy = Some(permissions);
@@ -507,13 +603,13 @@ impl Myapp{
                 TaskResult::QrCodeLoginResult(qrcode_result) => {
                     // 二维码登录的处理逻辑
                     match qrcode_result.status {
-                        common::login::QrCodeLoginStatus::Success(cookie) => {
+                        common::login::QrCodeLoginStatus::Success(_) => {
                             log::info!("二维码登录成功!");
                             
                             
                             if let Some(cookie_str) = qrcode_result.cookie {
                                 
-                                self.handle_login_success(&cookie_str);
+                                self.handle_login_success(&cookie_str, qrcode_result.refresh_token);
                             }
                         },
                         common::login::QrCodeLoginStatus::Failed(err) => {
@@ -540,7 +636,7 @@ impl Myapp{
                 TaskResult::SubmitSmsLoginResult(submit_result) => {
                     if submit_result.success{
                         if let Some(cookie_str) = submit_result.cookie {
-                            self.handle_login_success(&cookie_str);
+                            self.handle_login_success(&cookie_str, submit_result.refresh_token);
                         }
                     } else {
                         log::error!("短信登录失败: {}", submit_result.message);
@@ -555,8 +651,12 @@ impl Myapp{
                     }
                 }
                 TaskResult::GetAllorderRequestResult(order_result) => {
+                    self.orderlist_requesting = false;
                     // 处理订单请求结果
                     if order_result.success {
+                        if let Some(response) = &order_result.order_info {
+                            self.calendar_orders.insert(order_result.account_id.clone(), response.data.list.clone());
+                        }
                         self.total_order_data = Some(OrderData {
                             account_id: order_result.account_id.clone(),
                             data: order_result.order_info.clone(),
@@ -579,6 +679,7 @@ impl Myapp{
 
                         let project_info = inforesponse.data.clone();
                         let uid = order_result.uid.clone();
+                        self.remember_venue(&project_info);
                         if let Some(bilibili_ticket) = self.bilibiliticket_list
                           .iter_mut()
                          .find(|ticket| ticket.uid == uid){
@@ -627,6 +728,74 @@ impl Myapp{
                         self.show_screen_info = None; 
                     }
                 }
+                TaskResult::SearchProjectResult(search_result) => {
+                    self.project_search_loading = false;
+                    if search_result.success {
+                        if search_result.items.is_empty() {
+                            log::info!("没有找到与“{}”相关的项目", search_result.keyword);
+                        }
+                        self.project_search_results = search_result.items;
+                    } else {
+                        log::error!("搜索项目失败: {}", search_result.message);
+                    }
+                }
+                TaskResult::ResolveShortLinkResult(resolve_result) => {
+                    self.short_link_resolving = false;
+                    match resolve_result.project_id {
+                        Some(project_id) => {
+                            log::info!("{}，请确认后点击开始抢票", resolve_result.message);
+                            self.ticket_id = project_id;
+                        }
+                        None => {
+                            log::error!("解析短链接 {} 失败: {}", resolve_result.short_url, resolve_result.message);
+                        }
+                    }
+                }
+                TaskResult::WatchProjectResult(watch_result) => {
+                    self.handle_watch_result(watch_result);
+                }
+                TaskResult::RefreshCookieResult(refresh_result) => {
+                    self.cookie_refreshing.retain(|uid| *uid != refresh_result.uid);
+                    self.handle_cookie_refresh_result(refresh_result);
+                }
+                TaskResult::ImportAccountResult(import_result) => {
+                    self.handle_import_account_result(import_result);
+                }
+                TaskResult::GrabReportResult(report_result) => {
+                    let report = report_result.report;
+                    log::info!("账号 {} 抢票任务结束，共请求{}次，用时{:.1}秒", report_result.uid, report.total_attempts(), report.duration_ms() as f64 / 1000.0);
+                    self.grab_report = Some(report);
+                }
+                TaskResult::DryRunResult(dry_run_result) => {
+                    self.dry_run_running = false;
+                    let report = dry_run_result.report;
+                    if report.has_errors() {
+                        log::error!("{} 账号 {} 演练发现问题，请查看演练报告", DRY_RUN_TAG, dry_run_result.uid);
+                    } else {
+                        log::info!("{} 账号 {} 演练完成，未创建订单", DRY_RUN_TAG, dry_run_result.uid);
+                    }
+                    self.dry_run_report = Some(report);
+                }
+                TaskResult::GetAddressListResult(address_result) => {
+                    self.address_loading = None;
+                    if !address_result.success {
+                        log::error!("获取收货地址失败: {}", address_result.message);
+                        self.address_errors.insert(address_result.uid, address_result.message);
+                        continue;
+                    }
+                    self.address_errors.remove(&address_result.uid);
+                    self.address_lists.insert(address_result.uid, address_result.addresses);
+                }
+                TaskResult::SaveAddressResult(save_result) => {
+                    self.address_saving = false;
+                    if save_result.success {
+                        log::info!("{}", save_result.message);
+                        self.address_input = AddressInput::default();
+                        self.request_address_list(save_result.uid, true);
+                    } else {
+                        log::error!("{}", save_result.message);
+                    }
+                }
                 TaskResult::GrabTicketResult(grab_ticket_result)=>{
                     if grab_ticket_result.success{
                         let pay_url = match grab_ticket_result.pay_result {
@@ -758,11 +927,25 @@ impl Myapp{
         }
     }
 
-    pub fn handle_login_success(&mut self, cookie: &str) {
+    pub fn handle_login_success(&mut self, cookie: &str, refresh_token: Option<String>) {
     log::debug!("登录成功，cookie: {}", cookie);
     match add_account(cookie, &self.client,&self.default_ua){
-        Ok(account) => {
-            self.account_manager.accounts.push(account.clone());
+        Ok(mut account) => {
+            if refresh_token.is_none() {
+                log::warn!("登录响应中没有refresh_token，该账号无法自动刷新登录态");
+            }
+            account.refresh_token = refresh_token;
+            // 重新登录已有账号时替换旧账号，沿用备注名、标签等
+            match self.account_manager.accounts.iter_mut().find(|a| a.uid == account.uid) {
+                Some(existing) => {
+                    account.copy_metadata_from(existing);
+                    *existing = account.clone();
+                }
+                None => {
+                    account.sort_order = common::account::next_sort_order(&self.account_manager.accounts);
+                    self.account_manager.accounts.push(account.clone());
+                }
+            }
             match save_config(&mut self.config, None, None, Some(account.clone())){
                 Ok(_) => {
                     log::info!("登录成功，账号已添加");
@@ -780,12 +963,628 @@ impl Myapp{
     }
 
     }
+
+    //配置中的账号变化后（导入等）同步到账号列表，cookie未变的账号沿用已有client
+    pub fn sync_accounts_from_config(&mut self) {
+        let mut accounts = self.config.accounts.clone();
+        for account in &mut accounts {
+            if let Some(existing) = self.account_manager.accounts.iter().find(|a| a.uid == account.uid && a.cookie == account.cookie) {
+                account.cookie_manager = existing.cookie_manager.clone();
+                account.avatar_texture = existing.avatar_texture.clone();
+            }
+            account.ensure_client();
+        }
+        self.account_manager.accounts = accounts;
+    }
+
+    //保存账号备注名、备注和标签
+    pub fn save_account_metadata(&mut self, draft: AccountMetaDraft) {
+        let account = match self.account_manager.accounts.iter_mut().find(|a| a.uid == draft.uid) {
+            Some(account) => account,
+            None => return,
+        };
+        account.alias = draft.alias.trim().to_string();
+        account.notes = draft.notes;
+        account.tags = draft.tags;
+        let account = account.clone();
+        self.config.update_account(&account);
+        match self.config.save() {
+            Ok(_) => log::info!("账号 {} 信息已保存", account.uid),
+            Err(e) => log::error!("保存账号信息失败: {}", e),
+        }
+    }
+
+    //调整账号顺序
+    pub fn move_account_order(&mut self, uid: i64, up: bool) {
+        if !common::account::move_account(&mut self.account_manager.accounts, uid, up, &self.account_tag_filter) {
+            return;
+        }
+        for account in &self.account_manager.accounts {
+            if let Some(config_account) = self.config.accounts.iter_mut().find(|a| a.uid == account.uid) {
+                config_account.sort_order = account.sort_order;
+            }
+        }
+        common::account::sort_accounts(&mut self.config.accounts);
+        if let Err(e) = self.config.save() {
+            log::error!("保存账号顺序失败: {}", e);
+        }
+    }
+
+    //用新加载的配置替换账号、推送和自定义设置，并按新配置重建ua和client
+    fn apply_config(&mut self, config: AppConfig) {
+        self.push_config = config.push_config.clone();
+        self.custom_config = config.custom_config.clone();
+        self.account_manager.accounts = config.accounts.clone();
+        for account in &mut self.account_manager.accounts {
+            account.ensure_client();
+        }
+        self.default_ua = default_user_agent(&config.custom_config);
+        self.client = create_client(self.default_ua.clone());
+        self.config = config;
+    }
+
+    //重新读取当前档案的配置，读取异常时弹窗说明
+    fn reload_config(&mut self) {
+        let (config, error) = load_app_config();
+        self.config_load_error = error;
+        self.apply_config(config);
+    }
+
+    pub fn lock_app(&mut self) {
+        self.locked = true;
+        self.lock_passphrase_input.clear();
+        self.lock_reset_confirm = false;
+        self.lock_error = None;
+    }
+
+    //解锁：启动时解密配置并加载，自动锁定后只校验口令
+    pub fn unlock_app(&mut self) {
+        let passphrase = std::mem::take(&mut self.lock_passphrase_input);
+        let result = if common::app_lock::is_unlocked() {
+            common::app_lock::verify(&passphrase)
+        } else {
+            common::app_lock::unlock(&common::data_dir::config_path(), &passphrase).map(|_| self.reload_config())
+        };
+        match result {
+            Ok(_) => {
+                self.locked = false;
+                self.lock_error = None;
+                self.last_activity = std::time::Instant::now();
+            }
+            Err(e) => {
+                log::error!("解锁失败: {}", e);
+                self.lock_error = Some(e);
+            }
+        }
+    }
+
+    //忘记口令：删除当前档案的整个配置（配置整体加密，无法只保留部分设置）后以空配置启动
+    pub fn reset_app_lock(&mut self) {
+        if let Err(e) = common::app_lock::reset(&common::data_dir::config_path()) {
+            self.lock_error = Some(e);
+            return;
+        }
+        self.reload_config();
+        self.locked = false;
+        self.lock_error = None;
+        self.last_activity = std::time::Instant::now();
+    }
+
+    //无操作超过设定时间后自动锁定
+    fn tick_auto_lock(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| !i.events.is_empty() || i.pointer.is_moving()) {
+            self.last_activity = std::time::Instant::now();
+        }
+        let minutes = self.config.app_lock.auto_lock_minutes;
+        if minutes == 0 || !common::app_lock::is_unlocked() {
+            return;
+        }
+        if self.last_activity.elapsed() >= std::time::Duration::from_secs(minutes as u64 * 60) {
+            log::info!("{}分钟无操作，已自动锁定", minutes);
+            self.lock_app();
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_secs(30));
+        }
+    }
+
+    //切换配置档案，重新加载账号、推送和自定义设置
+    pub fn switch_profile(&mut self, name: &str) {
+        if name == self.active_profile {
+            return;
+        }
+        if let Err(e) = common::profile::switch_profile(name) {
+            log::error!("切换档案失败: {}", e);
+            return;
+        }
+        if common::app_lock::prepare(&common::data_dir::config_path()) {
+            self.apply_config(AppConfig::new());
+            self.lock_app();
+        } else {
+            self.reload_config();
+        }
+        self.active_profile = name.to_string();
+        self.profile_list = common::profile::list_profiles();
+        self.profile_delete_confirm = None;
+
+        // 清理上一个档案的选择状态
+        self.selected_account_uid = None;
+        self.selected_buyer_list = None;
+        self.bilibiliticket_list.clear();
+        self.show_screen_info = None;
+        self.confirm_ticket_info = None;
+        self.preflight = None;
+        self.dry_run_report = None;
+        self.grab_report = None;
+        self.show_orderlist_window = None;
+        self.show_add_buyer_window = None;
+        self.total_order_data = None;
+        self.watch_events.clear();
+        self.watch_requesting = None;
+        self.calendar_orders.clear();
+        self.show_address_window = None;
+        self.address_lists.clear();
+        self.address_errors.clear();
+        self.address_loading = None;
+        log::info!("已切换到档案 {}", name);
+    }
+
+    //关注列表：每次只刷新一个到期项目，相邻请求至少间隔 REQUEST_GAP_SECS
+    fn tick_watchlist(&mut self) {
+        if !self.config.watchlist.enabled {
+            return;
+        }
+        if let Some(last) = self.watch_last_request {
+            let elapsed = last.elapsed();
+            if elapsed < std::time::Duration::from_secs(REQUEST_GAP_SECS) {
+                return;
+            }
+            // 超过一分钟没有结果视为失败，允许下一次请求
+            if self.watch_requesting.is_some() && elapsed < std::time::Duration::from_secs(60) {
+                return;
+            }
+        }
+        let now = chrono::Utc::now().timestamp();
+        let project_id = match self.config.watchlist.next_due(now) {
+            Some(project_id) => project_id,
+            None => return,
+        };
+        // 优先使用首页选中的账号，否则用第一个账号
+        let cookie_manager = self
+            .account_manager
+            .accounts
+            .iter()
+            .find(|a| Some(a.uid) == self.selected_account_uid)
+            .or_else(|| self.account_manager.accounts.first())
+            .and_then(|a| a.cookie_manager.clone());
+        let cookie_manager = match cookie_manager {
+            Some(cookie_manager) => cookie_manager,
+            None => return,
+        };
+        let request = TaskRequest::WatchProjectRequest(WatchProjectRequest {
+            task_id: "".to_string(),
+            project_id: project_id.clone(),
+            cookie_manager,
+        });
+        self.watch_last_request = Some(std::time::Instant::now());
+        match self.task_manager.submit_task(request) {
+            Ok(_) => self.watch_requesting = Some(project_id),
+            Err(e) => {
+                log::error!("提交关注项目刷新任务失败: {}", e);
+                self.config.watchlist.mark_failed(&project_id, &e, now);
+            }
+        }
+    }
+
+    //定期更新运行状态摘要，程序崩溃时写入崩溃报告
+    fn tick_crash_snapshot(&mut self) {
+        if let Some(last) = self.crash_snapshot_last_update {
+            if last.elapsed() < std::time::Duration::from_secs(5) {
+                return;
+            }
+        }
+        self.crash_snapshot_last_update = Some(std::time::Instant::now());
+        common::crash_report::update_snapshot(common::crash_report::StateSnapshot {
+            active_profile: self.active_profile.clone(),
+            locked: self.locked,
+            accounts: self.account_manager.accounts.iter().map(common::crash_report::AccountSummary::from_account).collect(),
+            active_tasks: self.account_manager.active_tasks.values().map(|task| format!("抢票 票种{} {:?}", task.ticket_id, task.status)).collect(),
+            config: common::crash_report::sanitize_config(&self.config),
+        });
+    }
+
+    //生成诊断包，提issue时附上
+    pub fn create_diagnostic_bundle(&mut self) {
+        self.crash_snapshot_last_update = None;
+        self.tick_crash_snapshot();
+        match common::crash_report::write_diagnostic_bundle() {
+            Ok(path) => {
+                log::info!("诊断包已保存到 {}", path.display());
+                self.diagnostic_bundle_path = Some(path.display().to_string());
+            }
+            Err(e) => log::error!("生成诊断包失败: {}", e),
+        }
+    }
+
+    //定期检查有refresh_token的账号是否需要刷新web登录态（启动时也检查一次）
+    fn tick_cookie_refresh(&mut self) {
+        if let Some(last) = self.cookie_refresh_last_check {
+            if last.elapsed() < std::time::Duration::from_secs(REFRESH_CHECK_INTERVAL_SECS) {
+                return;
+            }
+        }
+        self.cookie_refresh_last_check = Some(std::time::Instant::now());
+        let mut requests = Vec::new();
+        for account in &self.account_manager.accounts {
+            if !account.is_login || self.cookie_refreshing.contains(&account.uid) {
+                continue;
+            }
+            let (refresh_token, cookie_manager) = match (&account.refresh_token, &account.cookie_manager) {
+                (Some(refresh_token), Some(cookie_manager)) => (refresh_token.clone(), cookie_manager.clone()),
+                _ => continue,
+            };
+            requests.push(RefreshCookieRequest {
+                task_id: "".to_string(),
+                uid: account.uid,
+                csrf: account.csrf.clone(),
+                refresh_token,
+                cookie_manager,
+            });
+        }
+        for request in requests {
+            let uid = request.uid;
+            match self.task_manager.submit_task(TaskRequest::RefreshCookieRequest(request)) {
+                Ok(_) => self.cookie_refreshing.push(uid),
+                Err(e) => log::error!("提交账号 {} 登录态检查失败: {}", uid, e),
+            }
+        }
+    }
+
+    fn handle_cookie_refresh_result(&mut self, result: RefreshCookieResult) {
+        if !result.success {
+            log::warn!("账号 {} 刷新登录态失败: {}", result.uid, result.message);
+            return;
+        }
+        let refreshed = match result.refreshed {
+            Some(refreshed) => refreshed,
+            None => {
+                log::debug!("账号 {} {}", result.uid, result.message);
+                return;
+            }
+        };
+        let account = match self.account_manager.accounts.iter_mut().find(|a| a.uid == result.uid) {
+            Some(account) => account,
+            None => return,
+        };
+        account.apply_refreshed_cookie(&refreshed);
+        let account = account.clone();
+        // 抢票对象里保存的是账号副本，一并更新
+        for bilibili_ticket in self.bilibiliticket_list.iter_mut().filter(|t| t.uid == account.uid) {
+            bilibili_ticket.account.cookie = account.cookie.clone();
+            bilibili_ticket.account.csrf = account.csrf.clone();
+            bilibili_ticket.account.refresh_token = account.refresh_token.clone();
+        }
+        self.config.update_account(&account);
+        if let Err(e) = self.config.save() {
+            log::error!("保存刷新后的登录态失败: {}", e);
+        }
+        log::info!("账号 {} 登录态已刷新", account.uid);
+    }
+
+    //批量导入账号：待校验的项提交给任务管理器，同时最多校验 MAX_IMPORT_IN_FLIGHT 个
+    fn tick_account_import(&mut self) {
+        const MAX_IMPORT_IN_FLIGHT: usize = 3;
+        let validating = self.account_import_items.iter().filter(|item| item.status == ImportStatus::Validating).count();
+        let mut slots = MAX_IMPORT_IN_FLIGHT.saturating_sub(validating);
+        for index in 0..self.account_import_items.len() {
+            if slots == 0 {
+                break;
+            }
+            if self.account_import_items[index].status != ImportStatus::Pending {
+                continue;
+            }
+            let request = TaskRequest::ImportAccountRequest(ImportAccountRequest {
+                task_id: "".to_string(),
+                index,
+                cookie: self.account_import_items[index].cookie.clone(),
+                client: self.client.clone(),
+                user_agent: self.default_ua.clone(),
+            });
+            self.account_import_items[index].status = match self.task_manager.submit_task(request) {
+                Ok(_) => {
+                    slots -= 1;
+                    ImportStatus::Validating
+                }
+                Err(e) => ImportStatus::Failed(format!("提交校验任务失败: {}", e)),
+            };
+        }
+    }
+
+    fn handle_import_account_result(&mut self, import_result: ImportAccountResult) {
+        let index = import_result.index;
+        // 导入报告已被清空或替换时丢弃
+        let item = match self.account_import_items.get(index) {
+            Some(item) if item.status == ImportStatus::Validating && item.cookie == import_result.cookie => item.clone(),
+            _ => return,
+        };
+        let status = match import_result.result {
+            Ok(mut account) => {
+                account.refresh_token = item.refresh_token.clone();
+                let (uid, name) = (account.uid, account.name.clone());
+                let existed = match self.account_manager.accounts.iter_mut().find(|a| a.uid == uid) {
+                    Some(existing) => {
+                        account.copy_metadata_from(existing);
+                        *existing = account.clone();
+                        true
+                    }
+                    None => {
+                        account.sort_order = common::account::next_sort_order(&self.account_manager.accounts);
+                        self.account_manager.accounts.push(account.clone());
+                        false
+                    }
+                };
+                self.config.add_account(&account);
+                if let Err(e) = self.config.save() {
+                    log::error!("保存导入的账号失败: {}", e);
+                }
+                log::info!("{} 导入账号 {}({}) 成功", item.source, name, uid);
+                if existed { ImportStatus::Updated { uid, name } } else { ImportStatus::Added { uid, name } }
+            }
+            Err(e) => {
+                log::error!("{} 导入失败: {}", item.source, e);
+                ImportStatus::Failed(e)
+            }
+        };
+        self.account_import_items[index].status = status;
+    }
+
+    //日历条目：关注项目 + 已加载的订单，只保留今天及以后
+    pub fn calendar_entries(&self) -> Vec<CalendarEntry> {
+        let mut entries = entries_from_watchlist(&self.config.watchlist);
+        let venues = self.known_venues();
+        for orders in self.calendar_orders.values() {
+            entries.extend(entries_from_orders(orders, &venues));
+        }
+        upcoming(entries, chrono::Utc::now().timestamp())
+    }
+
+    //请求账号的收货地址列表，force为false时5秒内不重复请求，失败过的账号不自动重试
+    pub fn request_address_list(&mut self, uid: i64, force: bool) {
+        if self.address_loading.is_some() {
+            return;
+        }
+        if force {
+            self.address_errors.remove(&uid);
+        } else {
+            if self.address_errors.contains_key(&uid) {
+                return;
+            }
+            if let Some(last) = self.address_last_request {
+                if last.elapsed() < std::time::Duration::from_secs(5) {
+                    return;
+                }
+            }
+        }
+        let cookie_manager = match self.account_manager.accounts.iter()
+            .find(|account| account.uid == uid)
+            .and_then(|account| account.cookie_manager.clone())
+        {
+            Some(cookie_manager) => cookie_manager,
+            None => {
+                log::error!("账号 {} 未登录，无法获取收货地址", uid);
+                self.address_errors.insert(uid, "账号未登录".to_string());
+                return;
+            }
+        };
+        let request = TaskRequest::GetAddressListRequest(GetAddressListRequest {
+            uid,
+            task_id: "".to_string(),
+            cookie_manager,
+        });
+        self.address_last_request = Some(std::time::Instant::now());
+        match self.task_manager.submit_task(request) {
+            Ok(_) => self.address_loading = Some(uid),
+            Err(e) => log::error!("提交获取收货地址请求失败: {}", e),
+        }
+    }
+
+    fn remember_venue(&mut self, info: &TicketInfo) {
+        if let Some(venue) = &info.venue_info {
+            if !venue.name.is_empty() {
+                self.project_venues.insert(info.id.to_string(), venue.name.clone());
+            }
+        }
+    }
+
+    //已知的项目场馆：本次运行获取过的项目 + 关注列表里保存的快照
+    pub fn known_venues(&self) -> HashMap<String, String> {
+        let mut venues = self.project_venues.clone();
+        for project in &self.config.watchlist.projects {
+            if let Some(snapshot) = &project.snapshot {
+                if !snapshot.venue.is_empty() {
+                    venues.entry(project.project_id.clone()).or_insert_with(|| snapshot.venue.clone());
+                }
+            }
+        }
+        venues
+    }
+
+    //开售提醒，每30秒检查一次
+    //tick_*只在界面重绘时执行，没有操作时egui不会重绘，按各定时任务的检查间隔主动请求重绘
+    fn schedule_tick_repaint(&self, ctx: &egui::Context) {
+        let mut intervals = Vec::new();
+        if self.config.watchlist.enabled && !self.config.watchlist.projects.is_empty() {
+            intervals.push(std::time::Duration::from_secs(1));
+        }
+        if self.config.calendar.reminder_enabled {
+            intervals.push(std::time::Duration::from_secs(30));
+        }
+        let can_refresh = self.account_manager.accounts.iter().any(|a| a.is_login && a.refresh_token.is_some());
+        if can_refresh {
+            let interval = std::time::Duration::from_secs(REFRESH_CHECK_INTERVAL_SECS);
+            let elapsed = self.cookie_refresh_last_check.map(|t| t.elapsed()).unwrap_or(interval);
+            intervals.push(interval.saturating_sub(elapsed).max(std::time::Duration::from_secs(1)));
+        }
+        if let Some(after) = intervals.into_iter().min() {
+            ctx.request_repaint_after(after);
+        }
+    }
+
+    fn tick_calendar_reminders(&mut self) {
+        if !self.config.calendar.reminder_enabled {
+            return;
+        }
+        if let Some(last) = self.calendar_last_reminder_check {
+            if last.elapsed() < std::time::Duration::from_secs(30) {
+                return;
+            }
+        }
+        self.calendar_last_reminder_check = Some(std::time::Instant::now());
+        let now = chrono::Utc::now().timestamp();
+        let entries = self.calendar_entries();
+        let due = self.config.calendar.take_due_reminders(&entries, now);
+        if due.is_empty() {
+            return;
+        }
+        for entry in &due {
+            let minutes = ((entry.time - now) / 60).max(0);
+            let title = format!("即将开售：{}", entry.title);
+            let message = format!("{} 将在{}分钟后开售，项目ID: {}", entry.title, minutes, entry.project_id);
+            log::info!("{}", message);
+            if self.push_config.enabled {
+                let push_request = TaskRequest::PushRequest(PushRequest {
+                    title,
+                    message,
+                    push_type: PushType::All,
+                    jump_url: Some(format!("https://show.bilibili.com/platform/detail.html?id={}", entry.project_id)),
+                    push_config: self.push_config.clone(),
+                });
+                if let Err(e) = self.task_manager.submit_task(push_request) {
+                    log::error!("提交开售提醒推送失败: {}", e);
+                }
+            }
+        }
+        if let Err(e) = self.config.save() {
+            log::error!("保存提醒记录失败: {}", e);
+        }
+    }
+
+    fn handle_watch_result(&mut self, result: WatchProjectResult) {
+        if self.watch_requesting.as_deref() == Some(result.project_id.as_str()) {
+            self.watch_requesting = None;
+        }
+        // 刷新期间已被移除
+        if !self.config.watchlist.contains(&result.project_id) {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let info = match result.ticket_info {
+            Some(info) if result.success => info,
+            _ => {
+                log::warn!("关注项目 {} 刷新失败: {}", result.project_id, result.message);
+                self.config.watchlist.mark_failed(&result.project_id, &result.message, now);
+                if let Err(e) = self.config.save() {
+                    log::error!("保存关注列表失败: {}", e);
+                }
+                return;
+            }
+        };
+        self.remember_venue(&info);
+        let changes = self.config.watchlist.apply_refresh(&result.project_id, &info, now);
+        if let Err(e) = self.config.save() {
+            log::error!("保存关注列表失败: {}", e);
+        }
+        if changes.is_empty() {
+            return;
+        }
+        for change in &changes {
+            log::info!("关注项目 {} 有变化：{}", info.name, change);
+            self.watch_events.push(WatchEvent {
+                time: now,
+                project_id: result.project_id.clone(),
+                project_name: info.name.clone(),
+                message: change.clone(),
+            });
+        }
+        if self.watch_events.len() > MAX_EVENTS {
+            let overflow = self.watch_events.len() - MAX_EVENTS;
+            self.watch_events.drain(0..overflow);
+        }
+        if self.config.watchlist.push_enabled && self.push_config.enabled {
+            let push_request = TaskRequest::PushRequest(PushRequest {
+                title: format!("关注项目有变化：{}", info.name),
+                message: changes.join("\n"),
+                push_type: PushType::All,
+                jump_url: Some(format!("https://show.bilibili.com/platform/detail.html?id={}", result.project_id)),
+                push_config: self.push_config.clone(),
+            });
+            if let Err(e) = self.task_manager.submit_task(push_request) {
+                log::error!("提交关注项目推送失败: {}", e);
+            }
+        }
+    }
 }
 
 
 
+//加载当前档案的配置，读取失败时备份原文件后使用默认配置
+//读取配置；失败时先备份原文件，再按部分恢复能读取的设置，返回需要提示给用户的说明
+fn load_app_config() -> (AppConfig, Option<String>) {
+    let e = match AppConfig::load() {
+        Ok(config) => {
+            log::info!("配置文件加载成功");
+            return (config, None);
+        },
+        Err(e) => e,
+    };
+    log::error!("配置文件加载失败: {}", e);
+    let path = common::data_dir::config_path();
+    let recovered = AppConfig::recover_from(&path);
+    let mut message = format!("配置文件加载失败：{}", e);
+    let cfg = match recovered {
+        Ok((cfg, issues)) => {
+            if issues.is_empty() {
+                message.push_str("\n\n已按部分重新读取，所有设置均已恢复。");
+            } else {
+                message.push_str("\n\n已恢复可以读取的部分，以下内容已重置或跳过：");
+            }
+            for issue in &issues {
+                log::warn!("配置恢复：{}", issue);
+                message.push_str(&format!("\n· {}", issue));
+            }
+            cfg
+        }
+        Err(err) => {
+            log::error!("配置无法恢复: {}", err);
+            message.push_str(&format!("\n\n配置无法恢复（{}），已使用默认配置。", err));
+            AppConfig::new()
+        }
+    };
+    if let Some(backup) = AppConfig::backup_broken(&path) {
+        log::warn!("已将无法读取的配置文件备份到 {}", backup.display());
+        message.push_str(&format!("\n\n原配置文件已备份到 {}", backup.display()));
+    }
+    if let Err(e) = cfg.save() {
+        log::error!("配置文件保存失败: {}", e);
+    }
+    (cfg, Some(message))
+}
+
 impl eframe::App for Myapp{
     fn update(&mut self, ctx:&egui::Context, frame: &mut eframe::Frame){
+        //锁定时只显示解锁界面，后台任务结果照常处理
+        if self.locked {
+            self.process_task_results();
+            ui::lock_screen::render(self, ctx);
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+            return;
+        }
+        self.tick_auto_lock(ctx);
+
+        //接口变化提示条
+        if common::schema_drift::has_unacknowledged() {
+            ui::api_drift::render_warning_strip(self, ctx);
+        }
+
         //侧栏
         ui::sidebar::render_sidebar(self,ctx);
 
@@ -814,6 +1613,14 @@ impl eframe::App for Myapp{
         //处理异步任务结果
         self.process_task_results();
 
+        //关注列表定时刷新
+        self.tick_watchlist();
+        self.tick_calendar_reminders();
+        self.tick_cookie_refresh();
+        self.tick_account_import();
+        self.tick_crash_snapshot();
+        self.schedule_tick_repaint(ctx);
+
         static mut LAST_MONITOR_TIME: Option<std::time::Instant> = None;
     
         unsafe {
@@ -989,6 +1796,11 @@ impl eframe::App for Myapp{
             
         }
 
+        //收货地址窗口
+        if let Some(uid) = self.show_address_window.clone() {
+            windows::address::show(self, ctx, &uid);
+        }
+
         //开启查看订单窗口？
         if let Some(uid) = &self.show_orderlist_window {
             let account_id = uid.clone().parse::<i64>().unwrap_or(0);
@@ -1098,6 +1910,16 @@ impl eframe::App for Myapp{
         }
 
 
+        //项目详情窗口
+        if self.project_detail_info.is_some() {
+            windows::project_detail::show(self, ctx);
+        }
+
+        //开售日历窗口
+        if self.show_calendar_window {
+            windows::calendar::show(self, ctx);
+        }
+
         //确认信息窗口
         if self.confirm_ticket_info.is_some() {
             let confirm_uid = match self.confirm_ticket_info.clone() {
@@ -1178,6 +2000,46 @@ impl eframe::App for Myapp{
             windows::show_qrcode::show(self, ctx);
         }
 
+        //设置导入预览窗口
+        if self.settings_import_preview.is_some() {
+            windows::settings_import::show(self, ctx);
+        }
+
+        //账号导入导出窗口
+        if self.show_account_transfer {
+            windows::account_transfer::show(self, ctx);
+        }
+
+        //账号信息编辑窗口
+        if self.account_meta_draft.is_some() {
+            windows::account_meta::show(self, ctx);
+        }
+
+        //联系人窗口
+        if let Some(uid) = self.show_contacts_window {
+            windows::contacts::show(self, ctx, uid);
+        }
+
+        //演练报告窗口
+        if self.dry_run_report.is_some() {
+            windows::dry_run::show(self, ctx);
+        }
+
+        //抢票报告窗口
+        if self.grab_report.is_some() {
+            windows::grab_report::show(self, ctx);
+        }
+
+        //配置读取异常提示
+        if self.config_load_error.is_some() {
+            windows::config_error::show(self, ctx);
+        }
+
+        //接口变化详情窗口
+        if self.show_api_drift {
+            windows::api_drift::show(self, ctx);
+        }
+
         
     }
     
@@ -1209,6 +2071,17 @@ match task_manager.submit_task(request) {
 }
 
 
+//默认ua带随机后缀，开启自定义ua时使用自定义值
+fn default_user_agent(custom_config: &CustomConfig) -> String {
+    if custom_config.open_custom_ua && !custom_config.custom_ua.is_empty() {
+        return custom_config.custom_ua.clone();
+    }
+    format!(
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36 Edg/134.0.0.0 {}",
+        generate_random_string(8)
+    )
+}
+
 pub fn create_client(user_agent: String) -> Client {
     let mut headers = header::HeaderMap::new();
     
@@ -1274,7 +2147,7 @@ fn decode_permissions(token: &str, public_key: &str) -> Result<Value, String> {
 
 // 加载本地保存的权限
 fn load_local_permissions(public_key: &str) -> Value {
-    match File::open("permissions") {
+    match File::open(common::data_dir::permissions_path()) {
         Ok(mut file) => {
             let mut contents = String::new();
             if file.read_to_string(&mut contents).is_ok() {
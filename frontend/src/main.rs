@@ -8,10 +8,27 @@ fn main() -> Result<(), eframe::Error> {
     std::env::set_var("LIBGL_ALWAYS_SOFTWARE", "1");      // 强制软件渲染
     std::env::set_var("MESA_GL_VERSION_OVERRIDE", "3.3"); // 尝试覆盖 GL 版本
     std::env::set_var("GALLIUM_DRIVER", "llvmpipe");      // 使用 llvmpipe 软件渲染器
+
+    // 检查程序是否已经在运行；需在迁移数据和初始化档案之前，避免第二个实例改动正在使用的文件
+    if !common::utils::ensure_single_instance() {
+        eprintln!("程序已经在运行中，请勿重复启动！");
+        //增加休眠时间，防止程序过快退出
+        std::thread::sleep(std::time::Duration::from_secs(5));
+        std::process::exit(1);
+    }
+
+    // 数据目录需在日志之前确定，日志文件写在数据目录下
+    let data_dir = common::data_dir::init(common::data_dir::parse_cli_override(std::env::args().skip(1)));
+    let migrate_messages = common::data_dir::migrate_from_cwd();
     if let Err(e) = common::init_logger() {
         eprintln!("初始化日志失败，原因: {}", e);
     }
     log::info!("日志初始化成功");
+    log::info!("数据目录: {}", data_dir.display());
+    for message in migrate_messages {
+        log::info!("{}", message);
+    }
+    common::profile::init();
 
     std::panic::set_hook(Box::new(|panic_info| {
         if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
@@ -20,23 +37,14 @@ fn main() -> Result<(), eframe::Error> {
                 // 继续允许程序运行
             } else {
                 log::error!("程序panic: {}", panic_info);
+                write_crash_report(&panic_info.to_string());
             }
         } else {
             log::error!("程序panic: {}", panic_info);
+            write_crash_report(&panic_info.to_string());
         }
     }));
 
-    // 检查程序是否已经在运行
-    if !common::utils::ensure_single_instance() {
-        eprintln!("程序已经在运行中，请勿重复启动！");
-        //增加休眠时间，防止程序过快退出
-        std::thread::sleep(std::time::Duration::from_secs(5));
-        std::process::exit(1);
-    }
-
-    // 创建资源目录（如果不存在）
-    create_resources_directory();
-
     let options = eframe::NativeOptions {
         initial_window_size: Some(Vec2::new(1200.0, 600.0)),
         min_window_size: Some(Vec2::new(800.0, 600.0)),
@@ -52,12 +60,10 @@ fn main() -> Result<(), eframe::Error> {
     )
 }
 
-// 确保资源目录存在
-fn create_resources_directory() {
-    let resources_dir = std::path::Path::new("resources/fonts");
-    if !resources_dir.exists() {
-        if let Err(e) = std::fs::create_dir_all(resources_dir) {
-            log::warn!("无法创建资源目录: {}", e);
-        }
+// panic时保存崩溃报告（调用栈、脱敏日志、运行状态）
+fn write_crash_report(message: &str) {
+    match common::crash_report::write_crash_bundle(message) {
+        Ok(path) => log::error!("崩溃报告已保存到 {}，提issue时请附上该文件", path.display()),
+        Err(e) => log::error!("保存崩溃报告失败: {}", e),
     }
 }
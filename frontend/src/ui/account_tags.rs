@@ -0,0 +1,44 @@
+use eframe::egui::{self, Color32, RichText};
+use common::account::AccountTag;
+
+pub fn tag_color(tag: &AccountTag) -> Color32 {
+    Color32::from_rgb(tag.color[0], tag.color[1], tag.color[2])
+}
+
+//彩色标签
+pub fn tag_chip(ui: &mut egui::Ui, tag: &AccountTag) -> egui::Response {
+    egui::Frame::none()
+        .fill(tag_color(tag))
+        .rounding(8.0)
+        .inner_margin(egui::vec2(6.0, 2.0))
+        .show(ui, |ui| {
+            ui.label(RichText::new(&tag.name).color(Color32::WHITE).size(12.0));
+        })
+        .response
+}
+
+//按标签筛选账号，再次点击已选标签或点"全部"取消筛选
+pub fn tag_filter_bar(ui: &mut egui::Ui, tags: &[AccountTag], filter: &mut Option<String>) {
+    if tags.is_empty() {
+        return;
+    }
+    // 选中的标签被删除后清空筛选
+    if let Some(selected) = filter {
+        if !tags.iter().any(|t| &t.name == selected) {
+            *filter = None;
+        }
+    }
+    ui.horizontal_wrapped(|ui| {
+        ui.label("按标签筛选：");
+        if ui.selectable_label(filter.is_none(), "全部").clicked() {
+            *filter = None;
+        }
+        for tag in tags {
+            let selected = filter.as_deref() == Some(tag.name.as_str());
+            let text = RichText::new(format!("● {}", tag.name)).color(tag_color(tag));
+            if ui.selectable_label(selected, text).clicked() {
+                *filter = if selected { None } else { Some(tag.name.clone()) };
+            }
+        }
+    });
+}
@@ -0,0 +1,19 @@
+use eframe::egui::{self, Color32, RichText};
+use crate::app::Myapp;
+
+//B站接口返回结构变化时在顶部常驻提示，直到用户查看
+pub fn render_warning_strip(app: &mut Myapp, ctx: &egui::Context) {
+    egui::TopBottomPanel::top("api_drift_strip")
+        .frame(egui::Frame::none().fill(Color32::from_rgb(255, 236, 179)).inner_margin(egui::Margin::symmetric(10.0, 6.0)))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("⚠ B站接口可能已变化，部分信息可能不完整或不准确").color(Color32::from_rgb(140, 80, 0)).strong());
+                if ui.button("查看详情").clicked() {
+                    app.show_api_drift = true;
+                }
+                if ui.button("知道了").clicked() {
+                    common::schema_drift::acknowledge();
+                }
+            });
+        });
+}
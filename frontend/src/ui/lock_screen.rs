@@ -0,0 +1,48 @@
+use eframe::egui::{self, Color32, RichText};
+use crate::app::Myapp;
+
+//解锁界面：输入启动口令，忘记口令时可重置（删除当前档案的全部配置）
+pub fn render(app: &mut Myapp, ctx: &egui::Context) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 4.0);
+            ui.heading(RichText::new("🔒 已锁定").size(28.0));
+            ui.add_space(8.0);
+            ui.label(RichText::new("请输入启动口令解锁").color(Color32::GRAY));
+            ui.add_space(16.0);
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut app.lock_passphrase_input)
+                    .password(true)
+                    .hint_text("启动口令")
+                    .desired_width(240.0),
+            );
+            response.request_focus();
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.add_space(8.0);
+            let button = egui::Button::new(RichText::new("解锁").size(16.0).color(Color32::WHITE))
+                .min_size(egui::vec2(240.0, 36.0))
+                .fill(Color32::from_rgb(102, 204, 255))
+                .rounding(12.0);
+            if ui.add(button).clicked() || submitted {
+                app.unlock_app();
+            }
+            if let Some(error) = &app.lock_error {
+                ui.add_space(6.0);
+                ui.label(RichText::new(error).color(Color32::from_rgb(220, 38, 38)));
+            }
+
+            // 自动锁定时配置已在内存中，不需要重置
+            if !common::app_lock::is_unlocked() {
+                ui.add_space(30.0);
+                ui.collapsing("忘记口令？", |ui| {
+                    ui.label(RichText::new("口令无法找回。重置会删除当前档案的配置，所有账号、cookie和设置都会被清除").color(Color32::from_rgb(200, 80, 0)));
+                    ui.checkbox(&mut app.lock_reset_confirm, "我已了解，删除当前档案的全部配置");
+                    if ui.add_enabled(app.lock_reset_confirm, egui::Button::new("删除配置并重置")).clicked() {
+                        app.reset_app_lock();
+                    }
+                });
+            }
+        });
+    });
+}
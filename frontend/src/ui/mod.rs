@@ -2,4 +2,6 @@ pub mod fonts;
 pub mod sidebar;
 pub mod tabs;
 pub mod loading;
-pub mod error_banner;
\ No newline at end of file
+pub mod error_banner;pub mod account_tags;
+pub mod lock_screen;
+pub mod api_drift;
@@ -1,13 +1,14 @@
 use eframe::egui;
 use crate::{app::{AccountSwitch, Myapp}};
-use common::{account::{signout_account, Account}, cookie_manager::{self, CookieManager}};
-use common::utils::load_texture_from_url;
+use crate::ui::account_tags::{tag_chip, tag_filter_bar};
+use crate::windows::account_meta::AccountMetaDraft;
+use common::{account::{all_tags, matches_tag_filter, signout_account, Account}, image_cache::ImageCache};
 use std::sync::Arc;
 
 pub fn render(app: &mut Myapp, ui: &mut egui::Ui){
     ui.heading("我的账户");
     ui.separator();
-    let mut example_account = Account{
+    let example_account = Account{
         uid: 0,
         name: "请登录账号".to_string(),
         vip_label: "未登录，请登录账号".to_string(),
@@ -18,37 +19,31 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui){
         account_status: "未登录".to_string(),
         is_active: false,
         avatar_url: None,
+        refresh_token: None,
+        alias: String::new(),
+        notes: String::new(),
+        tags: Vec::new(),
+        sort_order: 0,
+        contacts: Vec::new(),
         
         avatar_texture:None,
         
         cookie_manager: None,
+        extra: serde_json::Map::new(),
     };
 
 
     // 加载默认头像
     load_default_avatar(ui.ctx(),app);
 
-    let account_to_show = app.account_manager.accounts.first_mut().unwrap_or(&mut example_account);
-    let avatar_texture = load_user_avatar(ui.ctx(), account_to_show.cookie_manager.clone(), account_to_show);
-
-    if let Some(texture) = &avatar_texture{
-        show_user(
-            ui,
-            texture,account_to_show,
-            &mut app.delete_account,
-            &mut app.show_login_windows ,
-            &mut app.config,
-            &mut app.account_switch,
-            &mut app.show_add_buyer_window,
-            &mut app.show_orderlist_window,
-            );
-}
-    else {
-        // 如果头像加载失败，显示默认头像
+    let tags = all_tags(&app.account_manager.accounts);
+    tag_filter_bar(ui, &tags, &mut app.account_tag_filter);
+
+    if app.account_manager.accounts.is_empty() {
         if let Some(texture) = &app.default_avatar_texture {
             show_user(
                 ui,
-                texture,account_to_show,
+                texture,&example_account,
                 &mut app.delete_account,
                 &mut app.show_login_windows ,
                 &mut app.config,
@@ -58,24 +53,59 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui){
                 );
         }
     }
-//show_user_control(ui,&app.user_info);
-ui.separator();
-if let Some(texture) = &app.default_avatar_texture {
-    let account_to_show = app.account_manager.accounts.get(1).unwrap_or(&example_account);
-    show_user(
-        ui,
-        texture,account_to_show,
-        &mut app.delete_account,
-        &mut app.show_login_windows ,
-        &mut app.config,
-        &mut app.account_switch,
-        &mut app.show_add_buyer_window,
-        &mut app.show_orderlist_window,
-        );
 
+    let image_cache = app.image_cache.clone();
+    // 只在筛选结果内移动
+    let visible: Vec<usize> = (0..app.account_manager.accounts.len())
+        .filter(|i| matches_tag_filter(&app.account_manager.accounts[*i], &app.account_tag_filter))
+        .collect();
+    let mut edit_uid = None;
+    let mut move_request = None;
+    for (position, &index) in visible.iter().enumerate() {
+        let account_to_show = &mut app.account_manager.accounts[index];
+        // 头像加载失败或加载中时显示默认头像
+        let texture = load_user_avatar(ui.ctx(), &image_cache, account_to_show).or_else(|| app.default_avatar_texture.clone());
+        if let Some(texture) = &texture {
+            show_user(
+                ui,
+                texture,account_to_show,
+                &mut app.delete_account,
+                &mut app.show_login_windows ,
+                &mut app.config,
+                &mut app.account_switch,
+                &mut app.show_add_buyer_window,
+                &mut app.show_orderlist_window,
+                );
+        }
+        let uid = app.account_manager.accounts[index].uid;
+        ui.horizontal(|ui| {
+            if ui.button("✏ 备注/标签").clicked() {
+                edit_uid = Some(uid);
+            }
+            if ui.button("📇 联系人").clicked() {
+                app.show_contacts_window = Some(uid);
+            }
+            if ui.add_enabled(position > 0, egui::Button::new("⬆")).on_hover_text("上移").clicked() {
+                move_request = Some((uid, true));
+            }
+            if ui.add_enabled(position + 1 < visible.len(), egui::Button::new("⬇")).on_hover_text("下移").clicked() {
+                move_request = Some((uid, false));
+            }
+        });
+        ui.separator();
+    }
+    if let Some(uid) = edit_uid {
+        if let Some(account) = app.account_manager.accounts.iter().find(|a| a.uid == uid) {
+            app.account_meta_draft = Some(AccountMetaDraft::from_account(account));
+        }
+    }
+    if let Some((uid, up)) = move_request {
+        app.move_account_order(uid, up);
+    }
 
+if ui.button("📦 账号导入/导出").on_hover_text("加密导出账号、导入账号包或批量导入cookie").clicked() {
+    app.show_account_transfer = true;
 }
-ui.separator();
 
 
 
@@ -142,21 +172,21 @@ fn draw_user_avatar(ui: &mut egui::Ui, texture: &egui::TextureHandle, size: f32)
     response
 }
 
-fn load_user_avatar(ctx: &egui::Context, cookie_manager: Option<Arc<CookieManager>>, account: &mut Account) ->Option<egui::TextureHandle> {
+fn load_user_avatar(ctx: &egui::Context, image_cache: &Arc<ImageCache>, account: &mut Account) ->Option<egui::TextureHandle> {
     // 如果用户已登录且提供了头像路径，尝试加载
     if let Some(texture) = &account.avatar_texture {
         return Some(texture.clone());
     }
-    if account.is_login && cookie_manager.is_some() {
+    if account.is_login && account.cookie_manager.is_some() {
         if let Some(avatar_url) = &account.avatar_url {
-            // 尝试加载用户头像
-            let texture_option = load_texture_from_url(ctx, cookie_manager.unwrap(), avatar_url, "user_avatar");
+            // 头像在后台加载，加载完成前显示默认头像
+            let texture_option = image_cache.get(ctx, account.cookie_manager.clone(), avatar_url);
 
             account.avatar_texture = texture_option.clone();
             return texture_option;
         }
     }
-    //未登录或加载失败
+    //未登录或加载中
     None
 }
 // 加载默认头像
@@ -216,7 +246,7 @@ fn show_user( //显示用户头像等信息
     account: &Account,
     delete_account: &mut Option<String>,
     show_login_windows: &mut bool,
-    config: &mut common::utils::Config,
+    config: &mut common::config::AppConfig,
     account_switch: &mut Option<AccountSwitch>,
     show_add_buyer_window: &mut Option<String>,
     show_orderlist_window: &mut Option<String>,
@@ -244,9 +274,17 @@ fn show_user( //显示用户头像等信息
                     //第一行
                     //显示uid和昵称
                     ui.horizontal(|ui|{
+                        if !user.alias.trim().is_empty() {
+                            ui.add(egui::widgets::Label::new(
+                                egui::RichText::new(user.alias.trim())
+                                    .size(30.0)
+                                    .strong()
+                                    .color(egui::Color32::from_rgb(60, 60, 80))
+                            ));
+                        }
                         ui.add(egui::widgets::Label::new(
-                            egui::RichText::new(&user.name)
-                                .size(30.0)
+                            egui::RichText::new(if user.alias.trim().is_empty() { user.name.clone() } else { format!("({})", user.name) })
+                                .size(if user.alias.trim().is_empty() { 30.0 } else { 18.0 })
                                 .strong()
                                 .color(egui::Color32::from_rgb(60, 60, 80))
                         ));
@@ -324,7 +362,16 @@ fn show_user( //显示用户头像等信息
 
 
                         }
-                    })
+                        //标签
+                        for tag in &user.tags {
+                            tag_chip(ui, tag);
+                        }
+                    });
+                    //备注
+                    if !user.notes.trim().is_empty() {
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new(user.notes.trim()).color(egui::Color32::from_rgb(100, 100, 120)).size(14.0));
+                    }
 
                 });
 
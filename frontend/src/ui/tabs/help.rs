@@ -6,4 +6,21 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui) {
     ui.heading("预留帮助公告栏");
     ui.separator();
     ui.label("本项目地址：https://github.com/biliticket/bili_ticket_rush");
+    ui.add_space(12.0);
+
+    //诊断包：脱敏后的最近日志、运行状态和配置摘要，提issue时附上
+    ui.heading("问题反馈");
+    ui.label("遇到问题时可生成诊断包，提issue时一并上传。诊断包中的cookie、token、手机号、证件号、姓名和收货地址等已脱敏。");
+    if ui.button("生成诊断包").clicked() {
+        app.create_diagnostic_bundle();
+    }
+    if let Some(path) = app.diagnostic_bundle_path.clone() {
+        ui.horizontal(|ui| {
+            ui.label(format!("已保存到：{}", path));
+            if ui.small_button("复制路径").clicked() {
+                ui.output_mut(|o| o.copied_text = path.clone());
+            }
+        });
+    }
+    ui.label(egui::RichText::new(format!("程序崩溃时会自动在 {} 生成崩溃报告", common::data_dir::data_dir().join("crash_reports").display())).color(egui::Color32::GRAY));
 }
\ No newline at end of file
@@ -1,11 +1,13 @@
-use std::u32;
-
 use eframe::egui;
 use eframe::egui::Widget;
 use crate::app::Myapp;
-use common::account::{Account};
-use common::taskmanager::{TaskStatus, TicketRequest, TaskManager_debug};
+use common::account::{all_tags, matches_tag_filter, Account};
+use crate::ui::account_tags::{tag_chip, tag_color};
+use common::taskmanager::{TaskStatus, TicketRequest, TaskManager_debug, TaskRequest, SearchProjectRequest, ResolveShortLinkRequest};
 use common::ticket::BilibiliTicket;
+use common::link_parser::{parse_input, ParsedLink};
+use common::project_search::ProjectSearchItem;
+use common::time_format::humanize_time_text;
 
 
 pub fn render(app: &mut Myapp, ui: &mut egui::Ui) {
@@ -19,7 +21,7 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui) {
             .color(egui::Color32::from_rgb(255, 120, 50))
             .strong());
         ui.add_space(10.0);
-        ui.label(egui::RichText::new("请输入项目ID或粘贴票务链接，点击开始抢票").size(16.0).color(egui::Color32::GRAY));
+        ui.label(egui::RichText::new("请输入项目ID或粘贴票务链接（支持b23短链接），点击开始抢票；也可以搜索项目名称").size(16.0).color(egui::Color32::GRAY));
         ui.add_space(10.0);
         if let Some(accounce) = app.announce1.clone() {
             ui.label(egui::RichText::new(accounce)
@@ -49,7 +51,10 @@ fn ticket_input_area(ui: &mut egui::Ui, app: &mut Myapp) {
 
         //抢票按钮
         if styled_grab_button(ui).clicked() {
-            if !check_input_ticket(&mut app.ticket_id) {app.show_log_window = true; return};
+            if !resolve_ticket_input(app) {
+                if !app.short_link_resolving {app.show_log_window = true;}
+                return
+            };
             if app.account_manager.accounts.is_empty() {
                 log::info!("没有可用账号，请登录账号");
                 app.show_login_windows = true;
@@ -93,6 +98,10 @@ fn ticket_input_area(ui: &mut egui::Ui, app: &mut Myapp) {
 
         }
 
+        //项目搜索
+        ui.add_space(20.0);
+        project_search_area(ui, app);
+
         //底部状态文本
         ui.add_space(30.0);
        /*  let status_text = match app.is_loading {
@@ -128,7 +137,7 @@ fn styled_ticket_input(ui: &mut egui::Ui, text: &mut String) -> egui::Response {
                 ui.style_mut().override_font_id = Some(font_id.clone());
 
                 let input = egui::TextEdit::singleline(text)
-                    .hint_text("输入票务ID")
+                    .hint_text("输入项目ID或链接")
                     .desired_width(180.0)
                     .text_color(egui::Color32::BLACK) //指定文本颜色防止深色模式抽风
                     .margin(egui::vec2(0.0, 6.0))
@@ -198,7 +207,7 @@ fn account_selection(ui: &mut egui::Ui, app: &mut Myapp) {
                 .find(|a| Some(a.uid) == app.selected_account_uid);
 
             let selected_text = match selected_account {
-                Some(account) => format!("{} ({})", account.name, account.uid),
+                Some(account) => format!("{} ({})", account.display_name(), account.uid),
                 None => "选择账号".to_string(),
             };
 
@@ -206,19 +215,45 @@ fn account_selection(ui: &mut egui::Ui, app: &mut Myapp) {
                 .selected_text(selected_text)
                 .width(200.0)
                 .show_ui(ui, |ui| {
-                    for account in &app.account_manager.accounts {
-                        let text = format!("{} ({})", account.name, account.uid);
+                    for account in app.account_manager.accounts.iter().filter(|a| matches_tag_filter(a, &app.account_tag_filter)) {
+                        let text = format!("{} ({})", account.display_name(), account.uid);
                         let is_selected = Some(account.uid) == app.selected_account_uid;
 
-                        if ui.selectable_label(is_selected, text).clicked() {
+                        let response = ui.selectable_label(is_selected, text);
+                        let response = if account.notes.trim().is_empty() { response } else { response.on_hover_text(account.notes.trim()) };
+                        if response.clicked() {
                             app.selected_account_uid = Some(account.uid);
                         }
                     }
                 });
 
+            // 按标签筛选下拉列表中的账号
+            let tags = all_tags(&app.account_manager.accounts);
+            if !tags.is_empty() {
+                let filter_text = app.account_tag_filter.clone().unwrap_or_else(|| "全部标签".to_string());
+                egui::ComboBox::from_id_source("account_tag_filter")
+                    .selected_text(filter_text)
+                    .width(90.0)
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(app.account_tag_filter.is_none(), "全部标签").clicked() {
+                            app.account_tag_filter = None;
+                        }
+                        for tag in &tags {
+                            let selected = app.account_tag_filter.as_deref() == Some(tag.name.as_str());
+                            let text = egui::RichText::new(format!("● {}", tag.name)).color(tag_color(tag));
+                            if ui.selectable_label(selected, text).clicked() {
+                                app.account_tag_filter = Some(tag.name.clone());
+                            }
+                        }
+                    });
+            }
+
             // 显示会员等级和状态（如果有选中账号）
             if let Some(account) = selected_account {
                 ui.add_space(10.0);
+                for tag in &account.tags {
+                    tag_chip(ui, tag);
+                }
                 if !account.vip_label.is_empty() {
                     let vip_text = egui::RichText::new(&account.vip_label)
                         .size(13.0)
@@ -325,48 +360,166 @@ fn styled_grab_button(ui: &mut egui::Ui) -> egui::Response {
     }).inner
 }
 
-fn check_input_ticket(ticket_id: &mut String) -> bool{
-    //检查输入的票务ID是否有效
-    if ticket_id.is_empty(){
-        log::info!("请输入有效的票务id");
+//解析输入的项目ID或链接，短链接提交后台跟随跳转，解析完成后回填项目ID
+fn resolve_ticket_input(app: &mut Myapp) -> bool {
+    if app.short_link_resolving {
+        log::info!("正在解析短链接，请稍候");
         return false;
     }
-    if ticket_id.contains("https") {
-        if let Some(position) = ticket_id.find("id="){
-            let mut id = ticket_id.split_off(position+3);
-            if id.contains("&") {
-                let position = id.find("&").unwrap();
-                id.truncate(position);
+    match parse_input(&app.ticket_id) {
+        Ok(ParsedLink::ProjectId(id)) => {
+            log::info!("获取到的id为：{}", id);
+            app.ticket_id = id;
+            true
+        }
+        Ok(ParsedLink::ShortLink(short_url)) => {
+            let request = TaskRequest::ResolveShortLinkRequest(ResolveShortLinkRequest {
+                task_id: "".to_string(),
+                short_url,
+                client: app.client.clone(),
+            });
+            match app.task_manager.submit_task(request) {
+                Ok(_) => {
+                    log::info!("检测到短链接，正在解析...");
+                    app.short_link_resolving = true;
+                }
+                Err(e) => log::error!("提交短链接解析任务失败: {}", e),
             }
-            if id.len() == 5 || id.len() == 6 {
-                match id.parse::<u32>(){
-                    Ok(_) => {
-                        log::info!("获取到的id为：{}", id);
-                        *ticket_id = id;
-                        return true;
+            false
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            false
+        }
+    }
+}
+
+//项目搜索：关键词输入 + 结果列表
+fn project_search_area(ui: &mut egui::Ui, app: &mut Myapp) {
+    let panel_width = 500.0;
+
+    ui.horizontal(|ui| {
+        ui.add_space((ui.available_width() - panel_width) / 2.0);
+
+        egui::Frame::none()
+            .fill(egui::Color32::from_rgb(245, 245, 250))
+            .rounding(8.0)
+            .stroke(egui::Stroke::new(0.5, egui::Color32::from_rgb(200, 200, 220)))
+            .shadow(egui::epaint::Shadow::small_light())
+            .inner_margin(egui::vec2(16.0, 12.0))
+            .show(ui, |ui| {
+                ui.set_width(panel_width - 32.0);
+                ui.spacing_mut().item_spacing = egui::vec2(8.0, 8.0);
+
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("🔍").size(18.0));
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut app.project_search_keyword)
+                            .hint_text("搜索演出/展览名称")
+                            .text_color(egui::Color32::BLACK)
+                            .desired_width(300.0),
+                    );
+                    let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let button = egui::Button::new(egui::RichText::new("搜索").size(14.0).color(egui::Color32::WHITE))
+                        .fill(egui::Color32::from_rgb(102, 204, 255))
+                        .rounding(6.0);
+                    if (ui.add_enabled(!app.project_search_loading, button).clicked() || enter)
+                        && !app.project_search_loading
+                    {
+                        submit_project_search(app);
                     }
-                    Err(_) => {
-                        log::error!("输入的id不合法，请检查输入，可尝试直接输入id");
-                        return false;
+                    if app.project_search_loading {
+                        ui.spinner();
                     }
+                });
+
+                if app.project_search_results.is_empty() {
+                    return;
                 }
-            }
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("共{}个结果", app.project_search_results.len()))
+                            .color(egui::Color32::GRAY),
+                    );
+                    if ui.small_button("清空").clicked() {
+                        app.project_search_results.clear();
+                    }
+                });
+                ui.separator();
+
+                let mut selected: Option<ProjectSearchItem> = None;
+                egui::ScrollArea::vertical()
+                    .id_source("project_search_results")
+                    .max_height(260.0)
+                    .show(ui, |ui| {
+                        for item in &app.project_search_results {
+                            if search_result_row(ui, item, app.ticket_id == item.id) {
+                                selected = Some(item.clone());
+                            }
+                            ui.separator();
+                        }
+                    });
+                if let Some(item) = selected {
+                    log::info!("已选择项目：{}（ID: {}）", item.name, item.id);
+                    app.ticket_id = item.id;
+                }
+            });
+    });
+}
 
+//搜索结果单行，返回是否点击了选择
+fn search_result_row(ui: &mut egui::Ui, item: &ProjectSearchItem, is_selected: bool) -> bool {
+    let mut clicked = false;
+    ui.horizontal(|ui| {
+        ui.vertical(|ui| {
+            ui.set_width(300.0);
+            ui.label(egui::RichText::new(&item.name).color(egui::Color32::BLACK).size(15.0).strong());
+            ui.label(egui::RichText::new(item.location_text()).color(egui::Color32::GRAY).size(13.0));
+            if !item.show_time.is_empty() {
+                ui.label(egui::RichText::new(humanize_time_text(&item.show_time)).color(egui::Color32::GRAY).size(13.0));
+            }
+        });
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new(item.price_range_text()).color(egui::Color32::from_rgb(251, 114, 153)).size(14.0));
+            if !item.sale_status.is_empty() {
+                ui.label(egui::RichText::new(&item.sale_status).color(sale_status_color(&item.sale_status)).size(13.0));
+            }
+            let text = if is_selected { "已选择" } else { "选择" };
+            if ui.add_enabled(!is_selected, egui::Button::new(text)).clicked() {
+                clicked = true;
+            }
+        });
+    });
+    clicked
+}
 
+fn sale_status_color(status: &str) -> egui::Color32 {
+    if status.contains("售罄") || status.contains("结束") || status.contains("停售") {
+        egui::Color32::GRAY
+    } else if status.contains("预售") || status.contains("售票中") || status.contains("在售") {
+        egui::Color32::from_rgb(0, 150, 0)
+    } else {
+        egui::Color32::from_rgb(255, 120, 50)
+    }
+}
 
-        }else{
-            log::error!("未找到对应的id，请不要使用b23开头的短连接，正确链接以show.bilibili或mall.bilibili开头");
-            return false;
-        }
+fn submit_project_search(app: &mut Myapp) {
+    let keyword = app.project_search_keyword.trim().to_string();
+    if keyword.is_empty() {
+        log::info!("请输入搜索关键词");
+        return;
     }
-    match ticket_id.parse::<u32>() {
-        Ok(_) => {
-            log::info!("获取到的id为：{}", ticket_id);
-            return true;
-        }
-        Err(_) => {
-            log::error!("输入的id不是数字类型，请检查输入");
+    let request = TaskRequest::SearchProjectRequest(SearchProjectRequest {
+        task_id: "".to_string(),
+        keyword,
+        page: 1,
+        client: app.client.clone(),
+    });
+    match app.task_manager.submit_task(request) {
+        Ok(task_id) => {
+            log::debug!("提交搜索任务成功，任务ID: {}", task_id);
+            app.project_search_loading = true;
         }
+        Err(e) => log::error!("提交搜索任务失败: {}", e),
     }
-    return false;
 }
@@ -1,5 +1,8 @@
 use eframe::egui;
 use crate::app::Myapp;
+use common::link_parser::{parse_input, ParsedLink};
+use common::watchlist::MIN_REFRESH_INTERVAL_SECS;
+use common::time_format::{format_local, format_relative};
 
 pub fn render(app: &mut Myapp, ui: &mut egui::Ui){
     app.show_log_window = true;
@@ -8,7 +11,160 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui){
     } else {
         ui.label("无法连接服务器");
     }
-    
+
     ui.separator();
 
-}
\ No newline at end of file
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        if ui.button("📅 开售日历").clicked() {
+            app.show_calendar_window = true;
+        }
+        ui.add_space(5.0);
+        watchlist_panel(app, ui);
+        ui.add_space(10.0);
+        ui.separator();
+        watch_events_panel(app, ui);
+    });
+}
+
+//关注列表：定时刷新项目，售票状态/场次/票种/价格变化时提醒
+fn watchlist_panel(app: &mut Myapp, ui: &mut egui::Ui) {
+    ui.heading("关注列表");
+    ui.add_space(5.0);
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut app.watch_input)
+                .hint_text("项目ID或票务链接")
+                .desired_width(260.0),
+        );
+        if ui.button("添加关注").clicked() {
+            match parse_input(&app.watch_input) {
+                Ok(ParsedLink::ProjectId(id)) => match app.config.watchlist.add(&id) {
+                    Ok(_) => {
+                        log::info!("已添加关注项目: {}", id);
+                        app.watch_input.clear();
+                        changed = true;
+                    }
+                    Err(e) => log::error!("{}", e),
+                },
+                Ok(ParsedLink::ShortLink(_)) => {
+                    log::error!("短链接请先在首页解析出项目ID后再添加");
+                }
+                Err(e) => log::error!("{}", e),
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        changed |= ui.checkbox(&mut app.config.watchlist.enabled, "自动刷新").changed();
+        changed |= ui.checkbox(&mut app.config.watchlist.push_enabled, "有变化时推送").changed();
+        ui.label("刷新间隔(分钟)：");
+        let mut minutes = app.config.watchlist.interval() / 60;
+        let response = ui.add(
+            egui::DragValue::new(&mut minutes)
+                .clamp_range(MIN_REFRESH_INTERVAL_SECS / 60..=180)
+                .speed(1),
+        );
+        if response.changed() {
+            app.config.watchlist.refresh_interval_secs = minutes * 60;
+        }
+        // 拖动结束再保存
+        changed |= response.drag_released() || response.lost_focus();
+        if ui.button("全部立即刷新").clicked() {
+            for project in &mut app.config.watchlist.projects {
+                project.last_checked = 0;
+            }
+        }
+    });
+    if app.config.watchlist.push_enabled && !app.push_config.enabled {
+        ui.label(egui::RichText::new("推送总开关未开启，变化只会记录在下方").color(egui::Color32::GRAY));
+    }
+    if app.account_manager.accounts.is_empty() {
+        ui.label(egui::RichText::new("需要至少登录一个账号才能刷新项目信息").color(egui::Color32::RED));
+    }
+    ui.add_space(5.0);
+
+    if app.config.watchlist.projects.is_empty() {
+        ui.label(egui::RichText::new("暂无关注项目").color(egui::Color32::GRAY));
+    } else {
+        let now = chrono::Utc::now().timestamp();
+        let mut remove: Option<String> = None;
+        egui::Grid::new("watchlist_grid")
+            .num_columns(5)
+            .striped(true)
+            .spacing([12.0, 6.0])
+            .show(ui, |ui| {
+                for title in ["项目ID", "项目名称", "状态", "上次刷新", "操作"] {
+                    ui.label(egui::RichText::new(title).strong());
+                }
+                ui.end_row();
+                for project in &mut app.config.watchlist.projects {
+                    ui.label(&project.project_id);
+                    ui.label(if project.name.is_empty() { "待获取" } else { project.name.as_str() });
+                    match (&project.last_error, &project.snapshot) {
+                        (Some(error), _) => {
+                            ui.label(egui::RichText::new("刷新失败").color(egui::Color32::RED))
+                                .on_hover_text(error);
+                        }
+                        (None, Some(snapshot)) => {
+                            ui.label(&snapshot.sale_flag);
+                        }
+                        (None, None) => {
+                            ui.label("-");
+                        }
+                    };
+                    ui.label(elapsed_text(now, project.last_checked));
+                    ui.horizontal(|ui| {
+                        if app.watch_requesting.as_deref() == Some(project.project_id.as_str()) {
+                            ui.spinner();
+                        } else if ui.small_button("刷新").clicked() {
+                            project.last_checked = 0;
+                        }
+                        if ui.small_button("移除").clicked() {
+                            remove = Some(project.project_id.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        if let Some(project_id) = remove {
+            app.config.watchlist.remove(&project_id);
+            log::info!("已取消关注项目: {}", project_id);
+            changed = true;
+        }
+    }
+
+    if changed {
+        if let Err(e) = app.config.save() {
+            log::error!("保存关注列表失败: {}", e);
+        }
+    }
+}
+
+fn watch_events_panel(app: &mut Myapp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.heading("变更记录");
+        if !app.watch_events.is_empty() && ui.small_button("清空").clicked() {
+            app.watch_events.clear();
+        }
+    });
+    if app.watch_events.is_empty() {
+        ui.label(egui::RichText::new("暂无变化").color(egui::Color32::GRAY));
+        return;
+    }
+    for event in app.watch_events.iter().rev() {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new(format_local(event.time)).color(egui::Color32::GRAY));
+            ui.label(egui::RichText::new(format!("{}({})", event.project_name, event.project_id)).strong());
+            ui.label(&event.message);
+        });
+    }
+}
+
+fn elapsed_text(now: i64, last: i64) -> String {
+    if last <= 0 {
+        return "未刷新".to_string();
+    }
+    format_relative(last.min(now), now)
+}
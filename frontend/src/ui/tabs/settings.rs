@@ -1,6 +1,6 @@
 use eframe::egui;
 use crate::app::Myapp;
-use common::utils::save_config;
+use common::config::save_config;
 
 fn on_switch(ui: &mut egui::Ui, output_char: &str, on: &mut bool) -> egui::Response {
     ui.label(
@@ -119,6 +119,50 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui) {
     })   ; 
     
     ui.separator();
+    //配置档案
+    egui::Frame::none()
+        .fill(egui::Color32::from_rgb(245, 245, 250))  // 背景色
+        .rounding(12.0)  // 圆角半径
+        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 200, 220)))  // 边框
+        .inner_margin(egui::Margin { left: 10.0, right: 20.0, top: 15.0, bottom: 15.0 })  // 内边距
+        .show(ui, |ui| {
+
+            profile_setting(app, ui);
+
+        });
+    //启动口令
+    egui::Frame::none()
+        .fill(egui::Color32::from_rgb(245, 245, 250))  // 背景色
+        .rounding(12.0)  // 圆角半径
+        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 200, 220)))  // 边框
+        .inner_margin(egui::Margin { left: 10.0, right: 20.0, top: 15.0, bottom: 15.0 })  // 内边距
+        .show(ui, |ui| {
+
+            app_lock_setting(app, ui);
+
+        });
+    //HTTP记录/回放
+    egui::Frame::none()
+        .fill(egui::Color32::from_rgb(245, 245, 250))  // 背景色
+        .rounding(12.0)  // 圆角半径
+        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 200, 220)))  // 边框
+        .inner_margin(egui::Margin { left: 10.0, right: 20.0, top: 15.0, bottom: 15.0 })  // 内边距
+        .show(ui, |ui| {
+
+            http_recorder_setting(app, ui);
+
+        });
+    //导入导出
+    egui::Frame::none()
+        .fill(egui::Color32::from_rgb(245, 245, 250))  // 背景色
+        .rounding(12.0)  // 圆角半径
+        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 200, 220)))  // 边框
+        .inner_margin(egui::Margin { left: 10.0, right: 20.0, top: 15.0, bottom: 15.0 })  // 内边距
+        .show(ui, |ui| {
+
+            transfer_setting(app, ui);
+
+        });
             //推送设置：
     // 创建圆角长方形框架  
     egui::Frame::none()
@@ -149,12 +193,284 @@ pub fn render(app: &mut Myapp, ui: &mut egui::Ui) {
    
 }
 
-pub fn globle_setting(app: &mut Myapp, ui: &mut egui::Ui){
+fn passphrase_input(ui: &mut egui::Ui, title: &str, text: &mut String) {
     ui.horizontal(|ui| {
-        
-        common_input(ui, "请输入账号1预填手机号：", &mut app.custom_config.preinput_phone1, "请输入账号1绑定的手机号",true);
-        common_input(ui, "请输入账号2预填手机号：", &mut app.custom_config.preinput_phone1, "请输入账号2绑定的手机号，没有可不填",true);
+        ui.label(title);
+        ui.add(egui::TextEdit::singleline(text).password(true).desired_width(200.0));
+    });
+}
+
+pub fn app_lock_setting(app: &mut Myapp, ui: &mut egui::Ui){
+    ui.label(
+        egui::RichText::new("启动口令")
+              .size(15.0)
+              .color(egui::Color32::from_rgb(0,0,0))
+              .strong()
+    );
+    if !common::app_lock::is_enabled() {
+        ui.label(egui::RichText::new("未设置：配置只用本机机器码加密，本机上的其他程序也能解密。设置后启动时需要输入口令，配置改用口令加密").color(egui::Color32::GRAY));
+        passphrase_input(ui, "新口令：", &mut app.lock_new_passphrase);
+        passphrase_input(ui, "确认口令：", &mut app.lock_confirm_passphrase);
+        if profile_button(ui, "设置口令") {
+            let result = if app.lock_new_passphrase != app.lock_confirm_passphrase {
+                Err("两次输入的口令不一致".to_string())
+            } else {
+                common::app_lock::set_passphrase(&app.lock_new_passphrase, || app.config.save())
+            };
+            match result {
+                Ok(_) => log::info!("已设置启动口令"),
+                Err(e) => log::error!("设置启动口令失败: {}", e),
+            }
+            app.lock_new_passphrase.clear();
+            app.lock_confirm_passphrase.clear();
+        }
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("无操作自动锁定：");
+        let response = ui.add(egui::DragValue::new(&mut app.config.app_lock.auto_lock_minutes).clamp_range(0..=1440).suffix(" 分钟"));
+        ui.label(egui::RichText::new("0为不自动锁定").color(egui::Color32::GRAY));
+        if response.drag_released() || response.lost_focus() {
+            if let Err(e) = app.config.save() {
+                log::error!("保存自动锁定设置失败: {}", e);
+            }
+        }
+        ui.add_space(10.0);
+        if profile_button(ui, "立即锁定") {
+            app.lock_app();
+        }
+    });
+    ui.separator();
+    passphrase_input(ui, "当前口令：", &mut app.lock_passphrase_input);
+    passphrase_input(ui, "新口令：", &mut app.lock_new_passphrase);
+    passphrase_input(ui, "确认口令：", &mut app.lock_confirm_passphrase);
+    ui.horizontal(|ui| {
+        if profile_button(ui, "修改口令") {
+            let result = if app.lock_new_passphrase != app.lock_confirm_passphrase {
+                Err("两次输入的口令不一致".to_string())
+            } else {
+                common::app_lock::verify(&app.lock_passphrase_input)
+                    .and_then(|_| common::app_lock::set_passphrase(&app.lock_new_passphrase, || app.config.save()))
+            };
+            match result {
+                Ok(_) => log::info!("启动口令已修改"),
+                Err(e) => log::error!("修改启动口令失败: {}", e),
+            }
+            app.lock_passphrase_input.clear();
+            app.lock_new_passphrase.clear();
+            app.lock_confirm_passphrase.clear();
+        }
+        if profile_button(ui, "关闭口令") {
+            let result = common::app_lock::verify(&app.lock_passphrase_input)
+                .and_then(|_| common::app_lock::disable(|| app.config.save()));
+            match result {
+                Ok(_) => log::info!("已关闭启动口令"),
+                Err(e) => log::error!("关闭启动口令失败: {}", e),
+            }
+            app.lock_passphrase_input.clear();
+        }
+    });
+    ui.label(egui::RichText::new("关闭口令只需输入当前口令；忘记口令只能在解锁界面重置，当前档案的账号、cookie和设置会全部清除").color(egui::Color32::GRAY));
+}
+
+pub fn http_recorder_setting(app: &mut Myapp, ui: &mut egui::Ui){
+    use common::http_recorder::{self, RecorderStatus};
+    ui.label(
+        egui::RichText::new("HTTP记录/回放（调试用）")
+              .size(15.0)
+              .color(egui::Color32::from_rgb(0,0,0))
+              .strong()
+    );
+    ui.label(egui::RichText::new("记录接口请求和响应（cookie、csrf、手机号、证件号、姓名、收货地址等已脱敏），出现json解析失败时可用回放离线复现").color(egui::Color32::GRAY));
+    let status = http_recorder::status();
+    ui.horizontal(|ui| {
+        ui.label("记录文件：");
+        ui.add_enabled(status == RecorderStatus::Off, egui::TextEdit::singleline(&mut app.http_record_path).desired_width(360.0));
+    });
+    ui.horizontal(|ui| {
+        match &status {
+            RecorderStatus::Off => {
+                if profile_button(ui, "开始记录") {
+                    if let Err(e) = http_recorder::start_recording(std::path::Path::new(&app.http_record_path)) {
+                        log::error!("开始记录失败: {}", e);
+                    }
+                }
+                if profile_button(ui, "开始回放") {
+                    if let Err(e) = http_recorder::start_replay(std::path::Path::new(&app.http_record_path)) {
+                        log::error!("开始回放失败: {}", e);
+                    }
+                }
+            }
+            RecorderStatus::Recording { count, .. } => {
+                ui.label(egui::RichText::new(format!("● 记录中，已记录{}条", count)).color(egui::Color32::from_rgb(220, 38, 38)));
+                if profile_button(ui, "停止记录") {
+                    http_recorder::stop();
+                }
+            }
+            RecorderStatus::Replaying { total, served, .. } => {
+                ui.label(egui::RichText::new(format!("▶ 回放中（不联网），已使用{}/{}条", served, total)).color(egui::Color32::from_rgb(230, 150, 0)));
+                if profile_button(ui, "停止回放") {
+                    http_recorder::stop();
+                }
+            }
+        }
+    });
+}
+
+//配置档案：每个档案有独立的账号、推送和自定义设置
+pub fn profile_setting(app: &mut Myapp, ui: &mut egui::Ui){
+    let mut switch_to: Option<String> = None;
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("当前配置档案：")
+                  .size(15.0)
+                  .color(egui::Color32::from_rgb(0,0,0))
+                  .strong()
+        );
+        egui::ComboBox::from_id_source("profile_select")
+            .selected_text(&app.active_profile)
+            .show_ui(ui, |ui| {
+                for name in &app.profile_list {
+                    if ui.selectable_label(*name == app.active_profile, name).clicked() {
+                        switch_to = Some(name.clone());
+                    }
+                }
+            });
+        ui.label(
+            egui::RichText::new("切换后账号、推送和自定义设置都会替换为该档案的内容")
+                .color(egui::Color32::GRAY)
+        );
+    });
+    if let Some(name) = switch_to {
+        app.switch_profile(&name);
+    }
+    ui.separator();
+    ui.horizontal(|ui| {
+        common_input(ui, "档案名：", &mut app.profile_name_input, "请输入新档案名", false);
+        ui.add_space(10.0);
+        if profile_button(ui, "新建档案") {
+            match common::profile::create_profile(&app.profile_name_input) {
+                Ok(_) => {
+                    app.profile_list = common::profile::list_profiles();
+                    app.profile_name_input.clear();
+                }
+                Err(e) => log::error!("新建档案失败: {}", e),
+            }
+        }
+        if profile_button(ui, "复制当前档案") {
+            // 先保存当前设置，保证复制的是最新内容
+            if let Err(e) = save_config(&mut app.config, Some(&app.push_config), Some(&app.custom_config), None) {
+                log::error!("保存当前档案失败: {}", e);
+            }
+            match common::profile::duplicate_profile(&app.active_profile, &app.profile_name_input) {
+                Ok(_) => {
+                    app.profile_list = common::profile::list_profiles();
+                    app.profile_name_input.clear();
+                }
+                Err(e) => log::error!("复制档案失败: {}", e),
+            }
+        }
     });
+    let deletable: Vec<String> = app.profile_list
+        .iter()
+        .filter(|name| name.as_str() != common::profile::DEFAULT_PROFILE && **name != app.active_profile)
+        .cloned()
+        .collect();
+    if !deletable.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("删除档案：");
+            for name in deletable {
+                if ui.button(format!("🗑 {}", name)).clicked() {
+                    app.profile_delete_confirm = Some(name);
+                }
+            }
+        });
+    }
+    // 删除会连同档案里的账号和设置一起删掉，需要再确认一次
+    if let Some(name) = app.profile_delete_confirm.clone() {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(format!("确定删除档案「{}」？其中的账号、推送和自定义设置将一并删除，无法恢复", name))
+                    .color(egui::Color32::from_rgb(220, 38, 38))
+            );
+            if ui.button("确认删除").clicked() {
+                match common::profile::delete_profile(&name) {
+                    Ok(_) => app.profile_list = common::profile::list_profiles(),
+                    Err(e) => log::error!("删除档案失败: {}", e),
+                }
+                app.profile_delete_confirm = None;
+            }
+            if ui.button("取消").clicked() {
+                app.profile_delete_confirm = None;
+            }
+        });
+    }
+}
+
+//设置导入导出（明文json，默认不含cookie、推送token和联系人）
+pub fn transfer_setting(app: &mut Myapp, ui: &mut egui::Ui){
+    ui.horizontal(|ui| {
+        common_input(ui, "文件路径：", &mut app.settings_transfer_path, "导出/导入的json文件路径", false);
+    });
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut app.settings_export_options.include_accounts, "导出账号");
+        ui.add_enabled(
+            app.settings_export_options.include_accounts,
+            egui::Checkbox::new(&mut app.settings_export_options.include_cookies, "包含账号cookie"),
+        );
+        ui.add_enabled(
+            app.settings_export_options.include_accounts,
+            egui::Checkbox::new(&mut app.settings_export_options.include_personal, "包含联系人和备注"),
+        );
+        ui.checkbox(&mut app.settings_export_options.include_tokens, "包含推送token/打码key");
+    });
+    let options = &app.settings_export_options;
+    if options.include_cookies || options.include_tokens || (options.include_accounts && options.include_personal) {
+        ui.label(
+            egui::RichText::new("注意：导出文件为明文，包含登录凭据、token或联系人手机号，请勿发给他人")
+                .color(egui::Color32::from_rgb(200, 80, 0))
+        );
+    }
+    ui.horizontal(|ui| {
+        if profile_button(ui, "导出设置") {
+            // 导出前先同步界面上未保存的修改
+            app.config.push_config = app.push_config.clone();
+            app.config.custom_config = app.custom_config.clone();
+            let result = common::settings_transfer::export_settings(&app.config, &app.settings_export_options)
+                .and_then(|content| std::fs::write(&app.settings_transfer_path, content).map_err(|e| e.to_string()));
+            match result {
+                Ok(_) => log::info!("设置已导出到 {}", app.settings_transfer_path),
+                Err(e) => log::error!("导出设置失败: {}", e),
+            }
+        }
+        if profile_button(ui, "导入设置") {
+            let result = std::fs::read_to_string(&app.settings_transfer_path)
+                .map_err(|e| format!("读取文件失败: {}", e))
+                .and_then(|content| common::settings_transfer::preview_import(&app.config, &content));
+            match result {
+                Ok(preview) => {
+                    app.settings_import_resolutions.clear();
+                    app.settings_import_preview = Some(preview);
+                }
+                Err(e) => log::error!("导入设置失败: {}", e),
+            }
+        }
+    });
+}
+
+fn profile_button(ui: &mut egui::Ui, text: &str) -> bool {
+    let button = egui::Button::new(
+        egui::RichText::new(text).size(15.0).color(egui::Color32::WHITE)
+        )
+          .min_size(egui::vec2(100.0,35.0))
+          .fill(egui::Color32::from_rgb(102,204,255))
+          .rounding(15.0);
+    ui.add(button).clicked()
+}
+
+pub fn globle_setting(app: &mut Myapp, ui: &mut egui::Ui){
+    ui.label(egui::RichText::new("预填手机号已移到账号页各账号的「联系人」中").color(egui::Color32::GRAY));
     ui.separator();
     ui.horizontal(|ui|{
         ui.label("请选择验证码识别方式：");
@@ -182,6 +498,12 @@ pub fn globle_setting(app: &mut Myapp, ui: &mut egui::Ui){
         common_input(ui, "", &mut app.custom_config.custom_ua, "请输入自定义UA",false);
 
     });
+    ui.separator();
+    //实验功能：相关接口是按网页版推测的，没有经过抓包验证
+    ui.label(egui::RichText::new("实验功能（接口未经验证，可能失败或产生预期外的结果，请谨慎开启）").color(egui::Color32::from_rgb(200, 80, 0)));
+    ui.horizontal(|ui| {
+        on_switch(ui, "收货地址簿管理（不随订单提交）", &mut app.custom_config.experimental_address);
+    });
     
     
     
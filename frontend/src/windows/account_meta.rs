@@ -0,0 +1,133 @@
+use crate::app::Myapp;
+use crate::ui::account_tags::{tag_chip, tag_color};
+use eframe::egui::{self, Color32, RichText};
+use common::account::{all_tags, Account, AccountTag, TAG_COLORS};
+
+//编辑中的账号备注信息，保存时写回账号
+pub struct AccountMetaDraft {
+    pub uid: i64,
+    pub name: String,
+    pub alias: String,
+    pub notes: String,
+    pub tags: Vec<AccountTag>,
+    pub new_tag_name: String,
+    pub new_tag_color: [u8; 3],
+}
+
+impl AccountMetaDraft {
+    pub fn from_account(account: &Account) -> Self {
+        Self {
+            uid: account.uid,
+            name: account.name.clone(),
+            alias: account.alias.clone(),
+            notes: account.notes.clone(),
+            tags: account.tags.clone(),
+            new_tag_name: String::new(),
+            new_tag_color: TAG_COLORS[0],
+        }
+    }
+
+    fn add_tag(&mut self, tag: AccountTag) {
+        let name = tag.name.trim().to_string();
+        if name.is_empty() || self.tags.iter().any(|t| t.name == name) {
+            return;
+        }
+        self.tags.push(AccountTag { name, color: tag.color });
+    }
+}
+
+//编辑账号备注名、备注和标签
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let known_tags = all_tags(&app.account_manager.accounts);
+    let draft = match &mut app.account_meta_draft {
+        Some(draft) => draft,
+        None => return,
+    };
+    let mut open = true;
+    let mut save = false;
+    let mut cancel = false;
+
+    egui::Window::new("编辑账号信息")
+        .open(&mut open)
+        .default_size([420.0, 380.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(format!("账号：{} ({})", draft.name, draft.uid));
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("备注名：");
+                ui.add(egui::TextEdit::singleline(&mut draft.alias).hint_text("例如：小明的号").desired_width(240.0));
+            });
+            ui.label("备注：");
+            ui.add(egui::TextEdit::multiline(&mut draft.notes).desired_rows(3).desired_width(f32::INFINITY));
+            ui.separator();
+
+            ui.label("标签：");
+            let mut remove = None;
+            ui.horizontal_wrapped(|ui| {
+                if draft.tags.is_empty() {
+                    ui.label(RichText::new("暂无标签").color(Color32::GRAY));
+                }
+                for (index, tag) in draft.tags.iter().enumerate() {
+                    tag_chip(ui, tag);
+                    if ui.small_button("✖").on_hover_text("移除标签").clicked() {
+                        remove = Some(index);
+                    }
+                }
+            });
+            if let Some(index) = remove {
+                draft.tags.remove(index);
+            }
+
+            // 其他账号已有的标签，点击直接添加
+            let unused: Vec<&AccountTag> = known_tags.iter().filter(|t| !draft.tags.iter().any(|d| d.name == t.name)).collect();
+            if !unused.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("已有标签：");
+                    for tag in unused {
+                        if ui.button(RichText::new(format!("+ {}", tag.name)).color(tag_color(tag))).clicked() {
+                            draft.add_tag(tag.clone());
+                        }
+                    }
+                });
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("新标签：");
+                ui.add(egui::TextEdit::singleline(&mut draft.new_tag_name).desired_width(120.0));
+                for color in TAG_COLORS {
+                    let selected = draft.new_tag_color == color;
+                    let button = egui::Button::new(if selected { "✔" } else { " " })
+                        .fill(Color32::from_rgb(color[0], color[1], color[2]))
+                        .min_size(egui::vec2(18.0, 18.0));
+                    if ui.add(button).clicked() {
+                        draft.new_tag_color = color;
+                    }
+                }
+                if ui.button("添加").clicked() {
+                    let tag = AccountTag { name: draft.new_tag_name.clone(), color: draft.new_tag_color };
+                    draft.add_tag(tag);
+                    draft.new_tag_name.clear();
+                }
+            });
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("保存").clicked() {
+                    save = true;
+                }
+                if ui.button("取消").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if save {
+        if let Some(draft) = app.account_meta_draft.take() {
+            app.save_account_metadata(draft);
+        }
+    } else if !open || cancel {
+        app.account_meta_draft = None;
+    }
+}
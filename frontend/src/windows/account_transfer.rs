@@ -0,0 +1,154 @@
+use crate::app::Myapp;
+use eframe::egui::{self, Color32, RichText};
+use common::account_transfer::{export_bundle, import_bundle, AccountBundleEntry, ImportItem, ImportStatus};
+
+//账号导入导出：加密账号包导出/导入、从文本文件批量导入cookie
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let mut open = app.show_account_transfer;
+    egui::Window::new("账号导入导出")
+        .open(&mut open)
+        .default_size([560.0, 560.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                export_section(app, ui);
+                ui.separator();
+                import_section(app, ui);
+                ui.separator();
+                report_section(app, ui);
+            });
+        });
+    app.show_account_transfer = open;
+    if !open {
+        app.account_bundle_passphrase.clear();
+    }
+    if app.account_import_items.iter().any(|item| item.status.is_in_progress()) {
+        ctx.request_repaint_after(std::time::Duration::from_millis(200));
+    }
+}
+
+fn export_section(app: &mut Myapp, ui: &mut egui::Ui) {
+    ui.heading("导出账号");
+    ui.label(RichText::new("导出为加密账号包（含cookie和refresh_token），导入时需要输入相同口令").color(Color32::GRAY));
+    if app.account_manager.accounts.is_empty() {
+        ui.label("暂无账号");
+    }
+    ui.horizontal_wrapped(|ui| {
+        for account in &app.account_manager.accounts {
+            let mut selected = app.account_export_selected.contains(&account.uid);
+            if ui.checkbox(&mut selected, format!("{}({})", account.name, account.uid)).changed() {
+                if selected {
+                    app.account_export_selected.push(account.uid);
+                } else {
+                    app.account_export_selected.retain(|uid| *uid != account.uid);
+                }
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("账号包路径：");
+        ui.add(egui::TextEdit::singleline(&mut app.account_bundle_path).desired_width(360.0));
+    });
+    ui.horizontal(|ui| {
+        ui.label("口令：");
+        ui.add(egui::TextEdit::singleline(&mut app.account_bundle_passphrase).password(true).hint_text("至少6位").desired_width(200.0));
+    });
+    if ui.button("导出所选账号").clicked() {
+        let entries: Vec<AccountBundleEntry> = app
+            .account_manager
+            .accounts
+            .iter()
+            .filter(|account| app.account_export_selected.contains(&account.uid))
+            .map(AccountBundleEntry::from_account)
+            .collect();
+        let result = export_bundle(&entries, &app.account_bundle_passphrase)
+            .and_then(|content| std::fs::write(&app.account_bundle_path, content).map_err(|e| format!("写入文件失败: {}", e)));
+        match result {
+            Ok(_) => log::info!("已导出{}个账号到 {}", entries.len(), app.account_bundle_path),
+            Err(e) => log::error!("导出账号失败: {}", e),
+        }
+    }
+    ui.label(RichText::new("注意：账号包可直接登录账号，请妥善保管口令和文件").color(Color32::from_rgb(200, 80, 0)));
+}
+
+fn import_section(app: &mut Myapp, ui: &mut egui::Ui) {
+    let importing = app.account_import_items.iter().any(|item| item.status.is_in_progress());
+    ui.heading("导入账号");
+    ui.horizontal(|ui| {
+        if ui.add_enabled(!importing, egui::Button::new("从账号包导入")).clicked() {
+            let result = std::fs::read_to_string(&app.account_bundle_path)
+                .map_err(|e| format!("读取文件失败: {}", e))
+                .and_then(|content| import_bundle(&content, &app.account_bundle_passphrase));
+            match result {
+                Ok(entries) => {
+                    log::info!("账号包中有{}个账号，开始校验", entries.len());
+                    app.account_import_items = ImportItem::from_bundle(entries);
+                }
+                Err(e) => log::error!("导入账号包失败: {}", e),
+            }
+        }
+        ui.label(RichText::new("使用上方的账号包路径和口令").color(Color32::GRAY));
+    });
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.label("cookie文件：");
+        ui.add(egui::TextEdit::singleline(&mut app.account_cookie_file_path).desired_width(360.0));
+    });
+    ui.label(RichText::new("文本文件每行一个cookie，空行和#开头的行会被忽略").color(Color32::GRAY));
+    if ui.add_enabled(!importing, egui::Button::new("批量导入cookie")).clicked() {
+        match std::fs::read_to_string(&app.account_cookie_file_path) {
+            Ok(text) => {
+                let items = ImportItem::from_cookie_lines(&text);
+                if items.is_empty() {
+                    log::error!("文件中没有cookie");
+                } else {
+                    log::info!("读取到{}行cookie，开始校验", items.len());
+                    app.account_import_items = items;
+                }
+            }
+            Err(e) => log::error!("读取cookie文件失败: {}", e),
+        }
+    }
+}
+
+fn report_section(app: &mut Myapp, ui: &mut egui::Ui) {
+    if app.account_import_items.is_empty() {
+        return;
+    }
+    let count = |f: fn(&ImportStatus) -> bool| app.account_import_items.iter().filter(|item| f(&item.status)).count();
+    let pending = count(|s| s.is_in_progress());
+    let added = count(|s| matches!(s, ImportStatus::Added { .. }));
+    let updated = count(|s| matches!(s, ImportStatus::Updated { .. }));
+    let failed = count(|s| matches!(s, ImportStatus::Failed(_)));
+    ui.horizontal(|ui| {
+        ui.heading("导入结果");
+        if pending > 0 {
+            ui.spinner();
+        } else if ui.small_button("清空").clicked() {
+            app.account_import_items.clear();
+            return;
+        }
+    });
+    ui.label(format!("新增 {}，更新 {}，失败 {}，待校验 {}", added, updated, failed, pending));
+    egui::Grid::new("account_import_report")
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for item in &app.account_import_items {
+                ui.label(&item.source);
+                match &item.status {
+                    ImportStatus::Pending => ui.label(RichText::new("待校验").color(Color32::GRAY)),
+                    ImportStatus::Validating => ui.label(RichText::new("校验中...").color(Color32::GRAY)),
+                    ImportStatus::Added { uid, name } => {
+                        ui.label(RichText::new(format!("✔ 新增 {}({})", name, uid)).color(Color32::from_rgb(34, 160, 80)))
+                    }
+                    ImportStatus::Updated { uid, name } => {
+                        ui.label(RichText::new(format!("✔ 已更新 {}({})", name, uid)).color(Color32::from_rgb(34, 160, 80)))
+                    }
+                    ImportStatus::Failed(e) => ui.label(RichText::new(format!("✖ {}", e)).color(Color32::from_rgb(220, 38, 38))),
+                };
+                ui.end_row();
+            }
+        });
+}
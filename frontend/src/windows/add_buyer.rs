@@ -31,6 +31,9 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: &str) {
                 .color(egui::Color32::from_rgb(0,0,0))  
                 .strong()   
             );
+            if app.custom_config.experimental_address && ui.button("📦 管理收货地址").clicked() {
+                app.show_address_window = Some(uid.to_string());
+            }
         });
             ui.add_space(20.0);
             ui.horizontal(|ui|{
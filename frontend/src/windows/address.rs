@@ -0,0 +1,189 @@
+use crate::app::Myapp;
+use eframe::egui::{self, RichText};
+use common::address::{AddressInput, DeliveryAddress};
+use common::taskmanager::{SaveAddressRequest, TaskRequest};
+
+//收货地址管理：查看、新增、编辑、设为默认（纸质票快递用）
+pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: &str) {
+    let uid = match uid.parse::<i64>() {
+        Ok(uid) => uid,
+        Err(_) => {
+            app.show_address_window = None;
+            return;
+        }
+    };
+    if !app.account_manager.accounts.iter().any(|account| account.uid == uid) {
+        log::error!("未找到账号 {}", uid);
+        app.show_address_window = None;
+        return;
+    }
+    if !app.custom_config.experimental_address {
+        app.show_address_window = None;
+        return;
+    }
+    if !app.address_lists.contains_key(&uid) {
+        app.request_address_list(uid, false);
+    }
+    let mut window_open = app.show_address_window.is_some();
+    let addresses = app.address_lists.get(&uid).cloned();
+    let load_error = app.address_errors.get(&uid).cloned();
+
+    egui::Window::new("收货地址")
+        .open(&mut window_open)
+        .default_size([560.0, 520.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("收货地址");
+                if app.address_loading == Some(uid) {
+                    ui.spinner();
+                } else if ui.small_button("刷新").clicked() {
+                    app.request_address_list(uid, true);
+                }
+            });
+            ui.label(RichText::new("用于需要快递寄送纸质票的项目，下单时带上所选地址（实验功能）").color(egui::Color32::GRAY));
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                match &addresses {
+                    None if load_error.is_some() => {
+                        address_load_error(app, ui, uid, load_error.as_deref().unwrap_or_default());
+                    }
+                    None => {
+                        ui.label(RichText::new("正在获取收货地址...").color(egui::Color32::GRAY));
+                    }
+                    Some(list) if list.is_empty() => {
+                        ui.label(RichText::new("暂无收货地址，请在下方添加").color(egui::Color32::GRAY));
+                    }
+                    Some(list) => {
+                        for address in list {
+                            address_card(app, ui, uid, address);
+                            ui.add_space(4.0);
+                        }
+                    }
+                }
+            });
+
+            ui.separator();
+            address_form(app, ui, uid);
+        });
+
+    if !window_open {
+        app.show_address_window = None;
+        app.address_input = AddressInput::default();
+    }
+}
+
+fn address_card(app: &mut Myapp, ui: &mut egui::Ui, uid: i64, address: &DeliveryAddress) {
+    egui::Frame::none()
+        .fill(if ui.visuals().dark_mode { egui::Color32::from_rgb(6, 6, 6) } else { egui::Color32::from_rgb(245, 245, 250) })
+        .rounding(8.0)
+        .inner_margin(8.0)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(&address.name).strong());
+                ui.label(address.masked_phone());
+                if address.is_default {
+                    ui.label(RichText::new("默认").color(egui::Color32::from_rgb(251, 114, 153)));
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if !address.is_default && ui.add_enabled(!app.address_saving, egui::Button::new("设为默认").small()).clicked() {
+                        let mut input = AddressInput::from_address(address);
+                        input.is_default = true;
+                        submit_save(app, uid, input);
+                    }
+                    if ui.small_button("编辑").clicked() {
+                        app.address_input = AddressInput::from_address(address);
+                    }
+                });
+            });
+            ui.label(RichText::new(address.full_address()).color(egui::Color32::GRAY));
+        });
+}
+
+fn address_form(app: &mut Myapp, ui: &mut egui::Ui, uid: i64) {
+    let editing = app.address_input.id.is_some();
+    ui.heading(if editing { "编辑地址" } else { "新增地址" });
+    ui.add_space(5.0);
+    egui::Grid::new("address_form_grid")
+        .num_columns(2)
+        .spacing([10.0, 8.0])
+        .show(ui, |ui| {
+            let input = &mut app.address_input;
+            ui.label("收货人：");
+            ui.add(egui::TextEdit::singleline(&mut input.name).hint_text("姓名").desired_width(200.0));
+            ui.end_row();
+            ui.label("手机号：");
+            let response = ui.add(egui::TextEdit::singleline(&mut input.phone).hint_text("11位手机号").desired_width(200.0));
+            if response.changed() {
+                input.phone.retain(|c| c.is_ascii_digit());
+            }
+            ui.end_row();
+            ui.label("所在地区：");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut input.prov).hint_text("省份").desired_width(90.0));
+                ui.add(egui::TextEdit::singleline(&mut input.city).hint_text("城市").desired_width(90.0));
+                ui.add(egui::TextEdit::singleline(&mut input.area).hint_text("区县").desired_width(90.0));
+            });
+            ui.end_row();
+            ui.label("详细地址：");
+            ui.add(egui::TextEdit::multiline(&mut input.addr).hint_text("街道、门牌号等").desired_rows(2).desired_width(300.0));
+            ui.end_row();
+            ui.label("");
+            ui.checkbox(&mut input.is_default, "设为默认地址");
+            ui.end_row();
+        });
+
+    ui.add_space(5.0);
+    ui.horizontal(|ui| {
+        if app.address_saving {
+            ui.spinner();
+            ui.label("正在保存...");
+        } else if ui.button("保存").clicked() {
+            match app.address_input.validate() {
+                Ok(_) => {
+                    let input = app.address_input.clone();
+                    submit_save(app, uid, input);
+                }
+                Err(e) => log::error!("{}", e),
+            }
+        }
+        if editing && ui.button("取消编辑").clicked() {
+            app.address_input = AddressInput::default();
+        }
+    });
+}
+
+fn submit_save(app: &mut Myapp, uid: i64, input: AddressInput) {
+    let cookie_manager = match app.account_manager.accounts.iter()
+        .find(|account| account.uid == uid)
+        .and_then(|account| account.cookie_manager.clone())
+    {
+        Some(cookie_manager) => cookie_manager,
+        None => {
+            log::error!("账号 {} 未登录", uid);
+            return;
+        }
+    };
+    let request = TaskRequest::SaveAddressRequest(SaveAddressRequest {
+        uid,
+        task_id: "".to_string(),
+        input,
+        cookie_manager,
+    });
+    match app.task_manager.submit_task(request) {
+        Ok(_) => app.address_saving = true,
+        Err(e) => log::error!("提交保存收货地址请求失败: {}", e),
+    }
+}
+
+//获取地址失败：显示原因和重试按钮
+fn address_load_error(app: &mut Myapp, ui: &mut egui::Ui, uid: i64, message: &str) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(RichText::new(format!("获取收货地址失败：{}", message)).color(egui::Color32::from_rgb(220, 38, 38)));
+        if ui.button("重试").clicked() {
+            app.request_address_list(uid, true);
+        }
+    });
+}
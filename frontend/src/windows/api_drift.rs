@@ -0,0 +1,49 @@
+use crate::app::Myapp;
+use eframe::egui::{self, Color32, RichText};
+use common::schema_drift::{acknowledge, reports};
+
+//接口变化详情：列出各接口缺失、类型变化和新出现的字段
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let mut open = true;
+    let reports = reports();
+
+    egui::Window::new("B站接口变化")
+        .open(&mut open)
+        .default_size([520.0, 400.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(RichText::new("以下字段与程序预期不一致。项目id、票价、开售时间等关键字段出错时会停止抢票，其余字段已按默认值或转换后的值继续使用。若抢票异常，请附上日志反馈。").color(Color32::GRAY));
+            ui.separator();
+
+            if reports.is_empty() {
+                ui.label(RichText::new("本次运行未发现接口变化").color(Color32::GRAY));
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for report in &reports {
+                    let time = common::time_format::format_local(report.at);
+                    ui.label(RichText::new(format!("{}（{}）", report.endpoint, time)).strong());
+                    for path in &report.missing {
+                        ui.label(RichText::new(format!("  缺少字段 {}", path)).color(Color32::from_rgb(200, 60, 60)));
+                    }
+                    for (path, reason) in &report.type_changed {
+                        ui.label(RichText::new(format!("  类型变化 {}：{}", path, reason)).color(Color32::from_rgb(200, 120, 0)));
+                    }
+                    for path in &report.unexpected {
+                        ui.label(RichText::new(format!("  新字段 {}", path)).color(Color32::GRAY));
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+
+            ui.separator();
+            if ui.button("知道了").clicked() {
+                acknowledge();
+                app.show_api_drift = false;
+            }
+        });
+
+    if !open {
+        acknowledge();
+        app.show_api_drift = false;
+    }
+}
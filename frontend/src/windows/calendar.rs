@@ -0,0 +1,111 @@
+use crate::app::Myapp;
+use eframe::egui::{self, RichText};
+use common::calendar::{to_ics_events, EntryKind};
+use common::ics::to_ics;
+use common::time_format::{format_with_countdown, local_differs, local_zone_label, to_beijing};
+
+//开售日历：关注项目的开售/停售/演出时间和已购订单，可导出ics
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let mut window_open = app.show_calendar_window;
+    let entries = app.calendar_entries();
+
+    egui::Window::new("开售日历")
+        .open(&mut window_open)
+        .default_size([700.0, 550.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            let mut changed = false;
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .checkbox(&mut app.config.calendar.reminder_enabled, "开售前推送提醒")
+                    .changed();
+                ui.label("提前(分钟)：");
+                let response = ui.add(
+                    egui::DragValue::new(&mut app.config.calendar.reminder_minutes)
+                        .clamp_range(1..=720)
+                        .speed(1),
+                );
+                changed |= response.drag_released() || response.lost_focus();
+            });
+            if app.config.calendar.reminder_enabled && !app.push_config.enabled {
+                ui.label(RichText::new("推送总开关未开启，提醒只会写入日志").color(egui::Color32::GRAY));
+            }
+            if changed {
+                if let Err(e) = app.config.save() {
+                    log::error!("保存日历设置失败: {}", e);
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("导出路径：");
+                ui.add(egui::TextEdit::singleline(&mut app.calendar_export_path).desired_width(360.0));
+                if ui.button("导出ICS").clicked() {
+                    let alarm = if app.config.calendar.reminder_enabled {
+                        Some(app.config.calendar.reminder_minutes)
+                    } else {
+                        None
+                    };
+                    let content = to_ics(&to_ics_events(&entries, alarm));
+                    match std::fs::write(&app.calendar_export_path, content) {
+                        Ok(_) => log::info!("已导出{}个日程到 {}", entries.len(), app.calendar_export_path),
+                        Err(e) => log::error!("导出日历失败: {}", e),
+                    }
+                }
+            });
+            ui.label(
+                RichText::new("日程来自关注列表（刷新后）和已打开过订单列表的账号订单，时间为北京时间")
+                    .color(egui::Color32::GRAY),
+            );
+            let now = chrono::Utc::now().timestamp();
+            if local_differs(now) {
+                ui.label(
+                    RichText::new(format!("本地时区为 {}，鼠标悬停在时间上可查看本地时间", local_zone_label()))
+                        .color(egui::Color32::GRAY),
+                );
+            }
+            ui.separator();
+
+            if entries.is_empty() {
+                ui.label(RichText::new("暂无即将到来的日程").color(egui::Color32::GRAY));
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut current_day = String::new();
+                for entry in &entries {
+                    let time = match to_beijing(entry.time) {
+                        Some(time) => time,
+                        None => continue,
+                    };
+                    let day = time.format("%Y-%m-%d").to_string();
+                    if day != current_day {
+                        ui.add_space(6.0);
+                        ui.label(RichText::new(&day).strong().size(16.0));
+                        current_day = day;
+                    }
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(RichText::new(time.format("%H:%M").to_string()).monospace())
+                            .on_hover_text(format_with_countdown(entry.time, now));
+                        ui.label(RichText::new(entry.kind.label()).color(kind_color(entry.kind)).strong());
+                        ui.label(RichText::new(&entry.title).strong());
+                        if !entry.detail.is_empty() {
+                            ui.label(RichText::new(&entry.detail).color(egui::Color32::GRAY));
+                        }
+                        if !entry.location.is_empty() {
+                            ui.label(RichText::new(format!("@{}", entry.location)).color(egui::Color32::GRAY));
+                        }
+                        ui.label(RichText::new(format!("[{}]", entry.source)).color(egui::Color32::GRAY));
+                    });
+                }
+            });
+        });
+
+    app.show_calendar_window = window_open;
+}
+
+fn kind_color(kind: EntryKind) -> egui::Color32 {
+    match kind {
+        EntryKind::SaleStart => egui::Color32::from_rgb(0, 150, 0),
+        EntryKind::SaleEnd => egui::Color32::GRAY,
+        EntryKind::Performance => egui::Color32::from_rgb(0, 161, 214),
+    }
+}
@@ -0,0 +1,38 @@
+use crate::app::Myapp;
+use eframe::egui::{self, Color32, RichText};
+
+//配置文件读取异常：说明哪些部分被重置，以及原文件的备份位置
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let message = match &app.config_load_error {
+        Some(message) => message.clone(),
+        None => return,
+    };
+    let mut open = true;
+    let mut dismissed = false;
+
+    egui::Window::new("配置读取异常")
+        .open(&mut open)
+        .default_size([480.0, 320.0])
+        .resizable(true)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            ui.label(RichText::new("配置文件没有完整读取，请检查账号和设置是否正确。").color(Color32::from_rgb(220, 38, 38)));
+            ui.separator();
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                ui.label(&message);
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("复制说明").clicked() {
+                    ui.output_mut(|o| o.copied_text = message.clone());
+                }
+                if ui.button("知道了").clicked() {
+                    dismissed = true;
+                }
+            });
+        });
+
+    if !open || dismissed {
+        app.config_load_error = None;
+    }
+}
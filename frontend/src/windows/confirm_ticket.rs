@@ -3,6 +3,10 @@ use std::sync::Arc;
 use common::cookie_manager::CookieManager;
 use common::ticket::{*};
 use common::taskmanager::{GrabTicketRequest, TaskStatus, TaskRequest};
+use common::address::{needs_delivery, total_with_express};
+use common::contact::{account_contacts, add_contact, default_contact};
+use common::dry_run::DRY_RUN_TAG;
+use common::preflight::{run_preflight, CheckLevel, FixAction, PreflightInput, PreflightReport};
 use eframe::egui;
 use egui::{Color32, RichText, Vec2, Stroke};
 
@@ -21,12 +25,13 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
         }
     };
     let biliticket_uid;
-    let biliticket_project_id;
     let cookie_manager: Arc<CookieManager>;
     let id_bind;
     let screen_info: Option<ScreenInfo>;
     let ticket_info: Option<ScreenTicketInfo>;
     let buyers;
+    let express_fee;
+    let needs_address;
     
 
     app.is_loading = false;
@@ -34,7 +39,6 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
         let biliticket = &app.bilibiliticket_list[biliticket_index];
         
         biliticket_uid = biliticket.uid;
-        biliticket_project_id = biliticket.project_info.as_ref().map(|p| p.id.to_string());
         cookie_manager = biliticket.account.cookie_manager.clone().unwrap();
         
         id_bind = match &biliticket.project_info {
@@ -61,6 +65,11 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
     };
     screen_info = screen;
     ticket_info = ticket;
+    express_fee = biliticket.project_info.as_ref().map_or(0, |p| p.express_fee);
+    needs_address = match (&biliticket.project_info, &screen_info) {
+        (Some(project), Some(screen)) => needs_delivery(project, screen),
+        _ => false,
+    };
     
 
     // 获取购票人列表
@@ -149,11 +158,22 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
                                     app.bilibiliticket_list[biliticket_index].count = Some(count);
                                 }
                                 
-                                ui.label(format!("总价: ¥{:.2}", (ticket.price as f64 / 100.0) * count as f64));
+                                if needs_address {
+                                    let total = total_with_express(ticket.price, count, express_fee);
+                                    ui.label(format!("总价: ¥{:.2}（含快递费 ¥{:.2}）", total as f64 / 100.0, express_fee as f64 / 100.0));
+                                } else {
+                                    ui.label(format!("总价: ¥{:.2}", (ticket.price as f64 / 100.0) * count as f64));
+                                }
                             });
                         }
                     }
                 });
+
+            if needs_address {
+                ui.add_space(10.0);
+                ui.heading("收货地址");
+                ui.label(RichText::new("该场次为纸质票快递。下单接口带地址的格式未经验证，本工具不会提交收货地址，可能下单失败，建议在网页端下单").color(Color32::from_rgb(200, 80, 0)));
+            }
             
             match id_bind{
                 0 =>{
@@ -161,17 +181,68 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
                     ui.heading("输入联系人");
                     ui.add_space(5.0);
 
+                    let account_index = app.account_manager.accounts.iter().position(|a| a.uid == app.bilibiliticket_list[biliticket_index].uid);
+                    let contacts = account_contacts(account_index.map(|i| &app.account_manager.accounts[i]), &app.config.shared_contacts);
+                    {
+                        let biliticket = &mut app.bilibiliticket_list[biliticket_index];
+                        // 确保 no_bind_buyer_info 已经初始化，有默认联系人时直接带入
+                        if biliticket.no_bind_buyer_info.is_none() {
+                            let contact = default_contact(&contacts);
+                            biliticket.no_bind_buyer_info = Some(NoBindBuyerInfo {
+                              name: contact.map(|c| c.name.clone()).unwrap_or_default(),
+                              tel: contact.map(|c| c.phone.clone()).unwrap_or_default(),
+                              uid: biliticket.uid,
+                            });
+                        }
+                    }
+
+                    let mut save_contact = None;
+                    ui.horizontal(|ui|{
+                        let biliticket = &mut app.bilibiliticket_list[biliticket_index];
+                        if let Some(ref mut buyer_info) = biliticket.no_bind_buyer_info {
+                            ui.label("从联系人选择：");
+                            egui::ComboBox::from_id_source("no_bind_contact")
+                                .selected_text(
+                                    contacts.iter()
+                                        .find(|c| c.phone == buyer_info.tel && c.name == buyer_info.name)
+                                        .map(|c| c.label())
+                                        .unwrap_or_else(|| "手动输入".to_string()),
+                                )
+                                .width(200.0)
+                                .show_ui(ui, |ui| {
+                                    for contact in &contacts {
+                                        if ui.selectable_label(contact.phone == buyer_info.tel && contact.name == buyer_info.name, contact.label()).clicked() {
+                                            buyer_info.name = contact.name.clone();
+                                            buyer_info.tel = contact.phone.clone();
+                                        }
+                                    }
+                                });
+                            if contacts.is_empty() {
+                                ui.label(RichText::new("可在账号页「联系人」中添加").color(Color32::GRAY));
+                            }
+                            let saved = contacts.iter().any(|c| c.phone == buyer_info.tel && c.name == buyer_info.name);
+                            if !saved && !buyer_info.tel.is_empty() && ui.button("存为联系人").clicked() {
+                                save_contact = Some((buyer_info.name.clone(), buyer_info.tel.clone()));
+                            }
+                        }
+                    });
+                    if let (Some((name, tel)), Some(account_index)) = (save_contact, account_index) {
+                        let account = &mut app.account_manager.accounts[account_index];
+                        match add_contact(&mut account.contacts, &name, &tel) {
+                            Ok(_) => {
+                                let account = account.clone();
+                                app.config.update_account(&account);
+                                if let Err(e) = app.config.save() {
+                                    log::error!("保存联系人失败: {}", e);
+                                }
+                            }
+                            Err(e) => log::error!("添加联系人失败: {}", e),
+                        }
+                    }
+                    ui.add_space(5.0);
+
                     ui.horizontal(|ui|{
                         let biliticket = &mut app.bilibiliticket_list[biliticket_index];
-        
-                         // 确保 no_bind_buyer_info 已经初始化
-                         if biliticket.no_bind_buyer_info.is_none() {
-                             biliticket.no_bind_buyer_info = Some(NoBindBuyerInfo {
-                               name: String::new(),
-                               tel: String::new(),
-                               uid: biliticket.uid,
-                             });
-                         }
                          
                          if let Some(ref mut buyer_info) = biliticket.no_bind_buyer_info {
                             // 为姓名创建一个临时 Option<String>
@@ -383,131 +454,35 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
             
             ui.horizontal(|ui| {
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    // 根据模式决定按钮启用条件
-                    let biliticket = &app.bilibiliticket_list[biliticket_index];
-                    let local_captcha = app.local_captcha.clone();
-                    let is_hot = biliticket.project_info.clone().as_ref().map_or(false, |p| p.hot_project);
-                    let button_enabled = match id_bind {
-                        0 => {
-                            // 检查非实名购票人信息是否完整
-                            if let Some(info) = &biliticket.no_bind_buyer_info {
-                                !info.name.is_empty() && !info.tel.is_empty()
-                            } else {
-                                false
-                            }
-       
-                        },
-                        1 => app.selected_buyer_list.as_ref().map_or(false, |list| !list.is_empty()),
-                        2 => {
-                            // 多选模式，需要确保选中的购票人数量与票数匹配
-                            let ticket_count = app.bilibiliticket_list[biliticket_index].clone().count.unwrap_or(1);
-                            app.selected_buyer_list.as_ref().map_or(false, |list| !list.is_empty() && list.len() == ticket_count as usize)
-                        },
-                        _ => false,
-                    };
+                    // 购票人、地址等是否填写完整交给开抢前检查，给出修复入口
+                    let is_hot = app.bilibiliticket_list[biliticket_index].project_info.as_ref().map_or(false, |p| p.hot_project);
                     
-                    if ui.add_enabled(
-                        button_enabled,
+                    if ui.add(
                         egui::Button::new("确认购票")
                             .fill(Color32::from_rgb(59, 130, 246))
                             .min_size(Vec2::new(100.0, 36.0))
                     ).clicked() {
-                        match id_bind {
-                            0 => {
-                                log::info!("确认非实名票购票");
-                                let biliticket = &app.bilibiliticket_list[biliticket_index];
-                                if let Some(ref buyer_info) = biliticket.no_bind_buyer_info {
-                                    log::info!("非实名购票人信息: {:?}", buyer_info);
-                                    if let Some(screen) = screen_info {
-                                        if let Some(ticket) = ticket_info {
-                                            // 提交抢票任务
-                                    let grab_ticket_request = GrabTicketRequest {
-                                        task_id: "".to_string(),
-                                        uid: biliticket_uid,
-                                        project_id: biliticket_project_id.clone().unwrap_or_default(),
-                                        screen_id: screen.id.to_string(),
-                                        ticket_id: ticket.id.to_string(),
-                                        is_hot: is_hot, 
-                                        count: app.bilibiliticket_list[biliticket_index].clone().count.unwrap_or(1) as i16,
-                                        buyer_info: Vec::new(), // 实名购票人信息,这里传空列表
-                                        grab_mode: app.grab_mode,
-                                        status: TaskStatus::Pending,
-                                        start_time: None,
-                                        cookie_manager: cookie_manager.clone(),
-                                        biliticket: biliticket.clone(),
-                                        local_captcha: local_captcha.clone(),
-                                        skip_words: app.skip_words.clone(),
-                                    };
-                                    log::debug!("提交抢票任务: {:?}", grab_ticket_request);
-                                    // 提交到任务管理器
-                                    match app.task_manager.submit_task(TaskRequest::GrabTicketRequest(grab_ticket_request)) {
-                                        Ok(task_id) => {
-                                            log::info!("提交抢票任务成功，任务ID: {}", task_id);
-                                            app.confirm_ticket_info = None;
-                                            
-                                            
-                                        },
-                                        Err(e) => {
-                                            log::error!("提交抢票任务失败: {}", e);
-                                        }
-                                    }
-
-                                        }
-                                    }
-                                    
-                                }
-                            }
-                            1 | 2 => {
-                                if let Some(ref buyer_list) = app.selected_buyer_list {
-                                    if !buyer_list.is_empty() {
-                                        let ids: Vec<i64> = buyer_list.iter().map(|b| b.id).collect();
-                                        log::info!("确认购票，选择的购票人IDs: {:?}", ids);
-                                        
-                                        if let Some(screen) = screen_info_button {
-                                            if let Some(ticket) = ticket_info {
-                                                // 提交抢票任务
-                                                let grab_ticket_request = GrabTicketRequest {
-                                                    task_id: "".to_string(),
-                                                    uid: biliticket_uid,
-                                                    project_id: biliticket_project_id.clone().unwrap_or_default(),
-                                                    screen_id: screen.id.to_string(),
-                                                    ticket_id: ticket.id.to_string(),
-                                                    is_hot: is_hot,
-                                                    count: app.bilibiliticket_list[biliticket_index].clone().count.unwrap_or(1) as i16,
-                                                    buyer_info: buyer_list.clone(),
-                                                    grab_mode: app.grab_mode,
-                                                    status: TaskStatus::Pending,
-                                                    start_time: None,
-                                                    cookie_manager: cookie_manager.clone(),
-                                                    biliticket: biliticket.clone(),
-                                                    local_captcha: local_captcha.clone(),
-                                                    skip_words: app.skip_words.clone(),
-
-                                                };
-                                                log::debug!("提交抢票任务: {:?}", grab_ticket_request);
-                                                // 提交到任务管理器
-                                                match app.task_manager.submit_task(TaskRequest::GrabTicketRequest(grab_ticket_request)) {
-                                                    Ok(task_id) => {
-                                                        log::info!("提交抢票任务成功，任务ID: {}", task_id);
-                                                        app.confirm_ticket_info = None;
-                                                        app.selected_buyer_list = None; 
-                                                    },
-                                                    Err(e) => {
-                                                        log::error!("提交抢票任务失败: {}", e);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        // 关闭窗口
-                                        app.confirm_ticket_info = None;
-                                    }
-                                }
-                            }
-                            
-                            _ => {
-                                log::error!("未知的购票人绑定状态: {}", id_bind);
-                            }
-                        }
+                        // 先做开抢前检查，确认后再提交
+                        let biliticket = &app.bilibiliticket_list[biliticket_index];
+                        let count = biliticket.count.unwrap_or(1);
+                        let buyers = if id_bind == 0 { Vec::new() } else { app.selected_buyer_list.clone().unwrap_or_default() };
+                        let report = run_preflight(&PreflightInput {
+                            biliticket,
+                            screen: screen_info_button.as_ref(),
+                            ticket_id: app.selected_ticket_id,
+                            count,
+                            buyers: &buyers,
+                            push_config: &app.push_config,
+                            now: chrono::Utc::now().timestamp(),
+                        });
+                        app.preflight = Some(PendingGrab {
+                            uid: biliticket_uid,
+                            report,
+                            screen: screen_info_button.clone(),
+                            ticket: ticket_info.clone(),
+                            buyers,
+                            is_hot,
+                        });
                     }
                     
                     if ui.button("取消").clicked() {
@@ -517,10 +492,176 @@ pub fn show(app: &mut Myapp,ctx:&egui::Context,uid:&i64){
             });
         });
         
+    preflight_dialog(app, ctx, biliticket_index, cookie_manager, id_bind);
+
     // 更新窗口打开状态
     if !open {
         app.confirm_ticket_info = None;
     }
+    if app.confirm_ticket_info.is_none() {
+        app.preflight = None;
+    }
+}
+
+//开抢前检查结果，通过（或只有警告）时才能开始抢票
+pub struct PendingGrab {
+    pub uid: i64,
+    pub report: PreflightReport,
+    pub screen: Option<ScreenInfo>,
+    pub ticket: Option<ScreenTicketInfo>,
+    pub buyers: Vec<BuyerInfo>,
+    pub is_hot: bool,
+}
+
+fn preflight_dialog(app: &mut Myapp, ctx: &egui::Context, biliticket_index: usize, cookie_manager: Arc<CookieManager>, id_bind: usize) {
+    let pending = match &app.preflight {
+        Some(pending) => pending,
+        None => return,
+    };
+    let uid = pending.uid;
+    let has_errors = pending.report.has_errors();
+    let mut fix: Option<FixAction> = None;
+    let mut start = false;
+    let mut dry_run = false;
+    let mut close = false;
+
+    egui::Window::new("开抢前检查")
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "通过 {} 项，警告 {} 项，错误 {} 项",
+                pending.report.count(CheckLevel::Pass),
+                pending.report.count(CheckLevel::Warning),
+                pending.report.count(CheckLevel::Error)
+            ));
+            ui.separator();
+            egui::Grid::new("preflight_grid")
+                .num_columns(4)
+                .spacing([10.0, 6.0])
+                .show(ui, |ui| {
+                    for item in &pending.report.items {
+                        let (icon, color) = match item.level {
+                            CheckLevel::Pass => ("✔", Color32::from_rgb(34, 160, 80)),
+                            CheckLevel::Warning => ("⚠", Color32::from_rgb(230, 150, 0)),
+                            CheckLevel::Error => ("✖", Color32::from_rgb(220, 38, 38)),
+                        };
+                        ui.label(RichText::new(icon).color(color).strong());
+                        ui.label(RichText::new(item.title).strong());
+                        ui.label(&item.detail);
+                        match item.fix {
+                            Some(action) => {
+                                if ui.small_button(action.label()).clicked() {
+                                    fix = Some(action);
+                                }
+                            }
+                            None => {
+                                ui.label("");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+            ui.horizontal(|ui| {
+                let text = if pending.report.count(CheckLevel::Warning) > 0 { "忽略警告，开始抢票" } else { "开始抢票" };
+                if ui.add_enabled(!has_errors, egui::Button::new(text).fill(Color32::from_rgb(59, 130, 246))).clicked() {
+                    start = true;
+                }
+                if ui.add_enabled(!app.dry_run_running, egui::Button::new("演练（不下单）"))
+                    .on_hover_text("加载项目和购票人、检查并确认订单，但不会创建订单")
+                    .clicked()
+                {
+                    dry_run = true;
+                }
+                if ui.button("返回修改").clicked() {
+                    close = true;
+                }
+            });
+            if has_errors {
+                ui.label(RichText::new("请先处理标记为 ✖ 的问题").color(Color32::from_rgb(220, 38, 38)));
+            }
+        });
+
+    if let Some(action) = fix {
+        app.preflight = None;
+        match action {
+            FixAction::Relogin => app.show_login_windows = true,
+            FixAction::ReselectTicket => {
+                app.confirm_ticket_info = None;
+                app.show_screen_info = Some(uid);
+            }
+            FixAction::AddBuyer => {
+                app.show_add_buyer_window = Some(uid.to_string());
+                app.confirm_ticket_info = None;
+            }
+            FixAction::PushSettings => app.selected_tab = 3,
+        }
+        return;
+    }
+    if start || dry_run {
+        if let Some(pending) = app.preflight.take() {
+            submit_grab(app, biliticket_index, cookie_manager, id_bind, pending, dry_run);
+        }
+    } else if close {
+        app.preflight = None;
+    }
+}
+
+fn submit_grab(app: &mut Myapp, biliticket_index: usize, cookie_manager: Arc<CookieManager>, id_bind: usize, pending: PendingGrab, dry_run: bool) {
+    let (screen, ticket) = match (pending.screen, pending.ticket) {
+        (Some(screen), Some(ticket)) => (screen, ticket),
+        _ => {
+            log::error!("未选择场次或票种");
+            return;
+        }
+    };
+    let biliticket = &app.bilibiliticket_list[biliticket_index];
+    if id_bind == 0 {
+        log::info!("确认非实名票购票");
+        log::info!("非实名购票人信息: {:?}", biliticket.no_bind_buyer_info);
+    } else {
+        let ids: Vec<i64> = pending.buyers.iter().map(|b| b.id).collect();
+        log::info!("确认购票，选择的购票人IDs: {:?}", ids);
+    }
+    // 提交抢票任务
+    let grab_ticket_request = GrabTicketRequest {
+        task_id: "".to_string(),
+        uid: pending.uid,
+        project_id: biliticket.project_info.as_ref().map(|p| p.id.to_string()).unwrap_or_default(),
+        screen_id: screen.id.to_string(),
+        ticket_id: ticket.id.to_string(),
+        is_hot: pending.is_hot,
+        count: biliticket.count.unwrap_or(1) as i16,
+        buyer_info: pending.buyers, // 非实名时为空列表
+        grab_mode: app.grab_mode,
+        status: TaskStatus::Pending,
+        start_time: None,
+        cookie_manager,
+        biliticket: biliticket.clone(),
+        local_captcha: app.local_captcha.clone(),
+        skip_words: app.skip_words.clone(),
+        dry_run,
+    };
+    log::debug!("提交抢票任务: {:?}", grab_ticket_request);
+    // 提交到任务管理器
+    match app.task_manager.submit_task(TaskRequest::GrabTicketRequest(grab_ticket_request)) {
+        Ok(task_id) if dry_run => {
+            // 演练后保留当前选择，方便修改后再次演练或直接开抢
+            log::info!("{} 提交演练任务成功，任务ID: {}", DRY_RUN_TAG, task_id);
+            app.dry_run_running = true;
+        },
+        Ok(task_id) => {
+            log::info!("提交抢票任务成功，任务ID: {}", task_id);
+            app.confirm_ticket_info = None;
+            app.selected_buyer_list = None;
+        },
+        Err(e) => {
+            log::error!("提交抢票任务失败: {}", e);
+        }
+    }
 }
 
 // 隐藏部分证件号码
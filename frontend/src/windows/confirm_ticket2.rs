@@ -224,7 +224,7 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: &i64) {
             ui.add_space(15.0);
             ui.separator();
             ui.add_space(10.0);
-            
+
             ui.heading("关键词过滤");
             ui.label(RichText::new("输入需要过滤的关键词，多个关键词用空格分隔。当捡漏到包含这些关键词的标题时将自动跳过。").color(Color32::DARK_GRAY));
             ui.add_space(5.0);
@@ -334,6 +334,7 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: &i64) {
                                     biliticket: biliticket.clone(),
                                     local_captcha,
                                     skip_words: app.skip_words.clone(),
+                                    dry_run: false,
                                 };
                                 
                                 log::debug!("提交捡漏模式任务: {:?}", grab_ticket_request);
@@ -0,0 +1,117 @@
+use crate::app::Myapp;
+use eframe::egui::{self, Color32, RichText};
+use common::contact::{add_contact, remove_contact, set_default, Contact};
+
+//账号联系人和共享联系人：非实名票联系人、短信登录手机号从这里选
+pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: i64) {
+    let index = match app.account_manager.accounts.iter().position(|a| a.uid == uid) {
+        Some(index) => index,
+        None => {
+            app.show_contacts_window = None;
+            return;
+        }
+    };
+    let mut open = true;
+    let mut account_changed = false;
+    let mut shared_changed = false;
+
+    egui::Window::new("联系人")
+        .open(&mut open)
+        .default_size([420.0, 480.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            let account = &mut app.account_manager.accounts[index];
+            ui.label(format!("账号：{} ({})", account.display_name(), account.uid));
+            ui.separator();
+            account_changed = contact_list(
+                ui,
+                "contact_list",
+                &mut account.contacts,
+                &mut app.contact_name_input,
+                &mut app.contact_phone_input,
+            );
+
+            ui.add_space(10.0);
+            ui.heading("共享联系人");
+            ui.label(RichText::new("所有账号下单、短信登录时都可以选择").color(Color32::GRAY));
+            ui.separator();
+            shared_changed = contact_list(
+                ui,
+                "shared_contact_list",
+                &mut app.config.shared_contacts,
+                &mut app.shared_contact_name_input,
+                &mut app.shared_contact_phone_input,
+            );
+        });
+
+    if account_changed {
+        let account = app.account_manager.accounts[index].clone();
+        app.config.update_account(&account);
+    }
+    if account_changed || shared_changed {
+        if let Err(e) = app.config.save() {
+            log::error!("保存联系人失败: {}", e);
+        }
+    }
+    if !open {
+        app.show_contacts_window = None;
+    }
+}
+
+//联系人列表和添加/修改输入框，有改动时返回 true
+fn contact_list(ui: &mut egui::Ui, id: &str, contacts: &mut Vec<Contact>, name_input: &mut String, phone_input: &mut String) -> bool {
+    let mut changed = false;
+    if contacts.is_empty() {
+        ui.label(RichText::new("暂无联系人").color(Color32::GRAY));
+    }
+    let mut remove = None;
+    let mut make_default = None;
+    egui::Grid::new(id)
+        .num_columns(5)
+        .spacing([12.0, 6.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (i, contact) in contacts.iter().enumerate() {
+                ui.label(&contact.name);
+                ui.label(&contact.phone);
+                if ui.radio(contact.is_default, "默认").clicked() && !contact.is_default {
+                    make_default = Some(i);
+                }
+                // 按手机号保存，修改姓名后点「保存」即可覆盖
+                if ui.small_button("编辑").clicked() {
+                    *name_input = contact.name.clone();
+                    *phone_input = contact.phone.clone();
+                }
+                if ui.small_button("删除").clicked() {
+                    remove = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+    if let Some(i) = make_default {
+        set_default(contacts, i);
+        changed = true;
+    }
+    if let Some(i) = remove {
+        remove_contact(contacts, i);
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("姓名：");
+        ui.add(egui::TextEdit::singleline(name_input).desired_width(100.0));
+        ui.label("手机号：");
+        ui.add(egui::TextEdit::singleline(phone_input).desired_width(130.0));
+        if ui.button("保存").clicked() {
+            match add_contact(contacts, name_input, phone_input) {
+                Ok(_) => {
+                    name_input.clear();
+                    phone_input.clear();
+                    changed = true;
+                }
+                Err(e) => log::error!("添加联系人失败: {}", e),
+            }
+        }
+    });
+    changed
+}
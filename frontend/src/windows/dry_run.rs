@@ -0,0 +1,63 @@
+use crate::app::Myapp;
+use eframe::egui::{self, Color32, RichText};
+use common::preflight::CheckLevel;
+use common::time_format::format_local;
+
+//演练报告：列出本应提交的订单内容和各步骤结果
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let report = match &app.dry_run_report {
+        Some(report) => report,
+        None => return,
+    };
+    let mut open = true;
+
+    egui::Window::new("演练报告（未下单）")
+        .open(&mut open)
+        .default_size([520.0, 480.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::Frame::none()
+                .fill(Color32::from_rgb(255, 237, 213))
+                .rounding(6.0)
+                .inner_margin(8.0)
+                .show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.label(RichText::new("演练模式：以下内容不会提交，没有创建任何订单").color(Color32::from_rgb(154, 52, 18)).strong());
+                });
+            ui.add_space(6.0);
+            ui.label(format!("账号：{}    完成时间：{}", report.uid, format_local(report.finished_at)));
+            ui.separator();
+
+            ui.heading("本应提交的订单");
+            let summary = report.order_summary();
+            ui.label(&summary);
+            if ui.small_button("复制").clicked() {
+                ui.output_mut(|o| o.copied_text = summary.clone());
+            }
+            ui.separator();
+
+            ui.heading("步骤");
+            egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                egui::Grid::new("dry_run_steps")
+                    .num_columns(3)
+                    .spacing([10.0, 6.0])
+                    .show(ui, |ui| {
+                        for step in &report.steps {
+                            let (icon, color) = match step.level {
+                                CheckLevel::Pass => ("✔", Color32::from_rgb(34, 160, 80)),
+                                CheckLevel::Warning => ("⚠", Color32::from_rgb(230, 150, 0)),
+                                CheckLevel::Error => ("✖", Color32::from_rgb(220, 38, 38)),
+                            };
+                            ui.label(RichText::new(icon).color(color).strong());
+                            ui.label(RichText::new(&step.title).strong());
+                            ui.label(&step.detail);
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+
+    if !open {
+        app.dry_run_report = None;
+    }
+}
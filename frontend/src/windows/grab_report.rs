@@ -0,0 +1,134 @@
+use crate::app::Myapp;
+use eframe::egui::{self, Color32, RichText};
+use common::time_format::format_local_millis;
+
+//相对任务开始的时间
+fn format_offset(started_at: i64, timestamp: Option<i64>) -> String {
+    match timestamp {
+        Some(t) => format!("{}（开始后 {:.3} 秒）", format_local_millis(t), (t - started_at) as f64 / 1000.0),
+        None => "无".to_string(),
+    }
+}
+
+//抢票报告：各阶段请求次数、耗时分布、错误码统计和请求时间线
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let report = match &app.grab_report {
+        Some(report) => report,
+        None => return,
+    };
+    let mut open = true;
+
+    egui::Window::new("抢票报告")
+        .open(&mut open)
+        .default_size([620.0, 560.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            let (icon, color) = if report.success {
+                ("✔ 抢票成功", Color32::from_rgb(34, 160, 80))
+            } else {
+                ("✖ 未抢到票", Color32::from_rgb(220, 38, 38))
+            };
+            ui.label(RichText::new(icon).color(color).strong().size(16.0));
+            ui.label(&report.message);
+            ui.add_space(4.0);
+            ui.label(format!("账号：{}    项目：{} ({})", report.uid, report.project_name, report.project_id));
+            ui.label(format!(
+                "开始：{}    用时：{:.1} 秒    请求：{} 次",
+                format_local_millis(report.started_at),
+                report.duration_ms() as f64 / 1000.0,
+                report.total_attempts()
+            ));
+            ui.label(format!("首次拿到token：{}", format_offset(report.started_at, report.first_token_at)));
+            ui.label(format!("下单成功：{}", format_offset(report.started_at, report.order_at)));
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(380.0).show(ui, |ui| {
+                ui.heading("各阶段");
+                egui::Grid::new("grab_report_phases")
+                    .num_columns(7)
+                    .spacing([12.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for title in ["阶段", "请求", "失败", "P50", "P90", "P99", "最大"] {
+                            ui.label(RichText::new(title).strong());
+                        }
+                        ui.end_row();
+                        for phase in &report.phases {
+                            ui.label(&phase.phase);
+                            ui.label(phase.attempts.to_string());
+                            ui.label(phase.failures.to_string());
+                            ui.label(format!("{}ms", phase.p50_ms));
+                            ui.label(format!("{}ms", phase.p90_ms));
+                            ui.label(format!("{}ms", phase.p99_ms));
+                            ui.label(format!("{}ms", phase.max_ms));
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(8.0);
+
+                ui.heading("错误码");
+                if report.errno_histogram.is_empty() {
+                    ui.label(RichText::new("没有失败的请求").color(Color32::GRAY));
+                }
+                egui::Grid::new("grab_report_errno")
+                    .num_columns(5)
+                    .spacing([12.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for item in &report.errno_histogram {
+                            ui.label(&item.phase);
+                            ui.label(item.errno.to_string());
+                            ui.label(format!("{}次", item.count));
+                            ui.label(&item.category);
+                            ui.label(&item.explanation);
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(8.0);
+
+                egui::CollapsingHeader::new(format!("请求时间线（{}条）", report.timeline.len()))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::Grid::new("grab_report_timeline")
+                            .num_columns(4)
+                            .spacing([12.0, 2.0])
+                            .show(ui, |ui| {
+                                for attempt in &report.timeline {
+                                    ui.label(format_local_millis(attempt.at));
+                                    ui.label(&attempt.phase);
+                                    ui.label(format!("{}ms", attempt.latency_ms));
+                                    if attempt.errno == 0 {
+                                        ui.label(RichText::new("成功").color(Color32::from_rgb(34, 160, 80)));
+                                    } else {
+                                        ui.label(RichText::new(attempt.errno.to_string()).color(Color32::from_rgb(220, 38, 38)));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        if report.timeline_dropped > 0 {
+                            ui.label(RichText::new(format!("另有{}条超出上限未记录", report.timeline_dropped)).color(Color32::GRAY));
+                        }
+                    });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("保存为JSON").clicked() {
+                    match report.save() {
+                        Ok(path) => log::info!("抢票报告已保存到 {}", path.display()),
+                        Err(e) => log::error!("{}", e),
+                    }
+                }
+                if ui.button("复制JSON").clicked() {
+                    match report.to_json() {
+                        Ok(json) => ui.output_mut(|o| o.copied_text = json),
+                        Err(e) => log::error!("{}", e),
+                    }
+                }
+            });
+        });
+
+    if !open {
+        app.grab_report = None;
+    }
+}
@@ -253,6 +253,20 @@ fn ui_sms_login(ui: &mut egui::Ui, app: &mut Myapp) {
     ui.vertical_centered(|ui|{
         //phone的要传入app，参数从里面获得
         phone_input(ui, "手机号", app, "请输入手机号", true);
+        //从联系人中选择手机号
+        let contacts = common::contact::all_contacts(&app.account_manager.accounts, &app.config.shared_contacts);
+        if !contacts.is_empty() {
+            egui::ComboBox::from_id_source("sms_login_contact")
+                .selected_text("从联系人选择")
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for contact in &contacts {
+                        if ui.selectable_label(app.login_input.phone == contact.phone, contact.label()).clicked() {
+                            app.login_input.phone = contact.phone.clone();
+                        }
+                    }
+                });
+        }
         app.show_log_window = true;
         ui.add_space(10.0);
         common_input(ui, "验证码", &mut app.login_input.sms_code, "请输入验证码", true);
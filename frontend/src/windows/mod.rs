@@ -6,4 +6,15 @@ pub mod show_orderlist;
 pub mod screen_info;
 pub mod confirm_ticket;
 pub mod confirm_ticket2;
-pub mod show_qrcode;
\ No newline at end of file
+pub mod show_qrcode;
+pub mod settings_import;
+pub mod project_detail;
+pub mod calendar;
+pub mod address;
+pub mod dry_run;
+pub mod account_transfer;
+pub mod account_meta;
+pub mod contacts;
+pub mod api_drift;
+pub mod grab_report;
+pub mod config_error;
@@ -0,0 +1,178 @@
+use crate::app::Myapp;
+use eframe::egui::{self, RichText};
+use common::project_detail::{base_info_items, desc_sections, id_bind_text, price_matrix, purchase_limits};
+use common::time_format::{format_dual, format_with_countdown, local_differs, local_zone_label};
+
+//项目详情：场馆、实名制、限购、退票/入场须知、场次时间表、票价矩阵
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let info = match app.project_detail_info.clone() {
+        Some(info) => info,
+        None => return,
+    };
+    let mut window_open = true;
+    let base_info = base_info_items(&info);
+    let limits = purchase_limits(&info);
+    let sections = desc_sections(&info);
+    let matrix = price_matrix(&info);
+
+    egui::Window::new(format!("项目详情 - {}", info.name))
+        .id(egui::Id::new("project_detail_window"))
+        .open(&mut window_open)
+        .default_size([760.0, 600.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading(&info.name);
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(format!("项目ID: {}", info.id));
+                    ui.label(format!("状态: {}", info.sale_flag));
+                    if info.hot_project {
+                        ui.label(RichText::new("热门项目").color(egui::Color32::from_rgb(255, 120, 50)));
+                    }
+                    if info.pick_seat == 1 {
+                        ui.label(RichText::new("选座").color(egui::Color32::from_rgb(0, 161, 214)));
+                    }
+                });
+                let now = chrono::Utc::now().timestamp();
+                ui.label(format!("开售时间: {}", format_with_countdown(info.sale_begin, now)));
+                ui.label(format!("截止时间: {}", format_dual(info.sale_end)));
+                if local_differs(now) {
+                    ui.label(
+                        RichText::new(format!("时间以北京时间为准，本地时区为 {}", local_zone_label()))
+                            .color(egui::Color32::GRAY),
+                    );
+                }
+                ui.add_space(8.0);
+
+                // 场馆
+                ui.separator();
+                ui.heading("场馆");
+                match &info.venue_info {
+                    Some(venue) if !venue.name.is_empty() => {
+                        ui.label(RichText::new(&venue.name).strong());
+                        if !venue.address_detail.is_empty() {
+                            ui.horizontal(|ui| {
+                                ui.label(&venue.address_detail);
+                                if ui.small_button("复制地址").clicked() {
+                                    ui.output_mut(|o| o.copied_text = venue.address_detail.clone());
+                                }
+                            });
+                        }
+                    }
+                    _ => {
+                        ui.label(RichText::new("未获取到场馆信息").color(egui::Color32::GRAY));
+                    }
+                }
+
+                // 购票规则
+                ui.separator();
+                ui.heading("购票规则");
+                egui::Grid::new("project_detail_rules")
+                    .num_columns(2)
+                    .striped(true)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("实名制").strong());
+                        ui.label(id_bind_text(info.id_bind));
+                        ui.end_row();
+
+                        ui.label(RichText::new("限购").strong());
+                        if limits.is_empty() {
+                            ui.label(RichText::new("未注明").color(egui::Color32::GRAY));
+                        } else {
+                            ui.label(limits.join("\n"));
+                        }
+                        ui.end_row();
+
+                        if info.express_fee > 0 {
+                            ui.label(RichText::new("快递费").strong());
+                            ui.label(format!("¥{:.2}", info.express_fee as f64 / 100.0));
+                            ui.end_row();
+                        }
+
+                        // 实名认证、退票、入场等规则以接口返回为准
+                        for item in &base_info {
+                            ui.label(RichText::new(&item.title).strong());
+                            ui.add(egui::Label::new(&item.content).wrap(true));
+                            ui.end_row();
+                        }
+                    });
+
+                // 场次时间表
+                ui.separator();
+                ui.heading("场次时间表");
+                egui::Grid::new("project_detail_screens")
+                    .num_columns(5)
+                    .striped(true)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        for title in ["场次", "开始时间", "开售时间", "截止时间", "状态"] {
+                            ui.label(RichText::new(title).strong());
+                        }
+                        ui.end_row();
+                        for screen in &info.screen_list {
+                            ui.label(&screen.name);
+                            ui.label(format_dual(screen.start_time as i64));
+                            ui.label(format_dual(screen.sale_start as i64));
+                            ui.label(format_dual(screen.sale_end as i64));
+                            ui.label(&screen.sale_flag.display_name);
+                            ui.end_row();
+                        }
+                    });
+
+                // 票价矩阵
+                ui.separator();
+                ui.heading("票价");
+                egui::ScrollArea::horizontal().id_source("project_detail_prices").show(ui, |ui| {
+                    egui::Grid::new("project_detail_price_matrix")
+                        .num_columns(matrix.screen_names.len() + 1)
+                        .striped(true)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label(RichText::new("票种 \\ 场次").strong());
+                            for screen in &matrix.screen_names {
+                                ui.label(RichText::new(screen).strong());
+                            }
+                            ui.end_row();
+                            for (name, row) in matrix.ticket_names.iter().zip(&matrix.cells) {
+                                ui.label(name);
+                                for cell in row {
+                                    match cell {
+                                        Some(cell) => {
+                                            let color = if cell.clickable {
+                                                egui::Color32::from_rgb(245, 108, 108)
+                                            } else {
+                                                egui::Color32::GRAY
+                                            };
+                                            ui.label(
+                                                RichText::new(format!("¥{:.2}\n{}", cell.price as f64 / 100.0, cell.status))
+                                                    .color(color),
+                                            );
+                                        }
+                                        None => {
+                                            ui.label(RichText::new("-").color(egui::Color32::GRAY));
+                                        }
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                // 其他说明
+                for (idx, section) in sections.iter().enumerate() {
+                    ui.separator();
+                    egui::CollapsingHeader::new(RichText::new(&section.title).strong())
+                        .id_source(("project_detail_section", idx))
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            ui.add(egui::Label::new(&section.text).wrap(true));
+                        });
+                }
+            });
+        });
+
+    if !window_open {
+        app.project_detail_info = None;
+    }
+}
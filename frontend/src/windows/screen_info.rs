@@ -1,7 +1,6 @@
 use crate::app::Myapp;
-use chrono::TimeZone;
 use eframe::egui;
-use serde_json::Value;
+use common::time_format::{format_dual, format_with_countdown};
 
 pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: i64) {
     let bilibili_ticket = app
@@ -38,11 +37,17 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: i64) {
                 ui.heading(&ticket_data.name);
                 ui.add_space(5.0);
 
-                /* // 活动时间和地点
+                // 活动地点
                 if let Some(venue_info) = &ticket_data.venue_info {
-                    ui.label(format!("{} | {}", ticket_data.project_label, venue_info.name));
-                } */
+                    if !venue_info.name.is_empty() {
+                        ui.label(&venue_info.name);
+                    }
+                }
                 ui.label(format!("状态: {}", ticket_data.sale_flag));
+                ui.add_space(5.0);
+                if ui.button("📋 查看项目详情与购票规则").clicked() {
+                    app.project_detail_info = Some(ticket_data.clone());
+                }
                 ui.add_space(10.0);
             });
 
@@ -95,9 +100,10 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: i64) {
                         .outer_margin(10.0)
                         .show(ui, |ui| {
                             // 场次基本信息
-                            ui.label(format!("开始时间: {}", format_timestamp(selected_screen.start_time)));
-                            ui.label(format!("售票开始: {}", format_timestamp(selected_screen.sale_start)));
-                            ui.label(format!("售票结束: {}", format_timestamp(selected_screen.sale_end)));
+                            let now = chrono::Utc::now().timestamp();
+                            ui.label(format!("开始时间: {}", format_with_countdown(selected_screen.start_time as i64, now)));
+                            ui.label(format!("售票开始: {}", format_with_countdown(selected_screen.sale_start as i64, now)));
+                            ui.label(format!("售票结束: {}", format_dual(selected_screen.sale_end as i64)));
                             ui.label(format!("售票状态: {}", selected_screen.sale_flag.display_name));
 
                             ui.add_space(8.0);
@@ -170,37 +176,6 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: i64) {
                 }
             }
 
-            // 项目详细信息区
-            ui.add_space(10.0);
-            ui.collapsing("查看详细信息", |ui| {
-                ui.label("基本信息:");
-                ui.indent("basic_info", |ui| {
-                    ui.label(format!("项目ID: {}", ticket_data.id));
-
-                    // 检查performance_desc是否存在，并显示基础信息
-                    if let Some(desc) = &ticket_data.performance_desc {
-                        for item in &desc.list {
-                            if item.module == "base_info" {
-                                if let Some(array) = item.details.as_array() {
-                                    for info_item in array {
-                                        if let (Some(title), Some(content)) = (
-                                            info_item.get("title").and_then(Value::as_str),
-                                            info_item.get("content").and_then(Value::as_str)
-                                        ) {
-                                            ui.horizontal(|ui| {
-                                                ui.label(egui::RichText::new(format!("{}:", title)).strong());
-                                                ui.label(content);
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-
-                });
-            });
         });
 
         // 底部按钮
@@ -216,22 +191,3 @@ pub fn show(app: &mut Myapp, ctx: &egui::Context, uid: i64) {
         bilibili_ticket.project_info = None;
     }
 }
-
-// 将时间戳转换为可读时间
-// 将时间戳转换为可读时间 (接受usize类型)
-fn format_timestamp(timestamp: usize) -> String {
-    if timestamp <= 0 {
-        return "未设置".to_string();
-    }
-
-    // 安全地将usize转为i64
-    let timestamp_i64 = match i64::try_from(timestamp) {
-        Ok(ts) => ts,
-        Err(_) => return "时间戳溢出".to_string(), // 处理极端情况
-    };
-
-    match chrono::Local.timestamp_opt(timestamp_i64, 0) {
-        chrono::LocalResult::Single(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
-        _ => "无效时间".to_string(),
-    }
-}
@@ -0,0 +1,110 @@
+use crate::app::Myapp;
+use eframe::egui::{self, RichText};
+use common::config::save_config;
+use common::settings_transfer::{apply_import, ConflictResolution};
+
+//导入设置预览：确认改动和账号冲突后再写入配置
+pub fn show(app: &mut Myapp, ctx: &egui::Context) {
+    let preview = match app.settings_import_preview.clone() {
+        Some(preview) => preview,
+        None => return,
+    };
+    let mut window_open = true;
+    let mut confirmed = false;
+    let mut cancelled = false;
+
+    egui::Window::new("导入设置预览")
+        .open(&mut window_open)
+        .default_size([700.0, 500.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                ui.heading("设置改动");
+                if preview.changes.is_empty() {
+                    ui.label(RichText::new("没有需要修改的设置").color(egui::Color32::GRAY));
+                }
+                egui::Grid::new("settings_import_changes")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for change in &preview.changes {
+                            ui.monospace(&change.path);
+                            ui.label(RichText::new(&change.old).color(egui::Color32::from_rgb(200, 80, 0)));
+                            ui.label(RichText::new(&change.new).color(egui::Color32::from_rgb(0, 150, 0)));
+                            ui.end_row();
+                        }
+                    });
+
+                if !preview.new_accounts.is_empty() {
+                    ui.separator();
+                    ui.heading("新增账号");
+                    for (uid, name) in &preview.new_accounts {
+                        ui.label(format!("{} (UID: {})", name, uid));
+                    }
+                }
+
+                if !preview.conflicts.is_empty() {
+                    ui.separator();
+                    ui.heading("已存在的账号");
+                    for conflict in &preview.conflicts {
+                        let resolution = app
+                            .settings_import_resolutions
+                            .entry(conflict.uid)
+                            .or_insert(ConflictResolution::KeepExisting);
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} (UID: {})", conflict.name, conflict.uid));
+                            ui.radio_value(resolution, ConflictResolution::KeepExisting, "保留现有");
+                            ui.radio_value(resolution, ConflictResolution::Overwrite, "使用导入");
+                        });
+                        ui.label(
+                            RichText::new(format!("不同字段: {}", conflict.changed_fields.join(", ")))
+                                .color(egui::Color32::GRAY),
+                        );
+                    }
+                }
+
+                if !preview.skipped.is_empty() {
+                    ui.separator();
+                    ui.heading("已跳过");
+                    for message in &preview.skipped {
+                        ui.label(RichText::new(message).color(egui::Color32::GRAY));
+                    }
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let button = egui::Button::new(
+                    RichText::new("确认导入").size(16.0).color(egui::Color32::WHITE)
+                )
+                .min_size(egui::vec2(100.0, 35.0))
+                .fill(egui::Color32::from_rgb(102, 204, 255))
+                .rounding(15.0);
+                if ui.add(button).clicked() {
+                    confirmed = true;
+                }
+                if ui.button("取消").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        match apply_import(&mut app.config, &preview, &app.settings_import_resolutions) {
+            Ok(_) => {
+                app.push_config = app.config.push_config.clone();
+                app.custom_config = app.config.custom_config.clone();
+                app.sync_accounts_from_config();
+                match save_config(&mut app.config, None, None, None) {
+                    Ok(_) => log::info!("设置导入成功"),
+                    Err(e) => log::error!("设置已导入，但保存失败: {}", e),
+                }
+            }
+            Err(e) => log::error!("导入设置失败: {}", e),
+        }
+    }
+    if confirmed || cancelled || !window_open {
+        app.settings_import_preview = None;
+        app.settings_import_resolutions.clear();
+    }
+}
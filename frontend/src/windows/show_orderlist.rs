@@ -1,9 +1,11 @@
 use crate::app::{Myapp, OrderData};
 use eframe::egui::{self, RichText};
-use egui::{Image, TextureHandle};
-use std::sync::Arc;
+use egui::Image;
 use serde::{Deserialize, Serialize};
-use common::{cookie_manager::CookieManager, utils::load_texture_from_url};
+use common::calendar::{order_entry, to_ics_events, CalendarEntry};
+use common::ics::to_ics;
+use common::utils::open_with_system;
+use common::time_format::{format_dual, format_with_countdown, parse_beijing_time};
 
 pub fn show(
     app: &mut Myapp,
@@ -16,6 +18,20 @@ pub fn show(
         Some(data) => {app.is_loading = false; data.clone()},
         None => {app.is_loading = true; return;},
     };
+    // 订单图片走图片缓存，使用第一个账号的client请求
+    let image_client = app.account_manager.accounts.first().and_then(|account| account.cookie_manager.clone());
+    let venues = app.known_venues();
+    let now = chrono::Utc::now().timestamp();
+    // 未开演的订单日程，订单号 -> 日程
+    let upcoming_entries: Vec<(String, CalendarEntry)> = orders_data.data.as_ref()
+        .map(|data| data.data.list.iter()
+            .filter_map(|order| {
+                let venue = venues.get(&order.item_id.to_string()).map(String::as_str).unwrap_or("");
+                order_entry(order, venue).map(|entry| (order.order_id.clone(), entry))
+            })
+            .filter(|(_, entry)| entry.time >= now)
+            .collect())
+        .unwrap_or_default();
 
 
     // 显示窗口和订单数据
@@ -32,6 +48,14 @@ pub fn show(
                     .strong()
                 );
             });
+            ui.horizontal(|ui| {
+                let button = egui::Button::new("📅 导出全部即将开始的演出");
+                if ui.add_enabled(!upcoming_entries.is_empty(), button).clicked() {
+                    let entries: Vec<CalendarEntry> = upcoming_entries.iter().map(|(_, entry)| entry.clone()).collect();
+                    export_orders_ics(&entries, &format!("orders_{}.ics", orders_data.account_id));
+                }
+                ui.label(RichText::new(format!("{}场未开演", upcoming_entries.len())).color(egui::Color32::GRAY));
+            });
 
             // 添加滚动区域
             egui::ScrollArea::vertical().show(ui, |ui| {
@@ -61,21 +85,12 @@ pub fn show(
                                         order.img.url.clone()
                                     };
 
-                                    // 图片加载逻辑
-                                    ui.add_sized(image_size, |ui: &mut egui::Ui| {
-                                        if let Some(texture) = get_image_texture(ctx, &image_url) {
-                                            ui.centered_and_justified(|ui| {
-                                                ui.add(Image::new(&texture).fit_to_exact_size(image_size))
-                                            }).inner
-                                        } else {
-                                            let inner_response = ui.centered_and_justified(|ui| {
-                                                ui.label("图片加载中...")
-                                            });
-                                            // log::debug!("开始加载图片: {}", image_url);
-                                            request_image_async(ctx.clone(), app,image_url);
-                                            inner_response.inner
-                                        }
-                                    });
+                                    // 图片加载逻辑，未加载完成时显示占位图
+                                    let thumb_url = format!("{}@74w_74h.jpeg", image_url);
+                                    let texture = app.image_cache
+                                        .get(ctx, image_client.clone(), &thumb_url)
+                                        .unwrap_or_else(|| app.image_cache.placeholder(ctx));
+                                    ui.add_sized(image_size, Image::new(&texture).fit_to_exact_size(image_size));
 
                                     ui.add_space(12.0);
 
@@ -114,9 +129,19 @@ pub fn show(
 
                                         ui.horizontal(|ui| {
                                             ui.label(RichText::new("下单时间:").color(egui::Color32::GRAY));
-                                            ui.label(&order.ctime);
+                                            ui.label(order_time_text(&order.ctime));
                                         });
 
+                                        if let Some(entry) = upcoming_entries.iter().find(|(order_id, _)| *order_id == order.order_id).map(|(_, entry)| entry) {
+                                            ui.horizontal(|ui| {
+                                                ui.label(RichText::new("演出时间:").color(egui::Color32::GRAY));
+                                                ui.label(format_with_countdown(entry.time, now));
+                                                if ui.small_button("📅 添加到日历").clicked() {
+                                                    export_orders_ics(std::slice::from_ref(entry), &format!("order_{}.ics", order.order_id));
+                                                }
+                                            });
+                                        }
+
                                         ui.horizontal(|ui| {
                                             ui.label(RichText::new("价格:").color(egui::Color32::GRAY));
                                             // 将分转换为元并格式化为价格
@@ -196,60 +221,24 @@ pub fn show(
     }
 }
 
-// 辅助函数：从缓存获取图片纹理
-fn get_image_texture(ctx: &egui::Context, url: &str) -> Option<TextureHandle> {
-
-    ctx.memory(|mem| {
-        // log::debug!("{:?}", mem.data);
-        mem.data.get_temp::<TextureHandle>(egui::Id::new(url))
-    })
+//订单接口的时间为北京时间字符串，解析失败时原样显示
+fn order_time_text(text: &str) -> String {
+    parse_beijing_time(text).map(format_dual).unwrap_or_else(|| text.to_string())
 }
 
-// 辅助函数：异步请求图片
-fn request_image_async(ctx: egui::Context,app:&Myapp,url: String) {
-    if ctx.memory(|mem| mem.data.get_temp::<TextureHandle>(egui::Id::new(&url)).is_some()){
-        log::error!("图片已存在: {}", url);
-        return;
-    }
-    // 避免重复请求
-    if ctx.memory(|mem| mem.data.get_temp::<bool>(egui::Id::new(format!("loading_{}", url))).is_some()) {
-        return;
-    }
-
-    // 标记为正在加载
-    log::debug!("<正在加载图片>: {}", url);
-    ctx.memory_mut(|mem| mem.data.insert_temp(egui::Id::new(format!("loading_{}", url)), true));
-    
-    // 启动异步加载线程
-    let app_client =match  app.account_manager.accounts[0].cookie_manager.clone(){
-        Some(client) => client,
-        None => {
-            log::error!("cookie_manager不存在");
-            let rt = tokio::runtime::Runtime::new().unwrap();
-           
-            Arc::new(rt.block_on(CookieManager::new("", None, 0)))
-        }
-    };
-    let app_ua = app.default_ua.clone();
-    
-        // 这里应该实现实际的图片加载逻辑
-        // 示例：
-    std::thread::spawn(move || {
-        if let Some(texture)=load_texture_from_url(&ctx, app_client, &(url.clone()+"@74w_74h.jpeg"),  &url){
-            ctx.memory_mut(|mem| {
-                log::debug!("加载图片成功: {}", url);
-                mem.data.insert_temp(egui::Id::new(&url), texture);
-                mem.data.remove::<bool>(egui::Id::new(format!("loading_{}", url)));
-            });
-            ctx.request_repaint();
-        }else{
-            ctx.memory_mut(|mem| {
-                log::warn!("加载图片失败_ui,retrying: {}", url);
-                mem.data.remove::<bool>(egui::Id::new(format!("loading_{}", url)));
-            });
+//写入ics文件并用系统默认日历程序打开
+fn export_orders_ics(entries: &[CalendarEntry], file_name: &str) {
+    let dir = common::data_dir::data_dir().join("calendar");
+    let path = dir.join(file_name);
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, to_ics(&to_ics_events(entries, None))));
+    match result {
+        Ok(_) => {
+            log::info!("已导出{}个演出日程到 {}", entries.len(), path.display());
+            if let Err(e) = open_with_system(&path) {
+                log::warn!("{}，请手动导入该文件", e);
+            }
         }
-
-        });
-
-
+        Err(e) => log::error!("导出日历失败: {}", e),
+    }
 }